@@ -186,7 +186,7 @@ impl<'a> ArithmeticOnlyChecker<'a> {
             }
             Append | Concat | AsMaxLen | ContractOf | PrincipalOf | ListCons | Print
             | AsContract | ElementAt | ElementAtAlias | IndexOf | IndexOfAlias | Map | Filter
-            | Fold | Slice | ReplaceAt => {
+            | Fold | Slice | ReplaceAt | Sort | AssertEq | UnwrapOrElse | MapTry => {
                 return Err(Error::FunctionNotPermitted(function));
             }
             BuffToIntLe | BuffToUIntLe | BuffToIntBe | BuffToUIntBe => {
@@ -195,26 +195,26 @@ impl<'a> ArithmeticOnlyChecker<'a> {
             IsStandard | PrincipalDestruct | PrincipalConstruct => {
                 return Err(Error::FunctionNotPermitted(function));
             }
-            IntToAscii | IntToUtf8 | StringToInt | StringToUInt => {
+            IntToAscii | IntToUtf8 | StringToInt | StringToUInt | ToAscii | StringToBuff => {
                 return Err(Error::FunctionNotPermitted(function));
             }
             Sha512 | Sha512Trunc256 | Secp256k1Recover | Secp256k1Verify | Hash160 | Sha256
             | Keccak256 => {
                 return Err(Error::FunctionNotPermitted(function));
             }
-            Add | Subtract | Divide | Multiply | CmpGeq | CmpLeq | CmpLess | CmpGreater
-            | Modulo | Power | Sqrti | Log2 | BitwiseXor | And | Or | Not | Equals | If
-            | ConsSome | ConsOkay | ConsError | DefaultTo | UnwrapRet | UnwrapErrRet | IsOkay
-            | IsNone | Asserts | Unwrap | UnwrapErr | IsErr | IsSome | TryRet | ToUInt | ToInt
-            | Len | Begin | TupleMerge | BitwiseOr | BitwiseAnd | BitwiseXor2 | BitwiseNot
-            | BitwiseLShift | BitwiseRShift => {
+            Add | Subtract | Divide | Multiply | MulDiv | CmpGeq | CmpLeq | CmpLess
+            | CmpGreater | Modulo | Power | Sqrti | Log2 | BitwiseXor | And | Or | Not | Equals
+            | If | ConsSome | ConsOkay | ConsError | DefaultTo | UnwrapRet | UnwrapErrRet
+            | IsOkay | IsNone | Asserts | Unwrap | UnwrapErr | IsErr | IsSome | TryRet | ToUInt
+            | ToInt | Len | Begin | TupleMerge | BitwiseOr | BitwiseAnd | BitwiseXor2
+            | BitwiseNot | BitwiseLShift | BitwiseRShift | BitCount | LeadingZeros => {
                 // Check all arguments.
                 self.check_all(args)
             }
             // we need to treat all the remaining functions specially, because these
             //   do not eval all of their arguments (rather, one or more of their arguments
             //   is a name)
-            TupleGet => {
+            TupleGet | TupleGetOptional => {
                 // these functions use a name in the first argument
                 check_argument_count(2, args).map_err(|_| Error::UnexpectedContractStructure)?;
                 self.check_all(&args[1..])
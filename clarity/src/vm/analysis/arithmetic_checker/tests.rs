@@ -475,6 +475,31 @@ fn test_functions_clarity2() {
     }
 }
 
+#[test]
+fn test_functions_clarity3() {
+    let tests = [
+        (
+            "(define-private (foo (a (list 3 uint)))
+              (sort a))",
+            Err(FunctionNotPermitted(NativeFunctions::Sort)),
+        ),
+        (
+            "(define-private (zero) 0)
+          (unwrap-or-else (some 1) zero)",
+            Err(FunctionNotPermitted(NativeFunctions::UnwrapOrElse)),
+        ),
+    ];
+
+    for (contract, result) in tests.iter() {
+        assert_eq!(
+            arithmetic_check(contract, ClarityVersion::Clarity3, StacksEpochId::Epoch21),
+            result.clone(),
+            "Check contract:\n {}",
+            contract
+        );
+    }
+}
+
 #[test]
 fn test_functions_contract() {
     let good_tests = [
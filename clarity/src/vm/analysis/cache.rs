@@ -0,0 +1,141 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::{BTreeMap, HashMap};
+
+use stacks_common::types::StacksEpochId;
+use stacks_common::util::hash::Sha512Trunc256Sum;
+
+use crate::vm::analysis::{run_analysis, AnalysisDatabase, CheckError, ContractAnalysis};
+use crate::vm::contracts::referenced_contracts;
+use crate::vm::costs::LimitedCostTracker;
+use crate::vm::database::ClaritySerializable;
+use crate::vm::representations::SymbolicExpression;
+use crate::vm::types::QualifiedContractIdentifier;
+use crate::vm::ClarityVersion;
+
+/// Everything the analysis of a contract depends on: its source, the epoch and Clarity
+///  version it is analyzed at, and the stored analysis of each contract it references.
+///  A dependent's analysis only reads its dependencies' stored analyses (their
+///  interfaces, trait definitions and implemented traits), so hashing those records
+///  is enough to notice any change that could affect it, including changes that
+///  `ContractInterface` does not describe.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalysisCacheKey {
+    source_hash: Sha512Trunc256Sum,
+    epoch: StacksEpochId,
+    clarity_version: ClarityVersion,
+    /// The hash of each referenced contract's stored analysis, or `None` if it has not
+    ///  been analyzed yet.
+    dependencies: BTreeMap<QualifiedContractIdentifier, Option<Sha512Trunc256Sum>>,
+}
+
+impl AnalysisCacheKey {
+    pub fn new(
+        contract_identifier: &QualifiedContractIdentifier,
+        contract_source: &str,
+        expressions: &[SymbolicExpression],
+        analysis_db: &mut AnalysisDatabase,
+        epoch: StacksEpochId,
+        clarity_version: ClarityVersion,
+    ) -> AnalysisCacheKey {
+        // only reads, so the context is rolled back rather than committed
+        analysis_db.begin();
+        let dependencies = referenced_contracts(expressions)
+            .into_iter()
+            .filter(|dependency| dependency != contract_identifier)
+            .map(|dependency| {
+                let analysis_hash = analysis_db
+                    .load_contract_non_canonical(&dependency)
+                    .map(|analysis| Sha512Trunc256Sum::from_data(analysis.serialize().as_bytes()));
+                (dependency, analysis_hash)
+            })
+            .collect();
+        analysis_db.roll_back();
+        AnalysisCacheKey {
+            source_hash: Sha512Trunc256Sum::from_data(contract_source.as_bytes()),
+            epoch,
+            clarity_version,
+            dependencies,
+        }
+    }
+}
+
+/// A cache of successful contract analyses, for author tooling that re-analyzes a set of
+///  contracts as they are edited. An entry is reused only while its `AnalysisCacheKey`
+///  is unchanged, so editing a contract re-analyzes it, and changing a contract's
+///  analysis re-analyzes the contracts that reference it, but not the others. Failed
+///  analyses are not cached. Not used in production
+#[derive(Default)]
+pub struct AnalysisCache {
+    entries: HashMap<QualifiedContractIdentifier, (AnalysisCacheKey, ContractAnalysis)>,
+}
+
+impl AnalysisCache {
+    pub fn new() -> AnalysisCache {
+        AnalysisCache::default()
+    }
+
+    /// Analyze a contract like `run_analysis` (without saving it), at the epoch and
+    ///  Clarity version of `key`, unless the cached analysis of `contract_identifier` was
+    ///  made under the same key. Returns the analysis, and whether it came from the cache.
+    ///  A cached analysis charges no cost: `cost_tracker` is handed back in the returned
+    ///  analysis unchanged.
+    pub fn run_analysis(
+        &mut self,
+        contract_identifier: &QualifiedContractIdentifier,
+        key: AnalysisCacheKey,
+        expressions: &mut [SymbolicExpression],
+        analysis_db: &mut AnalysisDatabase,
+        cost_tracker: LimitedCostTracker,
+    ) -> Result<(ContractAnalysis, bool), (CheckError, LimitedCostTracker)> {
+        if let Some((cached_key, cached_analysis)) = self.entries.get(contract_identifier) {
+            if *cached_key == key {
+                let mut contract_analysis = cached_analysis.clone();
+                contract_analysis.cost_track = Some(cost_tracker);
+                return Ok((contract_analysis, true));
+            }
+        }
+
+        let contract_analysis = run_analysis(
+            contract_identifier,
+            expressions,
+            analysis_db,
+            false,
+            cost_tracker,
+            key.epoch,
+            key.clarity_version,
+        )?;
+        let mut cached_analysis = contract_analysis.clone();
+        cached_analysis.cost_track = None;
+        self.entries
+            .insert(contract_identifier.clone(), (key, cached_analysis));
+        Ok((contract_analysis, false))
+    }
+
+    /// Drop the cached analysis of `contract_identifier`, if any.
+    pub fn invalidate(&mut self, contract_identifier: &QualifiedContractIdentifier) {
+        self.entries.remove(contract_identifier);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
@@ -51,6 +51,7 @@ pub fn build_contract_interface(contract_analysis: &ContractAnalysis) -> Contrac
         cost_track: _,
         contract_interface: _,
         is_cost_contract_eligible: _,
+        warnings: _,
     } = contract_analysis;
 
     contract_interface
@@ -291,6 +292,31 @@ impl ContractInterfaceFunction {
     }
 }
 
+/// A function of a `ContractRuntimeInterface`. Unlike `ContractInterfaceFunction`, it
+///  has no outputs: the runtime definitions do not record the result types that
+///  analysis infers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractRuntimeInterfaceFunction {
+    pub name: String,
+    pub access: ContractInterfaceFunctionAccess,
+    pub args: Vec<ContractInterfaceFunctionArg>,
+}
+
+impl ContractRuntimeInterfaceFunction {
+    pub fn from_map(
+        map: &BTreeMap<ClarityName, Vec<FunctionArg>>,
+        access: ContractInterfaceFunctionAccess,
+    ) -> Vec<ContractRuntimeInterfaceFunction> {
+        map.iter()
+            .map(|(name, args)| ContractRuntimeInterfaceFunction {
+                name: name.clone().into(),
+                access: access.to_owned(),
+                args: ContractInterfaceFunctionArg::from_function_args(args),
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ContractInterfaceVariableAccess {
     constant,
@@ -393,6 +419,37 @@ impl ContractInterface {
     }
 }
 
+/// The interface of a deployed contract, built from its runtime definitions by
+///  `ContractContext::get_interface`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractRuntimeInterface {
+    pub functions: Vec<ContractRuntimeInterfaceFunction>,
+    pub variables: Vec<ContractInterfaceVariable>,
+    pub maps: Vec<ContractInterfaceMap>,
+    pub fungible_tokens: Vec<ContractInterfaceFungibleTokens>,
+    pub non_fungible_tokens: Vec<ContractInterfaceNonFungibleTokens>,
+    pub epoch: StacksEpochId,
+    pub clarity_version: ClarityVersion,
+}
+
+impl ContractRuntimeInterface {
+    pub fn new(epoch: StacksEpochId, clarity_version: ClarityVersion) -> Self {
+        Self {
+            functions: Vec::new(),
+            variables: Vec::new(),
+            maps: Vec::new(),
+            fungible_tokens: Vec::new(),
+            non_fungible_tokens: Vec::new(),
+            epoch,
+            clarity_version,
+        }
+    }
+
+    pub fn serialize(&self) -> String {
+        serde_json::to_string(self).expect("Failed to serialize contract interface")
+    }
+}
+
 #[test]
 fn test_string_rename_ascii() {
     let arg = ContractInterfaceFunctionArg {
@@ -0,0 +1,122 @@
+// Copyright (C) 2013-2020 Blocstack PBC, a public benefit corporation
+// Copyright (C) 2020 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::vm::analysis::errors::{CheckWarning, CheckWarnings};
+use crate::vm::analysis::types::ContractAnalysis;
+use crate::vm::functions::NativeFunctions;
+use crate::vm::representations::SymbolicExpression;
+use crate::vm::representations::SymbolicExpressionType::{Atom, List};
+use crate::vm::variables::NativeVariables;
+use crate::vm::ClarityVersion;
+
+#[cfg(test)]
+mod tests;
+
+///
+/// DeadCodeChecker collects warnings for expressions that can never be
+///  evaluated because an earlier expression in the same body always
+///  short-circuits, e.g. anything after `(asserts! false ...)`.
+///
+/// Unlike the other passes, it never fails the analysis: its findings are
+///  stored in `ContractAnalysis::warnings`.
+///
+pub struct DeadCodeChecker<'a> {
+    clarity_version: &'a ClarityVersion,
+    warnings: Vec<CheckWarning>,
+}
+
+impl<'a> DeadCodeChecker<'a> {
+    pub fn collect_warnings(contract_analysis: &mut ContractAnalysis) {
+        let mut checker = DeadCodeChecker {
+            clarity_version: &contract_analysis.clarity_version,
+            warnings: vec![],
+        };
+        for exp in contract_analysis.expressions.iter() {
+            checker.check_expression(exp);
+        }
+        contract_analysis.warnings = checker.warnings;
+    }
+
+    fn check_expression(&mut self, expression: &SymbolicExpression) {
+        if let List(ref expression) = expression.expr {
+            if let Some(body) = self.sequential_body(expression) {
+                if let Some(position) = body.iter().position(|exp| self.always_exits(exp)) {
+                    if let Some(unreachable) = body.get(position + 1) {
+                        self.warnings.push(CheckWarning::new(
+                            CheckWarnings::UnreachableCode,
+                            unreachable,
+                        ));
+                    }
+                }
+            }
+            for exp in expression.iter() {
+                self.check_expression(exp);
+            }
+        }
+    }
+
+    fn lookup_native(&self, expression: &[SymbolicExpression]) -> Option<NativeFunctions> {
+        let (function_name, _) = expression.split_first()?;
+        let function_name = function_name.match_atom()?;
+        NativeFunctions::lookup_by_name_at_version(function_name, self.clarity_version)
+    }
+
+    /// The expressions of `expression` that are evaluated one after the other,
+    ///  if it is a form with such a body.
+    fn sequential_body<'b>(
+        &self,
+        expression: &'b [SymbolicExpression],
+    ) -> Option<&'b [SymbolicExpression]> {
+        match self.lookup_native(expression)? {
+            NativeFunctions::Begin => Some(&expression[1..]),
+            NativeFunctions::Let => expression.get(2..),
+            _ => None,
+        }
+    }
+
+    fn is_literal_false(&self, expression: &SymbolicExpression) -> bool {
+        match expression.expr {
+            Atom(ref name) => {
+                NativeVariables::lookup_by_name_at_version(name, self.clarity_version)
+                    == Some(NativeVariables::NativeFalse)
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether evaluating `expression` always short-circuits, regardless of
+    ///  the values of any variables.
+    fn always_exits(&self, expression: &SymbolicExpression) -> bool {
+        let expression = match expression.expr {
+            List(ref expression) => expression,
+            _ => return false,
+        };
+        let first_arg = match expression.get(1) {
+            Some(arg) => arg,
+            None => return false,
+        };
+        match self.lookup_native(expression) {
+            // `unwrap!`, `try!` etc. can't be handed a literal `none`: the
+            //  type checker can't determine its type and rejects the contract.
+            Some(NativeFunctions::Asserts) => self.is_literal_false(first_arg),
+            Some(NativeFunctions::Begin) | Some(NativeFunctions::Let) => self
+                .sequential_body(expression)
+                .map(|body| body.iter().any(|exp| self.always_exits(exp)))
+                .unwrap_or(false),
+            _ => false,
+        }
+    }
+}
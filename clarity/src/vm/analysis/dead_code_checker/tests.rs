@@ -0,0 +1,108 @@
+// Copyright (C) 2013-2020 Blocstack PBC, a public benefit corporation
+// Copyright (C) 2020 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::vm::ClarityVersion;
+#[cfg(test)]
+use rstest::rstest;
+#[cfg(test)]
+use rstest_reuse::{self, *};
+use stacks_common::types::StacksEpochId;
+
+use crate::vm::analysis::errors::{CheckWarning, CheckWarnings};
+use crate::vm::analysis::mem_type_check;
+use crate::vm::diagnostic::Level;
+use crate::vm::representations::Span;
+
+#[template]
+#[rstest]
+#[case(ClarityVersion::Clarity1, StacksEpochId::Epoch2_05)]
+#[case(ClarityVersion::Clarity1, StacksEpochId::Epoch21)]
+#[case(ClarityVersion::Clarity2, StacksEpochId::Epoch21)]
+fn test_clarity_versions_dead_code_checker(
+    #[case] version: ClarityVersion,
+    #[case] epoch: StacksEpochId,
+) {
+}
+
+fn dead_code_warnings(
+    contract: &str,
+    version: ClarityVersion,
+    epoch: StacksEpochId,
+) -> Vec<CheckWarning> {
+    mem_type_check(contract, version, epoch)
+        .map_err(|e| format!("{} => {}", contract, e))
+        .unwrap()
+        .1
+        .warnings
+}
+
+#[apply(test_clarity_versions_dead_code_checker)]
+fn test_unreachable_after_short_circuit(
+    #[case] version: ClarityVersion,
+    #[case] epoch: StacksEpochId,
+) {
+    let contract = "(define-public (f)
+  (begin
+    (asserts! false (err u1))
+    (ok u2)))";
+    let warnings = dead_code_warnings(contract, version, epoch);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].warning, CheckWarnings::UnreachableCode);
+    assert_eq!(warnings[0].diagnostic.level, Level::Warning);
+    assert_eq!(
+        warnings[0].diagnostic.spans,
+        vec![Span {
+            start_line: 4,
+            start_column: 5,
+            end_line: 4,
+            end_column: 11
+        }]
+    );
+
+    let diverging = [
+        "(define-public (f) (let ((x u1)) (asserts! false (err u1)) (ok x)))",
+        "(define-read-only (f) (let ((x u1)) (begin (asserts! false u0) x)))",
+        "(define-public (f) (begin (begin (print u0) (asserts! false (err u1))) (ok u2)))",
+        "(begin (asserts! false (err u1)) (print u1))",
+    ];
+    for contract in diverging.iter() {
+        assert_eq!(
+            dead_code_warnings(contract, version, epoch).len(),
+            1,
+            "{}",
+            contract
+        );
+    }
+}
+
+#[apply(test_clarity_versions_dead_code_checker)]
+fn test_no_warning_for_live_code(#[case] version: ClarityVersion, #[case] epoch: StacksEpochId) {
+    let live = [
+        "(define-public (f (x uint)) (begin (asserts! (> x u0) (err u1)) (ok x)))",
+        "(define-public (f) (begin (asserts! true (err u1)) (ok u2)))",
+        "(define-public (f (x (optional uint))) (begin (unwrap! x (err u1)) (ok u2)))",
+        // the short-circuit is the last expression: nothing follows it
+        "(define-read-only (f) (begin (print u1) (asserts! false false)))",
+        "(define-read-only (f) (begin (if true true (asserts! false false)) true))",
+    ];
+    for contract in live.iter() {
+        assert!(
+            dead_code_warnings(contract, version, epoch).is_empty(),
+            "{}",
+            contract
+        );
+    }
+}
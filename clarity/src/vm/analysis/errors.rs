@@ -15,7 +15,8 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::vm::costs::{CostErrors, ExecutionCost};
-use crate::vm::diagnostic::{DiagnosableError, Diagnostic};
+use crate::vm::diagnostic::{DiagnosableError, Diagnostic, Level};
+use crate::vm::errors::ErrorCategory;
 use crate::vm::representations::SymbolicExpression;
 use crate::vm::types::{TraitIdentifier, TupleTypeSignature, TypeSignature, Value};
 use std::error;
@@ -185,6 +186,135 @@ pub enum CheckErrors {
 
     WriteAttemptedInReadOnly,
     AtBlockClosureMustBeReadOnly,
+
+    // contract limits (size, limit)
+    ContractSourceTooLarge(usize, usize),
+    TooManyDefinitions(usize, usize),
+    TooManyASTNodes(usize, usize),
+}
+
+impl CheckErrors {
+    /// A stable numeric code for this kind of error, in the range 1000-1999, for
+    ///  tools that report errors across releases. The payload is not part of the code.
+    pub fn error_code(&self) -> u32 {
+        match self {
+            CheckErrors::CostOverflow => 1000,
+            CheckErrors::CostBalanceExceeded(..) => 1001,
+            CheckErrors::MemoryBalanceExceeded(..) => 1002,
+            CheckErrors::CostComputationFailed(..) => 1003,
+            CheckErrors::ValueTooLarge => 1004,
+            CheckErrors::ValueOutOfBounds => 1005,
+            CheckErrors::TypeSignatureTooDeep => 1006,
+            CheckErrors::ExpectedName => 1007,
+            CheckErrors::BadMatchOptionSyntax(..) => 1008,
+            CheckErrors::BadMatchResponseSyntax(..) => 1009,
+            CheckErrors::BadMatchInput(..) => 1010,
+            CheckErrors::UnknownListConstructionFailure => 1011,
+            CheckErrors::ListTypesMustMatch => 1012,
+            CheckErrors::ConstructedListTooLarge => 1013,
+            CheckErrors::TypeError(..) => 1014,
+            CheckErrors::TypeLiteralError(..) => 1015,
+            CheckErrors::TypeValueError(..) => 1016,
+            CheckErrors::NoSuperType(..) => 1017,
+            CheckErrors::InvalidTypeDescription => 1018,
+            CheckErrors::UnknownTypeName(..) => 1019,
+            CheckErrors::UnionTypeError(..) => 1020,
+            CheckErrors::UnionTypeValueError(..) => 1021,
+            CheckErrors::ExpectedLiteral => 1022,
+            CheckErrors::ExpectedOptionalType(..) => 1023,
+            CheckErrors::ExpectedResponseType(..) => 1024,
+            CheckErrors::ExpectedOptionalOrResponseType(..) => 1025,
+            CheckErrors::ExpectedOptionalValue(..) => 1026,
+            CheckErrors::ExpectedResponseValue(..) => 1027,
+            CheckErrors::ExpectedOptionalOrResponseValue(..) => 1028,
+            CheckErrors::CouldNotDetermineResponseOkType => 1029,
+            CheckErrors::CouldNotDetermineResponseErrType => 1030,
+            CheckErrors::CouldNotDetermineSerializationType => 1031,
+            CheckErrors::UncheckedIntermediaryResponses => 1032,
+            CheckErrors::CouldNotDetermineMatchTypes => 1033,
+            CheckErrors::CouldNotDetermineType => 1034,
+            CheckErrors::TypeAlreadyAnnotatedFailure => 1035,
+            CheckErrors::TypeAnnotationExpectedFailure => 1036,
+            CheckErrors::CheckerImplementationFailure => 1037,
+            CheckErrors::BadTokenName => 1038,
+            CheckErrors::DefineFTBadSignature => 1039,
+            CheckErrors::DefineNFTBadSignature => 1040,
+            CheckErrors::NoSuchNFT(..) => 1041,
+            CheckErrors::NoSuchFT(..) => 1042,
+            CheckErrors::BadTransferSTXArguments => 1043,
+            CheckErrors::BadTransferFTArguments => 1044,
+            CheckErrors::BadTransferNFTArguments => 1045,
+            CheckErrors::BadMintFTArguments => 1046,
+            CheckErrors::BadBurnFTArguments => 1047,
+            CheckErrors::BadTupleFieldName => 1048,
+            CheckErrors::ExpectedTuple(..) => 1049,
+            CheckErrors::NoSuchTupleField(..) => 1050,
+            CheckErrors::EmptyTuplesNotAllowed => 1051,
+            CheckErrors::BadTupleConstruction => 1052,
+            CheckErrors::TupleExpectsPairs => 1053,
+            CheckErrors::NoSuchDataVariable(..) => 1054,
+            CheckErrors::BadMapName => 1055,
+            CheckErrors::NoSuchMap(..) => 1056,
+            CheckErrors::DefineFunctionBadSignature => 1057,
+            CheckErrors::BadFunctionName => 1058,
+            CheckErrors::BadMapTypeDefinition => 1059,
+            CheckErrors::PublicFunctionMustReturnResponse(..) => 1060,
+            CheckErrors::DefineVariableBadSignature => 1061,
+            CheckErrors::ReturnTypesMustMatch(..) => 1062,
+            CheckErrors::CircularReference(..) => 1063,
+            CheckErrors::NoSuchContract(..) => 1064,
+            CheckErrors::NoSuchPublicFunction(..) => 1065,
+            CheckErrors::PublicFunctionNotReadOnly(..) => 1066,
+            CheckErrors::ContractAlreadyExists(..) => 1067,
+            CheckErrors::ContractCallExpectName => 1068,
+            CheckErrors::ExpectedCallableType(..) => 1069,
+            CheckErrors::NoSuchBlockInfoProperty(..) => 1070,
+            CheckErrors::NoSuchBurnBlockInfoProperty(..) => 1071,
+            CheckErrors::GetBlockInfoExpectPropertyName => 1072,
+            CheckErrors::GetBurnBlockInfoExpectPropertyName => 1073,
+            CheckErrors::NameAlreadyUsed(..) => 1074,
+            CheckErrors::NonFunctionApplication => 1075,
+            CheckErrors::ExpectedListApplication => 1076,
+            CheckErrors::ExpectedSequence(..) => 1077,
+            CheckErrors::MaxLengthOverflow => 1078,
+            CheckErrors::BadLetSyntax => 1079,
+            CheckErrors::BadSyntaxBinding => 1080,
+            CheckErrors::BadSyntaxExpectedListOfPairs => 1081,
+            CheckErrors::MaxContextDepthReached => 1082,
+            CheckErrors::UndefinedFunction(..) => 1083,
+            CheckErrors::UndefinedVariable(..) => 1084,
+            CheckErrors::RequiresAtLeastArguments(..) => 1085,
+            CheckErrors::RequiresAtMostArguments(..) => 1086,
+            CheckErrors::IncorrectArgumentCount(..) => 1087,
+            CheckErrors::IfArmsMustMatch(..) => 1088,
+            CheckErrors::MatchArmsMustMatch(..) => 1089,
+            CheckErrors::DefaultTypesMustMatch(..) => 1090,
+            CheckErrors::TooManyExpressions => 1091,
+            CheckErrors::IllegalOrUnknownFunctionApplication(..) => 1092,
+            CheckErrors::UnknownFunction(..) => 1093,
+            CheckErrors::NoSuchTrait(..) => 1094,
+            CheckErrors::TraitReferenceUnknown(..) => 1095,
+            CheckErrors::TraitMethodUnknown(..) => 1096,
+            CheckErrors::ExpectedTraitIdentifier => 1097,
+            CheckErrors::ImportTraitBadSignature => 1098,
+            CheckErrors::TraitReferenceNotAllowed => 1099,
+            CheckErrors::BadTraitImplementation(..) => 1100,
+            CheckErrors::DefineTraitBadSignature => 1101,
+            CheckErrors::DefineTraitDuplicateMethod(..) => 1102,
+            CheckErrors::UnexpectedTraitOrFieldReference => 1103,
+            CheckErrors::TraitBasedContractCallInReadOnly => 1104,
+            CheckErrors::ContractOfExpectsTrait => 1105,
+            CheckErrors::IncompatibleTrait(..) => 1106,
+            CheckErrors::InvalidCharactersDetected => 1107,
+            CheckErrors::InvalidUTF8Encoding => 1108,
+            CheckErrors::InvalidSecp65k1Signature => 1109,
+            CheckErrors::WriteAttemptedInReadOnly => 1110,
+            CheckErrors::AtBlockClosureMustBeReadOnly => 1111,
+            CheckErrors::ContractSourceTooLarge(..) => 1112,
+            CheckErrors::TooManyDefinitions(..) => 1113,
+            CheckErrors::TooManyASTNodes(..) => 1114,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -217,6 +347,62 @@ impl CheckError {
         self.diagnostic.spans = exprs.iter().map(|e| e.span.clone()).collect();
         self.expressions.replace(exprs.clone().to_vec());
     }
+
+    pub fn error_code(&self) -> u32 {
+        self.err.error_code()
+    }
+
+    pub fn category(&self) -> ErrorCategory {
+        ErrorCategory::Checked
+    }
+}
+
+/// Findings of the analysis passes that don't prevent a contract from being
+///  deployed, but likely point at a mistake.
+#[derive(Debug, PartialEq, Clone)]
+pub enum CheckWarnings {
+    // the expression can never be evaluated: an earlier expression in the
+    //  same body always exits early
+    UnreachableCode,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct CheckWarning {
+    pub warning: CheckWarnings,
+    pub expression: SymbolicExpression,
+    pub diagnostic: Diagnostic,
+}
+
+impl CheckWarning {
+    pub fn new(warning: CheckWarnings, expr: &SymbolicExpression) -> CheckWarning {
+        let mut diagnostic = Diagnostic::warn(&warning);
+        diagnostic.spans = vec![expr.span.clone()];
+        CheckWarning {
+            warning,
+            expression: expr.clone(),
+            diagnostic,
+        }
+    }
+}
+
+impl DiagnosableError for CheckWarnings {
+    fn message(&self) -> String {
+        match self {
+            CheckWarnings::UnreachableCode => {
+                "unreachable code: a preceding expression always exits early".into()
+            }
+        }
+    }
+
+    fn suggestion(&self) -> Option<String> {
+        match self {
+            CheckWarnings::UnreachableCode => None,
+        }
+    }
+
+    fn level(&self) -> Level {
+        Level::Warning
+    }
 }
 
 impl fmt::Display for CheckErrors {
@@ -411,6 +597,9 @@ impl DiagnosableError for CheckErrors {
             CheckErrors::TraitBasedContractCallInReadOnly => format!("use of trait based contract calls are not allowed in read-only context"),
             CheckErrors::WriteAttemptedInReadOnly => format!("expecting read-only statements, detected a writing operation"),
             CheckErrors::AtBlockClosureMustBeReadOnly => format!("(at-block ...) closures expect read-only statements, but detected a writing operation"),
+            CheckErrors::ContractSourceTooLarge(size, limit) => format!("contract source is {} bytes, exceeding the limit of {}", size, limit),
+            CheckErrors::TooManyDefinitions(count, limit) => format!("contract has {} top-level definitions, exceeding the limit of {}", count, limit),
+            CheckErrors::TooManyASTNodes(count, limit) => format!("contract has {} expressions, exceeding the limit of {}", count, limit),
             CheckErrors::BadTokenName => format!("expecting an token name as an argument"),
             CheckErrors::DefineFTBadSignature => format!("(define-token ...) expects a token name as an argument"),
             CheckErrors::DefineNFTBadSignature => format!("(define-asset ...) expects an asset name and an asset identifier type signature as arguments"),
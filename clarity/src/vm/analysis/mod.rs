@@ -16,7 +16,9 @@
 
 pub mod analysis_db;
 pub mod arithmetic_checker;
+pub mod cache;
 pub mod contract_interface_builder;
+pub mod dead_code_checker;
 pub mod errors;
 pub mod read_only_checker;
 pub mod trait_checker;
@@ -35,10 +37,11 @@ use crate::vm::types::{QualifiedContractIdentifier, TypeSignature};
 use crate::vm::ClarityVersion;
 
 pub use self::analysis_db::AnalysisDatabase;
-pub use self::errors::{CheckError, CheckErrors, CheckResult};
+pub use self::errors::{CheckError, CheckErrors, CheckResult, CheckWarning, CheckWarnings};
 
 use self::arithmetic_checker::ArithmeticOnlyChecker;
 use self::contract_interface_builder::build_contract_interface;
+use self::dead_code_checker::DeadCodeChecker;
 use self::read_only_checker::ReadOnlyChecker;
 use self::trait_checker::TraitChecker;
 use self::type_checker::v2_05::TypeChecker as TypeChecker2_05;
@@ -142,6 +145,7 @@ pub fn run_analysis(
         }?;
         TraitChecker::run_pass(&epoch, &mut contract_analysis, db)?;
         ArithmeticOnlyChecker::check_contract_cost_eligible(&mut contract_analysis);
+        DeadCodeChecker::collect_warnings(&mut contract_analysis);
 
         if STORE_CONTRACT_SRC_INTERFACE {
             let interface = build_contract_interface(&contract_analysis);
@@ -158,5 +162,69 @@ pub fn run_analysis(
     }
 }
 
+/// Run the analysis passes like `run_analysis`, but for author tooling: rather than
+///  stopping at the first error, report the read-only checker's error (if the type
+///  checker did not find the same one) together with every independent error found
+///  by the type checker, in source order. Only the Clarity 2.1 type checker collects
+///  errors; earlier epochs report at most one type error. The contract is not saved.
+///  Not used in production
+pub fn run_analysis_collecting_errors(
+    contract_identifier: &QualifiedContractIdentifier,
+    expressions: &mut [SymbolicExpression],
+    analysis_db: &mut AnalysisDatabase,
+    cost_tracker: LimitedCostTracker,
+    epoch: StacksEpochId,
+    version: ClarityVersion,
+) -> Result<ContractAnalysis, (Vec<CheckError>, LimitedCostTracker)> {
+    let mut contract_analysis = ContractAnalysis::new(
+        contract_identifier.clone(),
+        expressions.to_vec(),
+        cost_tracker,
+        epoch,
+        version,
+    );
+    let result = analysis_db.execute(|db| {
+        let read_only_result = ReadOnlyChecker::run_pass(&epoch, &mut contract_analysis, db);
+        let type_check_result = match epoch {
+            StacksEpochId::Epoch20 | StacksEpochId::Epoch2_05 => {
+                TypeChecker2_05::run_pass(&epoch, &mut contract_analysis, db).map_err(|e| vec![e])
+            }
+            StacksEpochId::Epoch21 => {
+                TypeChecker2_1::run_pass_collecting_errors(&mut contract_analysis, db)
+            }
+            StacksEpochId::Epoch10 => unreachable!("Epoch 1.0 is not a valid epoch for analysis"),
+        };
+        let mut errors = type_check_result.err().unwrap_or_default();
+        if let Err(e) = read_only_result {
+            if !errors.iter().any(|type_error| type_error.err == e.err) {
+                errors.push(e);
+            }
+        }
+        if !errors.is_empty() {
+            errors.sort_by_key(|e| {
+                e.diagnostic
+                    .spans
+                    .first()
+                    .map(|span| (span.start_line, span.start_column))
+            });
+            return Err(errors);
+        }
+
+        TraitChecker::run_pass(&epoch, &mut contract_analysis, db).map_err(|e| vec![e])?;
+        ArithmeticOnlyChecker::check_contract_cost_eligible(&mut contract_analysis);
+        DeadCodeChecker::collect_warnings(&mut contract_analysis);
+
+        if STORE_CONTRACT_SRC_INTERFACE {
+            let interface = build_contract_interface(&contract_analysis);
+            contract_analysis.contract_interface = Some(interface);
+        }
+        Ok(())
+    });
+    match result {
+        Ok(_) => Ok(contract_analysis),
+        Err(errors) => Err((errors, contract_analysis.take_contract_cost_tracker())),
+    }
+}
+
 #[cfg(test)]
 mod tests;
@@ -285,19 +285,21 @@ impl<'a, 'b> ReadOnlyChecker<'a, 'b> {
         use crate::vm::functions::NativeFunctions::*;
 
         match function {
-            Add | Subtract | Divide | Multiply | CmpGeq | CmpLeq | CmpLess | CmpGreater
-            | Modulo | Power | Sqrti | Log2 | BitwiseXor | And | Or | Not | Hash160 | Sha256
-            | Keccak256 | Equals | If | Sha512 | Sha512Trunc256 | Secp256k1Recover
-            | Secp256k1Verify | ConsSome | ConsOkay | ConsError | DefaultTo | UnwrapRet
-            | UnwrapErrRet | IsOkay | IsNone | Asserts | Unwrap | UnwrapErr | Match | IsErr
-            | IsSome | TryRet | ToUInt | ToInt | BuffToIntLe | BuffToUIntLe | BuffToIntBe
-            | BuffToUIntBe | IntToAscii | IntToUtf8 | StringToInt | StringToUInt | IsStandard
-            | ToConsensusBuff | PrincipalDestruct | PrincipalConstruct | Append | Concat
-            | AsMaxLen | ContractOf | PrincipalOf | ListCons | GetBlockInfo | GetBurnBlockInfo
-            | TupleGet | TupleMerge | Len | Print | AsContract | Begin | FetchVar
-            | GetStxBalance | StxGetAccount | GetTokenBalance | GetAssetOwner | GetTokenSupply
-            | ElementAt | IndexOf | Slice | ReplaceAt | BitwiseAnd | BitwiseOr | BitwiseNot
-            | BitwiseLShift | BitwiseRShift | BitwiseXor2 | ElementAtAlias | IndexOfAlias => {
+            Add | Subtract | Divide | Multiply | MulDiv | CmpGeq | CmpLeq | CmpLess
+            | CmpGreater | Modulo | Power | Sqrti | Log2 | BitwiseXor | And | Or | Not
+            | Hash160 | Sha256 | Keccak256 | Equals | If | Sha512 | Sha512Trunc256
+            | Secp256k1Recover | Secp256k1Verify | ConsSome | ConsOkay | ConsError | DefaultTo
+            | UnwrapRet | UnwrapErrRet | IsOkay | IsNone | Asserts | Unwrap | UnwrapErr | Match
+            | IsErr | IsSome | TryRet | ToUInt | ToInt | BuffToIntLe | BuffToUIntLe
+            | BuffToIntBe | BuffToUIntBe | IntToAscii | IntToUtf8 | StringToInt | StringToUInt
+            | IsStandard | ToConsensusBuff | ToAscii | StringToBuff | PrincipalDestruct
+            | PrincipalConstruct | Append | Concat | AsMaxLen | ContractOf | PrincipalOf
+            | ListCons | GetBlockInfo | GetBurnBlockInfo | TupleGet | TupleGetOptional
+            | TupleMerge | Len | Print | AsContract | Begin | FetchVar | GetStxBalance
+            | StxGetAccount | GetTokenBalance | GetAssetOwner | GetTokenSupply | ElementAt
+            | IndexOf | Slice | ReplaceAt | BitwiseAnd | BitwiseOr | BitwiseNot | BitwiseLShift
+            | BitwiseRShift | BitwiseXor2 | ElementAtAlias | IndexOfAlias | Sort | AssertEq
+            | BitCount | LeadingZeros => {
                 // Check all arguments.
                 self.check_each_expression_is_read_only(args)
             }
@@ -355,10 +357,20 @@ impl<'a, 'b> ReadOnlyChecker<'a, 'b> {
                 //     read-only or not.
                 self.check_expression_application_is_read_only(args)
             }
-            Filter => {
+            Filter | MapTry => {
                 check_argument_count(2, args)?;
                 self.check_expression_application_is_read_only(args)
             }
+            UnwrapOrElse => {
+                check_argument_count(2, args)?;
+
+                // the fallback is called without arguments, so only its own definition
+                //   decides whether the application is read-only.
+                let is_input_read_only = self.check_read_only(&args[0])?;
+                let is_fallback_read_only =
+                    self.check_expression_application_is_read_only(&args[1..2])?;
+                Ok(is_input_read_only && is_fallback_read_only)
+            }
             Fold => {
                 check_argument_count(3, args)?;
 
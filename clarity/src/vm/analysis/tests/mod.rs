@@ -14,11 +14,17 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+use crate::vm::analysis::cache::{AnalysisCache, AnalysisCacheKey};
 use crate::vm::analysis::errors::CheckErrors;
 use crate::vm::analysis::type_checker::v2_1::tests::mem_type_check;
 use crate::vm::analysis::AnalysisDatabase;
 use crate::vm::analysis::{type_check, ContractAnalysis};
 use crate::vm::ast::parse;
+use crate::vm::costs::LimitedCostTracker;
+use crate::vm::database::MemoryBackingStore;
+use crate::vm::types::{FunctionType, QualifiedContractIdentifier, TypeSignature};
+use crate::vm::ClarityVersion;
+use stacks_common::types::StacksEpochId;
 
 #[test]
 fn test_list_types_must_match() {
@@ -297,3 +303,124 @@ fn test_write_attempt_in_readonly() {
     assert!(format!("{}", err.diagnostic)
         .contains("expecting read-only statements, detected a writing operation"));
 }
+
+#[test]
+fn test_analysis_cache_invalidated_by_dependency() {
+    let contract_a_v1 = "(define-read-only (get-n) u1)";
+    let contract_a_v2 = "(define-read-only (get-n) 1)";
+    let contract_b = "(define-read-only (f) (contract-call? .a get-n))";
+    let contract_c = "(define-read-only (g) u2)";
+
+    let a_id = QualifiedContractIdentifier::local("a").unwrap();
+    let b_id = QualifiedContractIdentifier::local("b").unwrap();
+    let c_id = QualifiedContractIdentifier::local("c").unwrap();
+
+    let epoch = StacksEpochId::Epoch21;
+    let version = ClarityVersion::Clarity2;
+
+    // deploy the dependencies of `b` into a fresh store
+    let deploy = |store: &mut MemoryBackingStore,
+                  contracts: &[(&QualifiedContractIdentifier, &str)]| {
+        let mut analysis_db = store.as_analysis_db();
+        for (contract_id, source) in contracts.iter() {
+            let mut expressions = parse(contract_id, source, version, epoch).unwrap();
+            analysis_db
+                .execute(|db| {
+                    db.test_insert_contract_hash(contract_id);
+                    type_check(contract_id, &mut expressions, db, true, &epoch, &version)
+                })
+                .unwrap();
+        }
+    };
+    let analyze = |cache: &mut AnalysisCache, store: &mut MemoryBackingStore| {
+        let mut expressions = parse(&b_id, contract_b, version, epoch).unwrap();
+        let mut analysis_db = store.as_analysis_db();
+        let key = AnalysisCacheKey::new(
+            &b_id,
+            contract_b,
+            &expressions,
+            &mut analysis_db,
+            epoch,
+            version,
+        );
+        let (analysis, cached) = cache
+            .run_analysis(
+                &b_id,
+                key,
+                &mut expressions,
+                &mut analysis_db,
+                LimitedCostTracker::new_free(),
+            )
+            .unwrap();
+        let return_type = match analysis.get_read_only_function_type("f") {
+            Some(FunctionType::Fixed(function)) => function.returns.clone(),
+            other => panic!("Unexpected function type: {:?}", other),
+        };
+        (return_type, cached)
+    };
+
+    let mut cache = AnalysisCache::new();
+
+    let mut store_v1 = MemoryBackingStore::new();
+    deploy(
+        &mut store_v1,
+        &[(&a_id, contract_a_v1), (&c_id, contract_c)],
+    );
+    assert_eq!(
+        analyze(&mut cache, &mut store_v1),
+        (TypeSignature::UIntType, false)
+    );
+    assert_eq!(
+        analyze(&mut cache, &mut store_v1),
+        (TypeSignature::UIntType, true)
+    );
+
+    // `b` does not reference `c`, so a different `c` does not bust its entry
+    let mut store_v1_c = MemoryBackingStore::new();
+    deploy(
+        &mut store_v1_c,
+        &[(&a_id, contract_a_v1), (&c_id, "(define-read-only (g) 2)")],
+    );
+    assert_eq!(
+        analyze(&mut cache, &mut store_v1_c),
+        (TypeSignature::UIntType, true)
+    );
+
+    // `a`'s interface changed: `b` is analyzed again, against the new `a`
+    let mut store_v2 = MemoryBackingStore::new();
+    deploy(
+        &mut store_v2,
+        &[(&a_id, contract_a_v2), (&c_id, contract_c)],
+    );
+    assert_eq!(
+        analyze(&mut cache, &mut store_v2),
+        (TypeSignature::IntType, false)
+    );
+    assert_eq!(
+        analyze(&mut cache, &mut store_v2),
+        (TypeSignature::IntType, true)
+    );
+
+    // without `a` deployed at all, `b` is re-analyzed and fails
+    let mut store_empty = MemoryBackingStore::new();
+    let mut expressions = parse(&b_id, contract_b, version, epoch).unwrap();
+    let mut analysis_db = store_empty.as_analysis_db();
+    let key = AnalysisCacheKey::new(
+        &b_id,
+        contract_b,
+        &expressions,
+        &mut analysis_db,
+        epoch,
+        version,
+    );
+    assert!(cache
+        .run_analysis(
+            &b_id,
+            key,
+            &mut expressions,
+            &mut analysis_db,
+            LimitedCostTracker::new_free(),
+        )
+        .is_err());
+    assert_eq!(cache.len(), 1);
+}
@@ -190,7 +190,17 @@ impl FunctionType {
                 }
                 Err(CheckErrors::UnionTypeError(arg_types.clone(), found_type.clone()).into())
             }
+            FunctionType::Hash(return_type) => FunctionType::UnionArgs(
+                vec![
+                    TypeSignature::max_buffer(),
+                    TypeSignature::UIntType,
+                    TypeSignature::IntType,
+                ],
+                return_type.clone(),
+            )
+            .check_args_2_05(accounting, args),
             FunctionType::ArithmeticVariadic
+            | FunctionType::ArithmeticVariadicOrList
             | FunctionType::ArithmeticBinary
             | FunctionType::ArithmeticUnary => {
                 if self == &FunctionType::ArithmeticUnary {
@@ -743,7 +743,10 @@ impl TypedNativeFunction {
             | StringToUInt | IntToAscii | IntToUtf8 | GetBurnBlockInfo | StxTransferMemo
             | StxGetAccount | BitwiseAnd | BitwiseOr | BitwiseNot | BitwiseLShift
             | BitwiseRShift | BitwiseXor2 | Slice | ToConsensusBuff | FromConsensusBuff
-            | ReplaceAt => unreachable!("Clarity 2 keywords should not show up in 2.05"),
+            | ReplaceAt | Sort | AssertEq | TupleGetOptional | MulDiv | ToAscii | StringToBuff
+            | UnwrapOrElse | BitCount | LeadingZeros | MapTry => {
+                unreachable!("Clarity 2 keywords should not show up in 2.05")
+            }
         }
     }
 }
@@ -16,8 +16,10 @@
 
 use crate::vm::representations::{ClarityName, SymbolicExpression};
 use crate::vm::types::signatures::{CallableSubtype, FunctionSignature};
-use crate::vm::types::{FunctionType, QualifiedContractIdentifier, TraitIdentifier, TypeSignature};
-use crate::vm::ClarityVersion;
+use crate::vm::types::{
+    FunctionType, QualifiedContractIdentifier, TraitIdentifier, TypeSignature, Value,
+};
+use crate::vm::{is_reserved, ClarityVersion};
 use std::collections::{BTreeMap, HashMap, HashSet};
 
 use crate::vm::contexts::MAX_CONTEXT_DEPTH;
@@ -41,7 +43,7 @@ impl TraitContext {
     pub fn new(clarity_version: ClarityVersion) -> TraitContext {
         match clarity_version {
             ClarityVersion::Clarity1 => Self::Clarity1(HashMap::new()),
-            ClarityVersion::Clarity2 => Self::Clarity2 {
+            ClarityVersion::Clarity2 | ClarityVersion::Clarity3 => Self::Clarity2 {
                 defined: HashSet::new(),
                 all: HashMap::new(),
             },
@@ -129,6 +131,9 @@ pub struct ContractContext {
     contract_identifier: QualifiedContractIdentifier,
     map_types: HashMap<ClarityName, (TypeSignature, TypeSignature)>,
     variable_types: HashMap<ClarityName, TypeSignature>,
+    // constants defined as literal values, which (from Clarity3) may stand in for
+    //  the literal where analysis requires a static value
+    literal_constants: HashMap<ClarityName, Value>,
     private_function_types: HashMap<ClarityName, FunctionType>,
     public_function_types: HashMap<ClarityName, FunctionType>,
     read_only_function_types: HashMap<ClarityName, FunctionType>,
@@ -137,6 +142,7 @@ pub struct ContractContext {
     non_fungible_tokens: HashMap<ClarityName, TypeSignature>,
     traits: TraitContext,
     pub implemented_traits: HashSet<TraitIdentifier>,
+    clarity_version: ClarityVersion,
 }
 
 impl ContractContext {
@@ -147,6 +153,7 @@ impl ContractContext {
         ContractContext {
             contract_identifier,
             variable_types: HashMap::new(),
+            literal_constants: HashMap::new(),
             private_function_types: HashMap::new(),
             public_function_types: HashMap::new(),
             read_only_function_types: HashMap::new(),
@@ -156,6 +163,7 @@ impl ContractContext {
             non_fungible_tokens: HashMap::new(),
             traits: TraitContext::new(clarity_version),
             implemented_traits: HashSet::new(),
+            clarity_version,
         }
     }
 
@@ -175,12 +183,23 @@ impl ContractContext {
             || self.traits.is_name_used(name)
             || self.map_types.contains_key(name)
         {
-            Err(CheckError::new(CheckErrors::NameAlreadyUsed(
+            return Err(CheckError::new(CheckErrors::NameAlreadyUsed(
                 name.to_string(),
-            )))
-        } else {
-            Ok(())
+            )));
+        }
+
+        // From Clarity3, contracts are also rejected at deploy time for names the
+        //  interpreter would refuse anyway: reserved names and read-only functions.
+        if self.clarity_version >= ClarityVersion::Clarity3
+            && (is_reserved(name, &self.clarity_version)
+                || self.read_only_function_types.contains_key(name))
+        {
+            return Err(CheckError::new(CheckErrors::NameAlreadyUsed(
+                name.to_string(),
+            )));
         }
+
+        Ok(())
     }
 
     fn check_function_type(&mut self, f_name: &str) -> CheckResult<()> {
@@ -277,6 +296,9 @@ impl ContractContext {
         trait_name: ClarityName,
         trait_signature: BTreeMap<ClarityName, FunctionSignature>,
     ) -> CheckResult<()> {
+        if self.clarity_version >= ClarityVersion::Clarity3 {
+            self.check_name_used(&trait_name)?;
+        }
         self.traits.add_defined_trait(
             self.contract_identifier.clone(),
             trait_name,
@@ -313,6 +335,14 @@ impl ContractContext {
         self.variable_types.get(name)
     }
 
+    pub fn add_literal_constant(&mut self, const_name: ClarityName, value: Value) {
+        self.literal_constants.insert(const_name, value);
+    }
+
+    pub fn get_literal_constant(&self, name: &str) -> Option<&Value> {
+        self.literal_constants.get(name)
+    }
+
     pub fn get_persisted_variable_type(&self, name: &str) -> Option<&TypeSignature> {
         self.persisted_variable_types.get(name)
     }
@@ -23,7 +23,7 @@ use crate::vm::costs::{
     analysis_typecheck_cost, cost_functions, runtime_cost, ClarityCostFunctionReference,
     CostErrors, CostOverflowingMath, CostTracker, ExecutionCost, LimitedCostTracker,
 };
-use crate::vm::functions::define::DefineFunctionsParsed;
+use crate::vm::functions::define::{DefineFunctions, DefineFunctionsParsed};
 use crate::vm::functions::NativeFunctions;
 use crate::vm::representations::SymbolicExpressionType::{
     Atom, AtomValue, Field, List, LiteralValue, TraitReference,
@@ -39,7 +39,7 @@ use crate::vm::types::{
     TypeSignature, Value, MAX_TYPE_DEPTH,
 };
 use crate::vm::variables::NativeVariables;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::TryInto;
 
 use crate::vm::ClarityVersion;
@@ -124,6 +124,33 @@ impl AnalysisPass for TypeChecker<'_, '_> {
         contract_analysis: &mut ContractAnalysis,
         analysis_db: &mut AnalysisDatabase,
     ) -> CheckResult<()> {
+        TypeChecker::run_checker(
+            contract_analysis,
+            analysis_db,
+            |checker, contract_analysis| checker.run(contract_analysis),
+        )
+    }
+}
+
+impl TypeChecker<'_, '_> {
+    /// Like `run_pass`, but reports every error found rather than only the first (see
+    ///  `run_collecting_errors`).
+    pub fn run_pass_collecting_errors(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+    ) -> Result<(), Vec<CheckError>> {
+        TypeChecker::run_checker(
+            contract_analysis,
+            analysis_db,
+            |checker, contract_analysis| checker.run_collecting_errors(contract_analysis),
+        )
+    }
+
+    fn run_checker<E>(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        check: fn(&mut TypeChecker, &mut ContractAnalysis) -> Result<(), E>,
+    ) -> Result<(), E> {
         let cost_track = contract_analysis.take_contract_cost_tracker();
         let mut command = TypeChecker::new(
             analysis_db,
@@ -133,7 +160,7 @@ impl AnalysisPass for TypeChecker<'_, '_> {
         );
         // run the analysis, and replace the cost tracker whether or not the
         //   analysis succeeded.
-        match command.run(contract_analysis) {
+        match check(&mut command, contract_analysis) {
             Ok(_) => {
                 let cost_track = command.into_contract_analysis(contract_analysis);
                 contract_analysis.replace_contract_cost_tracker(cost_track);
@@ -215,6 +242,49 @@ impl FunctionType {
                     FunctionReturnsSignature::Fixed(return_type) => Ok(return_type.clone()),
                 }
             }
+            FunctionType::Hash(return_type) => {
+                check_argument_count(1, args)?;
+                let found_type = &args[0];
+                let arg_types = [
+                    TypeSignature::max_buffer(),
+                    TypeSignature::UIntType,
+                    TypeSignature::IntType,
+                ];
+                for expected_type in arg_types.iter() {
+                    analysis_typecheck_cost(accounting, expected_type, found_type)?;
+                    if expected_type.admits_type(&StacksEpochId::Epoch21, found_type)? {
+                        return Ok(return_type.clone());
+                    }
+                }
+                // Clarity3 hashes any other value through its consensus serialization
+                if clarity_version >= ClarityVersion::Clarity3 {
+                    found_type.max_serialized_size()?;
+                    return Ok(return_type.clone());
+                }
+                Err(CheckErrors::UnionTypeError(arg_types.to_vec(), found_type.clone()).into())
+            }
+            FunctionType::ArithmeticVariadicOrList => {
+                // Clarity3 allows reducing a list of ints or uints, e.g., (+ (list 1 2 3))
+                if clarity_version >= ClarityVersion::Clarity3 {
+                    if let [TypeSignature::SequenceType(SequenceSubtype::ListType(list_type))] =
+                        args
+                    {
+                        let item_type = list_type.get_list_item_type();
+                        analysis_typecheck_cost(accounting, &TypeSignature::IntType, item_type)?;
+                        return match item_type {
+                            TypeSignature::IntType | TypeSignature::UIntType => {
+                                Ok(item_type.clone())
+                            }
+                            _ => Err(CheckErrors::UnionTypeError(
+                                vec![TypeSignature::IntType, TypeSignature::UIntType],
+                                item_type.clone(),
+                            )
+                            .into()),
+                        };
+                    }
+                }
+                FunctionType::ArithmeticVariadic.check_args_2_1(accounting, args, clarity_version)
+            }
             FunctionType::ArithmeticVariadic
             | FunctionType::ArithmeticBinary
             | FunctionType::ArithmeticUnary => {
@@ -867,6 +937,39 @@ fn type_reserved_variable(variable_name: &str, version: &ClarityVersion) -> Opti
     }
 }
 
+fn is_cost_error(err: &CheckErrors) -> bool {
+    matches!(
+        err,
+        CheckErrors::CostOverflow
+            | CheckErrors::CostBalanceExceeded(..)
+            | CheckErrors::MemoryBalanceExceeded(..)
+            | CheckErrors::CostComputationFailed(_)
+    )
+}
+
+/// The name introduced by a top-level `define-*` form, if `exp` is one.
+fn defined_name(exp: &SymbolicExpression) -> Option<&ClarityName> {
+    let (_, args) = DefineFunctions::try_parse(exp)?;
+    let name = args.first()?;
+    name.match_atom()
+        .or_else(|| name.match_list()?.first()?.match_atom())
+}
+
+/// Whether `err` is a use of a name whose definition already failed to check.
+fn follows_from_failed_definition(err: &CheckErrors, failed_definitions: &HashSet<String>) -> bool {
+    match err {
+        CheckErrors::UnknownFunction(name)
+        | CheckErrors::UndefinedFunction(name)
+        | CheckErrors::UndefinedVariable(name)
+        | CheckErrors::NoSuchDataVariable(name)
+        | CheckErrors::NoSuchMap(name)
+        | CheckErrors::NoSuchFT(name)
+        | CheckErrors::NoSuchNFT(name)
+        | CheckErrors::TraitReferenceUnknown(name) => failed_definitions.contains(name),
+        _ => false,
+    }
+}
+
 pub fn no_type() -> TypeSignature {
     TypeSignature::NoType
 }
@@ -932,6 +1035,25 @@ impl<'a, 'b> TypeChecker<'a, 'b> {
     }
 
     pub fn run(&mut self, contract_analysis: &mut ContractAnalysis) -> CheckResult<()> {
+        self.charge_analysis_storage(contract_analysis)?;
+        self.type_check_contract(contract_analysis, false)
+            .map_err(|mut errors| errors.remove(0))
+    }
+
+    /// Type check the contract like `run`, but rather than stopping at the first error,
+    ///  move on to the next top-level form and return all of the errors found. Errors
+    ///  that only follow from an earlier failed definition (e.g., calls to a function
+    ///  whose body failed to check) are not reported, and a cost error ends the analysis.
+    pub fn run_collecting_errors(
+        &mut self,
+        contract_analysis: &mut ContractAnalysis,
+    ) -> Result<(), Vec<CheckError>> {
+        self.charge_analysis_storage(contract_analysis)
+            .map_err(|e| vec![e])?;
+        self.type_check_contract(contract_analysis, true)
+    }
+
+    fn charge_analysis_storage(&mut self, contract_analysis: &ContractAnalysis) -> CheckResult<()> {
         // charge for the eventual storage cost of the analysis --
         //  it is linear in the size of the AST.
         let mut size: u64 = 0;
@@ -946,8 +1068,19 @@ impl<'a, 'b> TypeChecker<'a, 'b> {
         }
 
         runtime_cost(ClarityCostFunction::AnalysisStorage, self, size)?;
+        Ok(())
+    }
 
+    /// Type check each top-level form of the contract. Unless `collect_errors` is set,
+    ///  this stops at (and returns only) the first error.
+    fn type_check_contract(
+        &mut self,
+        contract_analysis: &ContractAnalysis,
+        collect_errors: bool,
+    ) -> Result<(), Vec<CheckError>> {
         let mut local_context = TypingContext::new(StacksEpochId::Epoch21, self.clarity_version);
+        let mut errors = vec![];
+        let mut failed_definitions = HashSet::new();
 
         for exp in contract_analysis.expressions.iter() {
             let mut result_res = self.try_type_check_define(&exp, &mut local_context);
@@ -956,13 +1089,35 @@ impl<'a, 'b> TypeChecker<'a, 'b> {
                     error.set_expression(&exp);
                 }
             }
-            let result = result_res?;
-            if result.is_none() {
-                // was _not_ a define statement, so handle like a normal statement.
-                self.type_check(&exp, &local_context)?;
+            let result = result_res.and_then(|result| {
+                if result.is_none() {
+                    // was _not_ a define statement, so handle like a normal statement.
+                    self.type_check(&exp, &local_context)?;
+                }
+                Ok(())
+            });
+
+            if let Err(error) = result {
+                if !collect_errors || is_cost_error(&error.err) {
+                    errors.push(error);
+                    return Err(errors);
+                }
+                // a failed function definition may leave its return type tracker behind
+                self.function_return_tracker = None;
+                if !follows_from_failed_definition(&error.err, &failed_definitions) {
+                    errors.push(error);
+                }
+                if let Some(name) = defined_name(exp) {
+                    failed_definitions.insert(name.to_string());
+                }
             }
         }
-        Ok(())
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
     }
 
     // Type check an expression, with an expected_type that should _admit_ the expression.
@@ -1064,8 +1219,27 @@ impl<'a, 'b> TypeChecker<'a, 'b> {
         let function_name = function_name
             .match_atom()
             .ok_or(CheckErrors::BadFunctionName)?;
-        let mut args = parse_name_type_pairs::<()>(StacksEpochId::Epoch21, args, &mut ())
-            .map_err(|_| CheckErrors::BadSyntaxBinding)?;
+        let mut args = match parse_name_type_pairs::<()>(StacksEpochId::Epoch21, args, &mut ()) {
+            Ok(args) => args,
+            Err(CheckErrors::UnknownTypeName(name))
+                if self.clarity_version >= ClarityVersion::Clarity3 =>
+            {
+                // from Clarity3, point the error at the argument with the unknown type
+                let mut error = CheckError::new(CheckErrors::UnknownTypeName(name));
+                if let Some(arg) = args.iter().find(|arg| {
+                    parse_name_type_pairs::<()>(
+                        StacksEpochId::Epoch21,
+                        std::slice::from_ref(*arg),
+                        &mut (),
+                    )
+                    .is_err()
+                }) {
+                    error.set_expression(arg);
+                }
+                return Err(error);
+            }
+            Err(_) => return Err(CheckErrors::BadSyntaxBinding.into()),
+        };
 
         if self.function_return_tracker.is_some() {
             panic!("Interpreter error: Previous function define left dirty typecheck state.");
@@ -1162,6 +1336,17 @@ impl<'a, 'b> TypeChecker<'a, 'b> {
         if let Some(ref native_function) =
             NativeFunctions::lookup_by_name_at_version(function, &self.clarity_version)
         {
+            // From Clarity3, argument counts are checked before any argument is type-checked.
+            //  Earlier versions leave the check to each native, several of which only
+            //  enforce a minimum.
+            if self.clarity_version >= ClarityVersion::Clarity3 {
+                if let Err(e) = native_function
+                    .arity_at_version(&self.clarity_version)
+                    .check(args.len())
+                {
+                    return Some(Err(e.into()));
+                }
+            }
             let typed_function = TypedNativeFunction::type_native_function(native_function);
             Some(typed_function.type_check_application(self, args, context))
         } else {
@@ -1428,6 +1613,10 @@ impl<'a, 'b> TypeChecker<'a, 'b> {
                         self,
                         v_type.type_size()?,
                     )?;
+                    if let Some(literal) = value.match_literal_value() {
+                        self.contract_context
+                            .add_literal_constant(v_name.clone(), literal.clone());
+                    }
                     self.contract_context.add_variable_type(v_name, v_type)?;
                 }
                 DefineFunctionsParsed::PrivateFunction { signature, body } => {
@@ -3,8 +3,8 @@ use stacks_common::types::StacksEpochId;
 use super::{TypeChecker, TypeResult};
 use crate::vm::analysis::read_only_checker::check_argument_count;
 use crate::vm::analysis::type_checker::contexts::TypingContext;
-use crate::vm::analysis::CheckError;
-use crate::vm::types::{BufferLength, SequenceSubtype, TypeSignature};
+use crate::vm::analysis::{CheckError, CheckErrors};
+use crate::vm::types::{BufferLength, SequenceSubtype, StringSubtype, TypeSignature};
 use crate::vm::SymbolicExpression;
 
 /// to-consensus-buff? admits exactly one argument:
@@ -39,3 +39,56 @@ pub fn check_special_from_consensus_buff(
     checker.type_check_expects(&args[1], context, &TypeSignature::max_buffer())?;
     TypeSignature::new_option(result_type).map_err(CheckError::from)
 }
+
+/// to-ascii? admits exactly one argument:
+///   * a buffer `(buff x)`
+///
+/// it returns an `(optional (string-ascii x))`
+pub fn check_special_to_ascii(
+    checker: &mut TypeChecker,
+    args: &[SymbolicExpression],
+    context: &TypingContext,
+) -> TypeResult {
+    check_argument_count(1, args)?;
+    let input_type = checker.type_check(&args[0], context)?;
+    match input_type {
+        TypeSignature::SequenceType(SequenceSubtype::BufferType(len)) => TypeSignature::new_option(
+            TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::ASCII(len))),
+        )
+        .map_err(CheckError::from),
+        _ => Err(CheckErrors::TypeError(TypeSignature::max_buffer(), input_type).into()),
+    }
+}
+
+/// string-to-buff admits exactly one argument:
+///   * a string, either `(string-ascii x)` or `(string-utf8 x)`
+///
+/// it returns a `(buff x)` for an ASCII string, and a `(buff (* 4 x))` for a
+/// UTF-8 string, since each character is encoded in at most 4 bytes.
+pub fn check_special_string_to_buff(
+    checker: &mut TypeChecker,
+    args: &[SymbolicExpression],
+    context: &TypingContext,
+) -> TypeResult {
+    check_argument_count(1, args)?;
+    let input_type = checker.type_check(&args[0], context)?;
+    let buffer_max_len = match input_type {
+        TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::ASCII(len))) => len,
+        TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::UTF8(len))) => {
+            BufferLength::try_from(u32::from(len).saturating_mul(4))?
+        }
+        _ => {
+            return Err(CheckErrors::UnionTypeError(
+                vec![
+                    TypeSignature::max_string_ascii(),
+                    TypeSignature::max_string_utf8(),
+                ],
+                input_type,
+            )
+            .into())
+        }
+    };
+    Ok(TypeSignature::SequenceType(SequenceSubtype::BufferType(
+        buffer_max_len,
+    )))
+}
@@ -161,6 +161,33 @@ fn check_special_get(
     }
 }
 
+fn check_special_get_optional(
+    checker: &mut TypeChecker,
+    args: &[SymbolicExpression],
+    context: &TypingContext,
+) -> TypeResult {
+    check_argument_count(2, args)?;
+
+    let field_to_get = args[0].match_atom().ok_or(CheckErrors::BadTupleFieldName)?;
+
+    let argument_type = checker.type_check(&args[1], context)?;
+
+    let tuple_type_sig = match argument_type {
+        TypeSignature::TupleType(tuple_type_sig) => tuple_type_sig,
+        TypeSignature::OptionalType(value_type_sig) => match *value_type_sig {
+            TypeSignature::TupleType(tuple_type_sig) => tuple_type_sig,
+            value_type_sig => return Err(CheckErrors::ExpectedTuple(value_type_sig).into()),
+        },
+        argument_type => return Err(CheckErrors::ExpectedTuple(argument_type).into()),
+    };
+
+    // an optional field is returned as-is, so that lookups can be chained
+    match inner_handle_tuple_get(&tuple_type_sig, field_to_get, checker)? {
+        field_type @ TypeSignature::OptionalType(_) => Ok(field_type),
+        field_type => Ok(TypeSignature::new_option(field_type)?),
+    }
+}
+
 fn check_special_merge(
     checker: &mut TypeChecker,
     args: &[SymbolicExpression],
@@ -185,6 +212,20 @@ fn check_special_merge(
         update.len(),
     )?;
 
+    if context.clarity_version >= ClarityVersion::Clarity3 {
+        // In Clarity3, `merge` updates a record: `update` may only set fields of `base`, to
+        //  values admitted by their types, so the result has `base`'s type.
+        for (field_name, update_type) in update.get_type_map().iter() {
+            let base_type = base.field_type(field_name).ok_or_else(|| {
+                CheckErrors::NoSuchTupleField(field_name.to_string(), base.clone())
+            })?;
+            if !base_type.admits_type(&StacksEpochId::Epoch21, update_type)? {
+                return Err(CheckErrors::TypeError(base_type.clone(), update_type.clone()).into());
+            }
+        }
+        return Ok(TypeSignature::TupleType(base));
+    }
+
     base.shallow_merge(&mut update);
     Ok(TypeSignature::TupleType(base))
 }
@@ -338,15 +379,46 @@ fn check_special_equals(
     Ok(TypeSignature::BoolType)
 }
 
+fn check_special_assert_eq(
+    checker: &mut TypeChecker,
+    args: &[SymbolicExpression],
+    context: &TypingContext,
+) -> TypeResult {
+    check_argument_count(2, args)?;
+
+    let arg_types = checker.type_check_all(args, context)?;
+    let (actual, expected) = (&arg_types[0], &arg_types[1]);
+    analysis_typecheck_cost(checker, actual, expected)?;
+    TypeSignature::least_supertype(&StacksEpochId::Epoch21, actual, expected)
+        .map_err(|_| CheckErrors::TypeError(expected.clone(), actual.clone()))?;
+
+    Ok(TypeSignature::new_response(
+        TypeSignature::BoolType,
+        TypeSignature::NoType,
+    )?)
+}
+
 fn check_special_if(
     checker: &mut TypeChecker,
     args: &[SymbolicExpression],
     context: &TypingContext,
 ) -> TypeResult {
-    check_argument_count(3, args)?;
+    if context.clarity_version >= ClarityVersion::Clarity3 {
+        check_arguments_at_least(2, args)?;
+        check_arguments_at_most(3, args)?;
+    } else {
+        check_argument_count(3, args)?;
+    }
 
     checker.type_check_expects(&args[0], context, &TypeSignature::BoolType)?;
 
+    if args.len() == 2 {
+        // single-arm `if`: `(some expr)` when true, `none` when false.
+        runtime_cost(ClarityCostFunction::AnalysisOptionCons, checker, 0)?;
+        let then_type = checker.type_check(&args[1], context)?;
+        return Ok(TypeSignature::new_option(then_type)?);
+    }
+
     let arg_types = checker.type_check_all(&args[1..], context)?;
 
     let expr1 = &arg_types[0];
@@ -701,7 +773,8 @@ impl TypedNativeFunction {
         use self::TypedNativeFunction::{Simple, Special};
         use crate::vm::functions::NativeFunctions::*;
         match function {
-            Add | Subtract | Divide | Multiply | BitwiseOr | BitwiseAnd | BitwiseXor2 => {
+            Add | Multiply => Simple(SimpleNativeFunction(FunctionType::ArithmeticVariadicOrList)),
+            Subtract | Divide | MulDiv | BitwiseOr | BitwiseAnd | BitwiseXor2 => {
                 Simple(SimpleNativeFunction(FunctionType::ArithmeticVariadic))
             }
             CmpGeq | CmpLeq | CmpLess | CmpGreater => {
@@ -718,6 +791,10 @@ impl TypedNativeFunction {
             Modulo | Power | BitwiseXor => {
                 Simple(SimpleNativeFunction(FunctionType::ArithmeticBinary))
             }
+            BitCount | LeadingZeros => Simple(SimpleNativeFunction(FunctionType::UnionArgs(
+                vec![TypeSignature::IntType, TypeSignature::UIntType],
+                TypeSignature::UIntType,
+            ))),
             And | Or => Simple(SimpleNativeFunction(FunctionType::Variadic(
                 TypeSignature::BoolType,
                 TypeSignature::BoolType,
@@ -802,46 +879,11 @@ impl TypedNativeFunction {
                 )],
                 returns: TypeSignature::BoolType,
             }))),
-            Hash160 => Simple(SimpleNativeFunction(FunctionType::UnionArgs(
-                vec![
-                    TypeSignature::max_buffer(),
-                    TypeSignature::UIntType,
-                    TypeSignature::IntType,
-                ],
-                BUFF_20.clone(),
-            ))),
-            Sha256 => Simple(SimpleNativeFunction(FunctionType::UnionArgs(
-                vec![
-                    TypeSignature::max_buffer(),
-                    TypeSignature::UIntType,
-                    TypeSignature::IntType,
-                ],
-                BUFF_32.clone(),
-            ))),
-            Sha512Trunc256 => Simple(SimpleNativeFunction(FunctionType::UnionArgs(
-                vec![
-                    TypeSignature::max_buffer(),
-                    TypeSignature::UIntType,
-                    TypeSignature::IntType,
-                ],
-                BUFF_32.clone(),
-            ))),
-            Sha512 => Simple(SimpleNativeFunction(FunctionType::UnionArgs(
-                vec![
-                    TypeSignature::max_buffer(),
-                    TypeSignature::UIntType,
-                    TypeSignature::IntType,
-                ],
-                BUFF_64.clone(),
-            ))),
-            Keccak256 => Simple(SimpleNativeFunction(FunctionType::UnionArgs(
-                vec![
-                    TypeSignature::max_buffer(),
-                    TypeSignature::UIntType,
-                    TypeSignature::IntType,
-                ],
-                BUFF_32.clone(),
-            ))),
+            Hash160 => Simple(SimpleNativeFunction(FunctionType::Hash(BUFF_20.clone()))),
+            Sha256 => Simple(SimpleNativeFunction(FunctionType::Hash(BUFF_32.clone()))),
+            Sha512Trunc256 => Simple(SimpleNativeFunction(FunctionType::Hash(BUFF_32.clone()))),
+            Sha512 => Simple(SimpleNativeFunction(FunctionType::Hash(BUFF_64.clone()))),
+            Keccak256 => Simple(SimpleNativeFunction(FunctionType::Hash(BUFF_32.clone()))),
             Secp256k1Recover => Special(SpecialNativeFunction(&check_secp256k1_recover)),
             Secp256k1Verify => Special(SpecialNativeFunction(&check_secp256k1_verify)),
             GetStxBalance => Simple(SimpleNativeFunction(FunctionType::Fixed(FixedFunction {
@@ -937,6 +979,7 @@ impl TypedNativeFunction {
             FetchVar => Special(SpecialNativeFunction(&check_special_fetch_var)),
             SetVar => Special(SpecialNativeFunction(&check_special_set_var)),
             Map => Special(SpecialNativeFunction(&sequences::check_special_map)),
+            MapTry => Special(SpecialNativeFunction(&sequences::check_special_map_try)),
             Filter => Special(SpecialNativeFunction(&sequences::check_special_filter)),
             Fold => Special(SpecialNativeFunction(&sequences::check_special_fold)),
             Append => Special(SpecialNativeFunction(&sequences::check_special_append)),
@@ -951,6 +994,8 @@ impl TypedNativeFunction {
             }
             Slice => Special(SpecialNativeFunction(&sequences::check_special_slice)),
             ReplaceAt => Special(SpecialNativeFunction(&sequences::check_special_replace_at)),
+            Sort => Special(SpecialNativeFunction(&sequences::check_special_sort)),
+            AssertEq => Special(SpecialNativeFunction(&check_special_assert_eq)),
             ListCons => Special(SpecialNativeFunction(&check_special_list_cons)),
             FetchEntry => Special(SpecialNativeFunction(&maps::check_special_fetch_entry)),
             SetEntry => Special(SpecialNativeFunction(&maps::check_special_set_entry)),
@@ -958,6 +1003,7 @@ impl TypedNativeFunction {
             DeleteEntry => Special(SpecialNativeFunction(&maps::check_special_delete_entry)),
             TupleCons => Special(SpecialNativeFunction(&check_special_tuple_cons)),
             TupleGet => Special(SpecialNativeFunction(&check_special_get)),
+            TupleGetOptional => Special(SpecialNativeFunction(&check_special_get_optional)),
             TupleMerge => Special(SpecialNativeFunction(&check_special_merge)),
             Begin => Special(SpecialNativeFunction(&check_special_begin)),
             Print => Special(SpecialNativeFunction(&check_special_print)),
@@ -971,6 +1017,9 @@ impl TypedNativeFunction {
             ConsOkay => Special(SpecialNativeFunction(&options::check_special_okay)),
             ConsError => Special(SpecialNativeFunction(&options::check_special_error)),
             DefaultTo => Special(SpecialNativeFunction(&options::check_special_default_to)),
+            UnwrapOrElse => Special(SpecialNativeFunction(
+                &options::check_special_unwrap_or_else,
+            )),
             Asserts => Special(SpecialNativeFunction(&options::check_special_asserts)),
             UnwrapRet => Special(SpecialNativeFunction(&options::check_special_unwrap_or_ret)),
             UnwrapErrRet => Special(SpecialNativeFunction(
@@ -991,6 +1040,10 @@ impl TypedNativeFunction {
             FromConsensusBuff => Special(SpecialNativeFunction(
                 &conversions::check_special_from_consensus_buff,
             )),
+            ToAscii => Special(SpecialNativeFunction(&conversions::check_special_to_ascii)),
+            StringToBuff => Special(SpecialNativeFunction(
+                &conversions::check_special_string_to_buff,
+            )),
         }
     }
 }
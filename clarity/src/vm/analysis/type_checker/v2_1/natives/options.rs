@@ -20,6 +20,7 @@ use crate::vm::representations::{ClarityName, SymbolicExpression};
 use crate::vm::types::TypeSignature;
 use crate::vm::ClarityVersion;
 
+use super::sequences::get_simple_native_or_user_define;
 use super::{
     check_argument_count, check_arguments_at_least, no_type, CheckError, CheckErrors, TypeChecker,
     TypeResult,
@@ -129,6 +130,33 @@ pub fn check_special_default_to(
     }
 }
 
+pub fn check_special_unwrap_or_else(
+    checker: &mut TypeChecker,
+    args: &[SymbolicExpression],
+    context: &TypingContext,
+) -> TypeResult {
+    check_argument_count(2, args)?;
+
+    let input = checker.type_check(&args[0], context)?;
+    let fallback_name = args[1]
+        .match_atom()
+        .ok_or(CheckErrors::NonFunctionApplication)?;
+    // like `map`, the fallback may only be a simple native or a defined function.
+    let function_type = get_simple_native_or_user_define(fallback_name, checker)?;
+    let fallback =
+        function_type.check_args(checker, &[], context.epoch, context.clarity_version)?;
+
+    analysis_typecheck_cost(checker, &fallback, &input)?;
+
+    if let TypeSignature::OptionalType(input_type) = input {
+        let contained_type = *input_type;
+        TypeSignature::least_supertype(&StacksEpochId::Epoch21, &fallback, &contained_type)
+            .map_err(|_| CheckErrors::DefaultTypesMustMatch(fallback, contained_type).into())
+    } else {
+        Err(CheckErrors::ExpectedOptionalType(input).into())
+    }
+}
+
 pub fn check_special_asserts(
     checker: &mut TypeChecker,
     args: &[SymbolicExpression],
@@ -19,7 +19,7 @@ use stacks_common::types::StacksEpochId;
 use crate::vm::functions::NativeFunctions;
 use crate::vm::representations::{SymbolicExpression, SymbolicExpressionType};
 pub use crate::vm::types::signatures::{BufferLength, ListTypeData, StringUTF8Length, BUFF_1};
-use crate::vm::types::{FunctionType, TypeSignature};
+use crate::vm::types::{FunctionType, SequenceSubtype, TypeSignature};
 use crate::vm::types::{SequenceSubtype::*, StringSubtype::*};
 use crate::vm::types::{Value, MAX_VALUE_SIZE};
 use std::convert::TryFrom;
@@ -35,7 +35,7 @@ use crate::vm::costs::cost_functions::ClarityCostFunction;
 use crate::vm::costs::{analysis_typecheck_cost, cost_functions, runtime_cost};
 use crate::vm::ClarityVersion;
 
-fn get_simple_native_or_user_define(
+pub fn get_simple_native_or_user_define(
     function_name: &str,
     checker: &mut TypeChecker,
 ) -> CheckResult<FunctionType> {
@@ -113,6 +113,54 @@ pub fn check_special_map(
         .map_err(|_| CheckErrors::ConstructedListTooLarge.into())
 }
 
+pub fn check_special_map_try(
+    checker: &mut TypeChecker,
+    args: &[SymbolicExpression],
+    context: &TypingContext,
+) -> TypeResult {
+    check_argument_count(2, args)?;
+
+    let function_name = args[0]
+        .match_atom()
+        .ok_or(CheckErrors::NonFunctionApplication)?;
+    // like `map`, only native or defined functions may be applied.
+    let function_type = get_simple_native_or_user_define(function_name, checker)?;
+    runtime_cost(
+        ClarityCostFunction::AnalysisIterableFunc,
+        checker,
+        args.len(),
+    )?;
+
+    let argument_type = checker.type_check(&args[1], context)?;
+    let (entry_type, len) = match argument_type {
+        TypeSignature::SequenceType(ListType(list_data)) => list_data.destruct(),
+        TypeSignature::SequenceType(BufferType(buffer_data)) => {
+            (TypeSignature::min_buffer(), buffer_data.into())
+        }
+        TypeSignature::SequenceType(StringType(ASCII(ascii_data))) => {
+            (TypeSignature::min_string_ascii(), ascii_data.into())
+        }
+        TypeSignature::SequenceType(StringType(UTF8(utf8_data))) => {
+            (TypeSignature::min_string_utf8(), utf8_data.into())
+        }
+        _ => return Err(CheckErrors::ExpectedSequence(argument_type).into()),
+    };
+
+    let mapped_type = function_type.check_args(
+        checker,
+        &[entry_type],
+        context.epoch,
+        context.clarity_version,
+    )?;
+    let (ok_type, err_type) = match mapped_type {
+        TypeSignature::ResponseType(response_types) => *response_types,
+        _ => return Err(CheckErrors::ExpectedResponseType(mapped_type).into()),
+    };
+    let ok_list =
+        TypeSignature::list_of(ok_type, len).map_err(|_| CheckErrors::ConstructedListTooLarge)?;
+    Ok(TypeSignature::new_response(ok_list, err_type)?)
+}
+
 pub fn check_special_filter(
     checker: &mut TypeChecker,
     args: &[SymbolicExpression],
@@ -292,6 +340,23 @@ pub fn check_special_append(
     }
 }
 
+/// The value of `expr` if it is a literal uint. Clarity3 also accepts a constant defined
+///  as a literal uint.
+fn static_uint(checker: &TypeChecker, expr: &SymbolicExpression) -> Option<u128> {
+    match expr.expr {
+        SymbolicExpressionType::LiteralValue(Value::UInt(value)) => Some(value),
+        SymbolicExpressionType::Atom(ref name)
+            if checker.clarity_version >= ClarityVersion::Clarity3 =>
+        {
+            match checker.contract_context.get_literal_constant(name) {
+                Some(Value::UInt(value)) => Some(*value),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
 pub fn check_special_as_max_len(
     checker: &mut TypeChecker,
     args: &[SymbolicExpression],
@@ -299,9 +364,9 @@ pub fn check_special_as_max_len(
 ) -> TypeResult {
     check_argument_count(2, args)?;
 
-    let expected_len = match args[1].expr {
-        SymbolicExpressionType::LiteralValue(Value::UInt(expected_len)) => expected_len,
-        _ => {
+    let expected_len = match static_uint(checker, &args[1]) {
+        Some(expected_len) => expected_len,
+        None => {
             let expected_len_type = checker.type_check(&args[1], context)?;
             return Err(CheckErrors::TypeError(TypeSignature::UIntType, expected_len_type).into());
         }
@@ -429,9 +494,7 @@ pub fn check_special_slice(
     // Check sequence
     let seq_type = checker.type_check(&args[0], context)?;
     let seq = match seq_type {
-        TypeSignature::SequenceType(seq) => {
-            TypeSignature::new_option(TypeSignature::SequenceType(seq))?
-        }
+        TypeSignature::SequenceType(seq) => seq,
         _ => return Err(CheckErrors::ExpectedSequence(seq_type).into()),
     };
 
@@ -440,7 +503,37 @@ pub fn check_special_slice(
     // Check right position argument
     checker.type_check_expects(&args[2], context, &TypeSignature::UIntType)?;
 
-    Ok(seq)
+    // from Clarity3, with static positions, a slice holds at most `right - left` elements
+    let seq = if checker.clarity_version >= ClarityVersion::Clarity3 {
+        match (
+            static_uint(checker, &args[1]),
+            static_uint(checker, &args[2]),
+        ) {
+            (Some(left), Some(right)) if left <= right => shorten_sequence(seq, right - left)?,
+            _ => seq,
+        }
+    } else {
+        seq
+    };
+
+    Ok(TypeSignature::new_option(TypeSignature::SequenceType(seq))?)
+}
+
+/// The type of a sequence of the same kind as `seq`, with a maximum length of at most
+///  `max_len`.
+fn shorten_sequence(seq: SequenceSubtype, max_len: u128) -> Result<SequenceSubtype, CheckErrors> {
+    let cap = |len: u32| u32::try_from(max_len).map_or(len, |max_len| len.min(max_len));
+    let shortened = match seq {
+        ListType(list) => {
+            let len = cap(list.get_max_len());
+            let (entry_type, _) = list.destruct();
+            ListType(ListTypeData::new_list(entry_type, len)?)
+        }
+        BufferType(len) => BufferType(BufferLength::try_from(cap(u32::from(len)))?),
+        StringType(ASCII(len)) => StringType(ASCII(BufferLength::try_from(cap(u32::from(len)))?)),
+        StringType(UTF8(len)) => StringType(UTF8(StringUTF8Length::try_from(cap(u32::from(len)))?)),
+    };
+    Ok(shortened)
 }
 
 /// This function type checks the Clarity2 function `replace-at?`.
@@ -467,3 +560,19 @@ pub fn check_special_replace_at(
     let final_type = TypeSignature::new_option(input_type)?;
     Ok(final_type)
 }
+
+/// This function type checks the Clarity3 function `sort`. Lists are homogeneous,
+///  so any list can be sorted, and the result has the same type as the input.
+pub fn check_special_sort(
+    checker: &mut TypeChecker,
+    args: &[SymbolicExpression],
+    context: &TypingContext,
+) -> TypeResult {
+    check_argument_count(1, args)?;
+
+    let input_type = checker.type_check(&args[0], context)?;
+    match &input_type {
+        TypeSignature::SequenceType(ListType(_)) => Ok(input_type),
+        _ => Err(CheckErrors::ExpectedListApplication.into()),
+    }
+}
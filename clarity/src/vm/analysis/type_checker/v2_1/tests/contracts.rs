@@ -506,6 +506,112 @@ fn test_names_tokens_contracts_bad(#[case] version: ClarityVersion, #[case] epoc
     });
 }
 
+#[apply(test_clarity_versions_contracts)]
+fn test_contract_call_checked_against_deployed(
+    #[case] version: ClarityVersion,
+    #[case] epoch: StacksEpochId,
+) {
+    let token_id = QualifiedContractIdentifier::local("token").unwrap();
+    let token = "(define-public (transfer (amount uint) (to principal)) (ok amount))
+         (define-read-only (balance (who principal)) u0)
+         (define-private (burn (amount uint)) amount)";
+
+    let mut marf = MemoryBackingStore::new();
+    let mut db = marf.as_analysis_db();
+    let mut token_contract = parse(&token_id, token, version, epoch).unwrap();
+    db.execute(|db| {
+        db.test_insert_contract_hash(&token_id);
+        type_check(&token_id, &mut token_contract, db, true)
+    })
+    .unwrap();
+
+    let caller_id = QualifiedContractIdentifier::local("caller").unwrap();
+    let mut check_call = |call: &str| {
+        let caller = format!("(define-public (go) {})", call);
+        let mut caller_contract = parse(&caller_id, &caller, version, epoch).unwrap();
+        db.execute(|db| type_check(&caller_id, &mut caller_contract, db, false))
+            .map_err(|e| e.err)
+    };
+
+    check_call("(contract-call? .token transfer u1 tx-sender)").unwrap();
+    check_call("(begin (contract-call? .token balance tx-sender) (ok u0))").unwrap();
+
+    let bad = [
+        "(contract-call? .token transfer 1 tx-sender)",
+        "(contract-call? .token transfer u1)",
+        "(contract-call? .token burn u1)",
+        "(contract-call? .token mint u1)",
+        "(contract-call? .no-such-token transfer u1 tx-sender)",
+    ];
+    let expected = [
+        CheckErrors::TypeError(TypeSignature::UIntType, TypeSignature::IntType),
+        CheckErrors::IncorrectArgumentCount(2, 1),
+        CheckErrors::NoSuchPublicFunction(token_id.to_string(), "burn".into()),
+        CheckErrors::NoSuchPublicFunction(token_id.to_string(), "mint".into()),
+        CheckErrors::NoSuchContract(
+            QualifiedContractIdentifier::local("no-such-token")
+                .unwrap()
+                .to_string(),
+        ),
+    ];
+    for (call, expected) in bad.iter().zip(expected.iter()) {
+        assert_eq!(&check_call(call).unwrap_err(), expected, "{}", call);
+    }
+}
+
+#[apply(test_clarity_versions_contracts)]
+fn test_contract_call_result_is_callee_response(
+    #[case] version: ClarityVersion,
+    #[case] epoch: StacksEpochId,
+) {
+    let vault_id = QualifiedContractIdentifier::local("vault").unwrap();
+    let vault = "(define-public (withdraw (amount uint))
+           (if (> amount u10) (err \"too-large\") (ok amount)))";
+
+    let mut marf = MemoryBackingStore::new();
+    let mut db = marf.as_analysis_db();
+    let mut vault_contract = parse(&vault_id, vault, version, epoch).unwrap();
+    db.execute(|db| {
+        db.test_insert_contract_hash(&vault_id);
+        type_check(&vault_id, &mut vault_contract, db, true)
+    })
+    .unwrap();
+
+    let caller_id = QualifiedContractIdentifier::local("caller").unwrap();
+    let mut check_caller = |body: &str| {
+        let caller = format!("(define-public (go) {})", body);
+        let mut caller_contract = parse(&caller_id, &caller, version, epoch).unwrap();
+        db.execute(|db| type_check(&caller_id, &mut caller_contract, db, false))
+            .map_err(|e| e.err)
+    };
+
+    // the callee's err comes back as a value the caller can inspect and recover from
+    check_caller(
+        "(ok (match (contract-call? .vault withdraw u20)
+               amount amount
+               reason (len reason)))",
+    )
+    .unwrap();
+
+    let bad = [
+        "(ok (match (contract-call? .vault withdraw u20) amount amount reason reason))",
+        "(ok (+ 1 (unwrap-err-panic (contract-call? .vault withdraw u20))))",
+    ];
+    let expected = [
+        CheckErrors::MatchArmsMustMatch(
+            TypeSignature::UIntType,
+            TypeSignature::from_string("(string-ascii 9)", version, epoch),
+        ),
+        CheckErrors::TypeError(
+            TypeSignature::IntType,
+            TypeSignature::from_string("(string-ascii 9)", version, epoch),
+        ),
+    ];
+    for (body, expected) in bad.iter().zip(expected.iter()) {
+        assert_eq!(&check_caller(body).unwrap_err(), expected, "{}", body);
+    }
+}
+
 #[test]
 fn test_bad_map_usage() {
     let bad_fetch = "(define-map tokens { account: principal } { balance: int })
@@ -2760,7 +2866,9 @@ fn clarity_trait_experiments_downcast_literal_2(
         })
         .unwrap_err();
     match version {
-        ClarityVersion::Clarity2 => assert!(err.starts_with("ExpectedCallableType(PrincipalType)")),
+        ClarityVersion::Clarity2 | ClarityVersion::Clarity3 => {
+            assert!(err.starts_with("ExpectedCallableType(PrincipalType)"))
+        }
         ClarityVersion::Clarity1 => {
             assert!(err.starts_with("TraitReferenceUnknown(\"principal-value\")"))
         }
@@ -2955,7 +3063,9 @@ fn clarity_trait_experiments_trait_cast_incompatible(
                 assert!(err.starts_with("TypeError(CallableType(Trait(TraitIdentifier"))
             }
         }
-        ClarityVersion::Clarity2 => assert!(err.starts_with("IncompatibleTrait")),
+        ClarityVersion::Clarity2 | ClarityVersion::Clarity3 => {
+            assert!(err.starts_with("IncompatibleTrait"))
+        }
     }
 }
 
@@ -21,18 +21,22 @@ use rstest_reuse::{self, *};
 
 use crate::vm::analysis::errors::CheckErrors;
 use crate::vm::analysis::mem_type_check as mem_run_analysis;
+use crate::vm::analysis::run_analysis_collecting_errors;
 use crate::vm::analysis::type_checker::v2_1::{TypeChecker, TypeResult, TypingContext};
 use crate::vm::analysis::types::ContractAnalysis;
 use crate::vm::analysis::AnalysisDatabase;
 use crate::vm::ast::errors::ParseErrors;
 use crate::vm::ast::{build_ast, parse};
 use crate::vm::contexts::OwnedEnvironment;
+use crate::vm::costs::LimitedCostTracker;
+use crate::vm::diagnostic::DiagnosableError;
 use crate::vm::representations::SymbolicExpression;
 use crate::vm::types::{
     BufferLength, FixedFunction, FunctionType, PrincipalData, QualifiedContractIdentifier,
-    TraitIdentifier, TypeSignature, Value, BUFF_1, BUFF_20, BUFF_21, BUFF_32, BUFF_64,
+    TraitIdentifier, TupleTypeSignature, TypeSignature, Value, BUFF_1, BUFF_20, BUFF_21, BUFF_32,
+    BUFF_64, MAX_TYPE_DEPTH,
 };
-use crate::vm::{execute_v2, ClarityName};
+use crate::vm::{execute_v2, execute_v3, ClarityName};
 use stacks_common::types::StacksEpochId;
 
 use crate::vm::database::MemoryBackingStore;
@@ -83,6 +87,14 @@ fn type_check_helper_v1(exp: &str) -> TypeResult {
         .map(|(type_sig_opt, _)| type_sig_opt.unwrap())
 }
 
+fn mem_type_check_v3(exp: &str) -> CheckResult<(Option<TypeSignature>, ContractAnalysis)> {
+    mem_run_analysis(exp, ClarityVersion::Clarity3, StacksEpochId::latest())
+}
+
+fn type_check_helper_v3(exp: &str) -> TypeResult {
+    mem_type_check_v3(exp).map(|(type_sig_opt, _)| type_sig_opt.unwrap())
+}
+
 fn buff_type(size: u32) -> TypeSignature {
     TypeSignature::SequenceType(BufferType(size.try_into().unwrap())).into()
 }
@@ -236,7 +248,82 @@ fn test_to_consensus_buff() {
     }
 
     for (bad_test, expected) in bad.iter() {
-        assert_eq!(expected, &type_check_helper(&bad_test).unwrap_err().err);
+        assert_eq!(expected, &type_check_helper(bad_test).unwrap_err().err);
+    }
+}
+
+#[test]
+fn test_to_ascii_and_string_to_buff() {
+    let good = [
+        ("(to-ascii? 0x616263)", "(optional (string-ascii 3))"),
+        ("(to-ascii? 0x61ff63)", "(optional (string-ascii 3))"),
+        ("(to-ascii? 0x)", "(optional (string-ascii 0))"),
+        ("(string-to-buff \"abc\")", "(buff 3)"),
+        ("(string-to-buff u\"ab\")", "(buff 8)"),
+        (
+            "(to-ascii? (string-to-buff \"abc\"))",
+            "(optional (string-ascii 3))",
+        ),
+        (
+            "(define-private (my-func (x (string-utf8 262144)))
+           (string-to-buff x))
+          (my-func u\"\\u{e9}\")
+         ",
+            "(buff 1048576)",
+        ),
+    ];
+
+    let bad = [
+        ("(to-ascii?)", CheckErrors::IncorrectArgumentCount(1, 0)),
+        (
+            "(to-ascii? 0x00 0x00)",
+            CheckErrors::IncorrectArgumentCount(1, 2),
+        ),
+        (
+            "(to-ascii? \"abc\")",
+            CheckErrors::TypeError(
+                TypeSignature::max_buffer(),
+                TypeSignature::SequenceType(StringType(ASCII(3_u32.try_into().unwrap()))),
+            ),
+        ),
+        (
+            "(string-to-buff 0x00)",
+            CheckErrors::UnionTypeError(
+                vec![
+                    TypeSignature::max_string_ascii(),
+                    TypeSignature::max_string_utf8(),
+                ],
+                TypeSignature::SequenceType(BufferType(1_u32.try_into().unwrap())),
+            ),
+        ),
+    ];
+
+    for (good_test, expected) in good.iter() {
+        let type_result = type_check_helper_v3(good_test).unwrap();
+        assert_eq!(expected, &type_result.to_string());
+
+        assert!(
+            type_result
+                .admits(
+                    &StacksEpochId::Epoch21,
+                    &execute_v3(good_test).unwrap().unwrap()
+                )
+                .unwrap(),
+            "The analyzed type must admit the evaluated type"
+        );
+    }
+
+    for (bad_test, expected) in bad.iter() {
+        assert_eq!(expected, &type_check_helper_v3(bad_test).unwrap_err().err);
+    }
+
+    for type_check in [type_check_helper_v1, type_check_helper] {
+        for test in ["(to-ascii? 0x61)", "(string-to-buff \"a\")"] {
+            assert!(matches!(
+                type_check(test).unwrap_err().err,
+                CheckErrors::UnknownFunction(_)
+            ));
+        }
     }
 }
 
@@ -430,12 +517,14 @@ fn test_stx_ops() {
         "(stx-burn? u10 'SM2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQVX8X0G)",
         r#"(stx-transfer? u10 tx-sender 'SM2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQVX8X0G)"#,
         r#"(stx-transfer-memo? u10 tx-sender 'SM2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQVX8X0G 0x0102)"#,
+        r#"(stx-transfer-memo? u10 tx-sender 'SM2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQVX8X0G 0x00000000000000000000000000000000000000000000000000000000000000000000)"#,
         "(stx-get-balance 'SM2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQVX8X0G)",
     ];
     let expected = [
         "(response bool uint)",
         "(response bool uint)",
         "(response bool uint)",
+        "(response bool uint)",
         "uint",
     ];
 
@@ -454,6 +543,7 @@ fn test_stx_ops() {
         r#"(stx-transfer-memo? u4 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR true 0x0102)"#,
         r#"(stx-transfer-memo? u10 tx-sponsor? 'SM2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQVX8X0G 0x0102)"#,
         r#"(stx-transfer-memo? u10 tx-sender 'SM2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQVX8X0G)"#,  // valid arguments for stx-transfer
+        r#"(stx-transfer-memo? u10 tx-sender 'SM2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQVX8X0G 0x0000000000000000000000000000000000000000000000000000000000000000000000)"#,
         "(stx-burn? u4)",
         "(stx-burn? 4 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR)",
         "(stx-burn? u4 true)",
@@ -482,6 +572,10 @@ fn test_stx_ops() {
         CheckErrors::TypeError(PrincipalType, BoolType),
         CheckErrors::TypeError(PrincipalType, OptionalType(Box::from(PrincipalType))),
         CheckErrors::IncorrectArgumentCount(4, 3),
+        CheckErrors::TypeError(
+            SequenceType(BufferType(BufferLength::try_from(34_u32).unwrap())),
+            SequenceType(BufferType(BufferLength::try_from(35_u32).unwrap())),
+        ),
         CheckErrors::IncorrectArgumentCount(2, 1),
         CheckErrors::TypeError(UIntType, IntType),
         CheckErrors::TypeError(PrincipalType, BoolType),
@@ -834,6 +928,35 @@ fn test_bitwise_bad_checks() {
     }
 }
 
+#[test]
+fn test_bit_count_and_leading_zeros_checks() {
+    let good = ["(bit-count u7)", "(leading-zeros -1)"];
+    for good_test in good.iter() {
+        assert_eq!(
+            "uint",
+            &format!("{}", type_check_helper_v3(good_test).unwrap())
+        );
+    }
+
+    let bad = ["(bit-count true)", "(leading-zeros 1 2)"];
+    let bad_expected = [
+        CheckErrors::UnionTypeError(vec![IntType, UIntType], BoolType),
+        CheckErrors::IncorrectArgumentCount(1, 2),
+    ];
+    for (bad_test, expected) in bad.iter().zip(bad_expected.iter()) {
+        assert_eq!(expected, &type_check_helper_v3(bad_test).unwrap_err().err);
+    }
+
+    for type_check in [type_check_helper_v1, type_check_helper] {
+        for good_test in good.iter() {
+            assert!(matches!(
+                type_check(good_test).unwrap_err().err,
+                CheckErrors::UnknownFunction(_)
+            ));
+        }
+    }
+}
+
 #[test]
 fn test_simple_arithmetic_checks() {
     let good = [
@@ -871,6 +994,58 @@ fn test_simple_arithmetic_checks() {
     }
 }
 
+#[test]
+fn test_arithmetic_over_lists() {
+    let good = [
+        "(+ (list 1 2 3))",
+        "(* (list u2 u3))",
+        "(+ 5 4 1)",
+        "(fold + (list 1 2 3) 0)",
+        "(map * (list (list u1 u2) (list u3)))",
+    ];
+    let expected = ["int", "uint", "int", "int", "(list 2 uint)"];
+    let bad = [
+        "(+ (list))",
+        "(* (list true))",
+        "(+ (list 1) (list 2))",
+        "(- (list 1 2 3))",
+    ];
+    let bad_expected = [
+        CheckErrors::UnionTypeError(vec![IntType, UIntType], TypeSignature::NoType),
+        CheckErrors::UnionTypeError(vec![IntType, UIntType], BoolType),
+        CheckErrors::UnionTypeError(
+            vec![IntType, UIntType],
+            TypeSignature::list_of(IntType, 1).unwrap(),
+        ),
+        CheckErrors::UnionTypeError(
+            vec![IntType, UIntType],
+            TypeSignature::list_of(IntType, 3).unwrap(),
+        ),
+    ];
+
+    for (good_test, expected) in good.iter().zip(expected.iter()) {
+        assert_eq!(
+            expected,
+            &format!("{}", type_check_helper_v3(good_test).unwrap())
+        );
+    }
+
+    for (bad_test, expected) in bad.iter().zip(bad_expected.iter()) {
+        assert_eq!(expected, &type_check_helper_v3(bad_test).unwrap_err().err);
+    }
+
+    // Clarity1 and Clarity2 only support the variadic form
+    for type_check in [type_check_helper_v1, type_check_helper] {
+        assert_eq!(
+            CheckErrors::UnionTypeError(
+                vec![IntType, UIntType],
+                TypeSignature::list_of(IntType, 3).unwrap(),
+            ),
+            type_check("(+ (list 1 2 3))").unwrap_err().err
+        );
+    }
+}
+
 #[test]
 fn test_simple_hash_checks() {
     let good = [
@@ -894,6 +1069,22 @@ fn test_simple_hash_checks() {
         "(buff 32)",
     ];
 
+    // Clarity3 hashes any other value over its consensus serialization
+    let good_v3 = [
+        "(hash160 true)",
+        "(sha256 (tuple (a 1) (b u2)))",
+        "(sha512 (some \"abc\"))",
+        "(sha512/256 (ok 1))",
+        "(keccak256 (list 1 2 3))",
+    ];
+    let expected_v3 = [
+        "(buff 20)",
+        "(buff 32)",
+        "(buff 64)",
+        "(buff 32)",
+        "(buff 32)",
+    ];
+
     let bad_types = [
         "(hash160 true)",
         "(sha256 false)",
@@ -914,6 +1105,13 @@ fn test_simple_hash_checks() {
         );
     }
 
+    for (good_test, expected) in good_v3.iter().zip(expected_v3.iter()) {
+        assert_eq!(
+            expected,
+            &format!("{}", type_check_helper_v3(good_test).unwrap())
+        );
+    }
+
     for bad_test in bad_types.iter() {
         assert!(match type_check_helper(&bad_test).unwrap_err().err {
             CheckErrors::UnionTypeError(_, _) => true,
@@ -965,6 +1163,42 @@ fn test_simple_ifs() {
     }
 }
 
+#[test]
+fn test_single_arm_ifs() {
+    let good = ["(if true 1)", "(if (> 1 2) \"abc\")", "(if true (some 1))"];
+    let expected = [
+        "(optional int)",
+        "(optional (string-ascii 3))",
+        "(optional (optional int))",
+    ];
+
+    let bad = ["(if)", "(if 0 1)", "(if true 1 2 3)"];
+    let bad_expected = [
+        CheckErrors::RequiresAtLeastArguments(2, 0),
+        CheckErrors::TypeError(BoolType, IntType),
+        CheckErrors::RequiresAtMostArguments(3, 4),
+    ];
+
+    for (good_test, expected) in good.iter().zip(expected.iter()) {
+        assert_eq!(
+            expected,
+            &format!("{}", type_check_helper_v3(good_test).unwrap())
+        );
+    }
+
+    for (bad_test, expected) in bad.iter().zip(bad_expected.iter()) {
+        assert_eq!(expected, &type_check_helper_v3(bad_test).unwrap_err().err);
+    }
+
+    // single-arm `if` is only available from Clarity3
+    for type_check in [type_check_helper_v1, type_check_helper] {
+        assert_eq!(
+            CheckErrors::IncorrectArgumentCount(3, 2),
+            type_check("(if true 1)").unwrap_err().err
+        );
+    }
+}
+
 #[test]
 fn test_simple_lets() {
     let good = [
@@ -1157,14 +1391,19 @@ fn test_eqs(#[case] version: ClarityVersion, #[case] epoch: StacksEpochId) {
         "(is-eq (list 1 2 3 4 5) (list 1 2 3 4 5 6 7))",
         "(is-eq (tuple (good 1) (bad 2)) (tuple (good 2) (bad 3)))",
         "(is-eq \"abcdef\" \"abc\" \"a\")",
+        "(is-eq 1)",
+        "(is-eq 1 1 1 1)",
+        "(is-eq (ok 1) (err 1))",
     ];
 
-    let expected = ["bool", "bool", "bool"];
+    let expected = ["bool", "bool", "bool", "bool", "bool", "bool"];
 
     let bad = [
         "(is-eq 1 2 false)",
         "(is-eq 1 2 3 (list 2))",
         "(is-eq (some 1) (some true))",
+        "(is-eq)",
+        "(is-eq (ok 1) (some 1))",
     ];
 
     let bad_expected = [
@@ -1174,6 +1413,11 @@ fn test_eqs(#[case] version: ClarityVersion, #[case] epoch: StacksEpochId) {
             TypeSignature::from_string("(optional bool)", version, epoch),
             TypeSignature::from_string("(optional int)", version, epoch),
         ),
+        CheckErrors::RequiresAtLeastArguments(1, 0),
+        CheckErrors::TypeError(
+            TypeSignature::new_option(IntType).unwrap(),
+            TypeSignature::new_response(IntType, TypeSignature::NoType).unwrap(),
+        ),
     ];
 
     for (good_test, expected) in good.iter().zip(expected.iter()) {
@@ -1299,6 +1543,25 @@ fn test_lists() {
     for (bad_test, expected) in bad.iter().zip(bad_expected.iter()) {
         assert_eq!(expected, &type_check_helper(&bad_test).unwrap_err().err);
     }
+
+    // mapping over parallel lists checks each against the callback's parameter types
+    let zip = "(define-private (zip (a int) (b uint)) { a: a, b: b })";
+    assert_eq!(
+        "(list 2 (tuple (a int) (b uint)))",
+        &format!(
+            "{}",
+            mem_type_check(&format!("{} (map zip (list 1 2 3) (list u1 u2))", zip))
+                .unwrap()
+                .0
+                .unwrap()
+        )
+    );
+    assert_eq!(
+        CheckErrors::TypeError(UIntType, IntType),
+        mem_type_check(&format!("{} (map zip (list 1 2 3) (list 1 2 3))", zip))
+            .unwrap_err()
+            .err
+    );
 }
 
 #[test]
@@ -1355,6 +1618,92 @@ fn test_buff() {
     }
 }
 
+#[test]
+fn test_literal_length_bounds() {
+    let good = [
+        "(define-data-var v (buff 4) 0x01020304)",
+        // escapes count by their decoded length: this is 3 bytes, not 4
+        "(define-data-var v (string-ascii 3) \"a\\nb\")",
+    ];
+    for good_test in good.iter() {
+        mem_type_check(good_test).unwrap();
+    }
+
+    let bad = [
+        "(define-data-var v (buff 4) 0x0102030405060708090a)",
+        "(define-data-var v (string-ascii 2) \"a\\nb\")",
+    ];
+    let bad_expected = [
+        (CheckErrors::TypeError(buff_type(4), buff_type(10)), 29),
+        (CheckErrors::TypeError(ascii_type(2), ascii_type(3)), 37),
+    ];
+    for (bad_test, (expected, column)) in bad.iter().zip(bad_expected.iter()) {
+        let err = mem_type_check(bad_test).unwrap_err();
+        assert_eq!(expected, &err.err);
+        // the error points at the offending literal
+        assert_eq!(err.diagnostic.spans.len(), 1);
+        assert_eq!(err.diagnostic.spans[0].start_line, 1);
+        assert_eq!(err.diagnostic.spans[0].start_column, *column);
+    }
+}
+
+#[test]
+fn test_native_arity() {
+    let good = [
+        "(list)",
+        "(+ 1)",
+        "(principal-construct? 0x16 0xfa6bf38ed557fe417333710d6033e9419391a320)",
+        "(principal-construct? 0x16 0xfa6bf38ed557fe417333710d6033e9419391a320 \"foo\")",
+    ];
+    for good_test in good.iter() {
+        type_check_helper_v3(good_test).unwrap();
+    }
+
+    let bad = [
+        "(>= 1)",
+        "(mod 1)",
+        "(mod 1 2 3)",
+        "(+)",
+        "(and)",
+        "(is-eq)",
+        "(unwrap! (some 1))",
+        "(define-map m int int) (map-get? m 1 2)",
+        "(define-data-var v int 0) (var-set v 1 2)",
+        "(principal-construct? 0x16)",
+        "(principal-construct? 0x16 0x00 \"foo\" \"bar\")",
+        // arity is checked before the arguments themselves
+        "(>= (+ 1 true))",
+    ];
+    let bad_expected = [
+        CheckErrors::IncorrectArgumentCount(2, 1),
+        CheckErrors::IncorrectArgumentCount(2, 1),
+        CheckErrors::IncorrectArgumentCount(2, 3),
+        CheckErrors::RequiresAtLeastArguments(1, 0),
+        CheckErrors::RequiresAtLeastArguments(1, 0),
+        CheckErrors::RequiresAtLeastArguments(1, 0),
+        CheckErrors::IncorrectArgumentCount(2, 1),
+        CheckErrors::IncorrectArgumentCount(2, 3),
+        CheckErrors::IncorrectArgumentCount(2, 3),
+        CheckErrors::RequiresAtLeastArguments(2, 1),
+        CheckErrors::RequiresAtMostArguments(3, 4),
+        CheckErrors::IncorrectArgumentCount(2, 1),
+    ];
+    for (bad_test, expected) in bad.iter().zip(bad_expected.iter()) {
+        assert_eq!(expected, &type_check_helper_v3(bad_test).unwrap_err().err);
+    }
+
+    // the error points at the offending application
+    let err = mem_run_analysis(
+        "(begin\n  (mod 1))",
+        ClarityVersion::Clarity3,
+        StacksEpochId::latest(),
+    )
+    .unwrap_err();
+    assert_eq!(err.diagnostic.spans.len(), 1);
+    assert_eq!(err.diagnostic.spans[0].start_line, 2);
+    assert_eq!(err.diagnostic.spans[0].start_column, 3);
+}
+
 #[test]
 fn test_buff_fold() {
     let good = [
@@ -1392,8 +1741,16 @@ fn test_buff_map() {
 
 #[test]
 fn test_native_as_max_len() {
-    let good = ["(as-max-len? (list 1 2 3 4) u5)"];
-    let expected = ["(optional (list 5 int))"];
+    let good = [
+        "(as-max-len? (list 1 2 3 4) u5)",
+        "(as-max-len? 0x0102 u8)",
+        "(as-max-len? 0x01020304 u2)",
+    ];
+    let expected = [
+        "(optional (list 5 int))",
+        "(optional (buff 8))",
+        "(optional (buff 2))",
+    ];
 
     for (good_test, expected) in good.iter().zip(expected.iter()) {
         assert_eq!(
@@ -1406,17 +1763,88 @@ fn test_native_as_max_len() {
         "(as-max-len? \"\" u1048577)",
         "(as-max-len? u\"\" u1048577)",
         "(as-max-len? 0x01 u1048577)",
+        // the bound must be a uint literal, so that the result type is static
+        "(let ((max u8)) (as-max-len? 0x01 max))",
+        "(as-max-len? 0x01 8)",
     ];
     let bad_expected = [
         CheckErrors::ValueTooLarge,
         CheckErrors::ValueTooLarge,
         CheckErrors::ValueTooLarge,
+        CheckErrors::TypeError(UIntType, UIntType),
+        CheckErrors::TypeError(UIntType, IntType),
     ];
     for (bad_test, expected) in bad.iter().zip(bad_expected.iter()) {
         assert_eq!(expected, &type_check_helper(&bad_test).unwrap_err().err);
     }
 }
 
+#[test]
+fn test_as_max_len_refines_argument_bounds() {
+    let narrow = "(define-private (take-short (b (buff 2))) b)
+        (define-private (pass-through (b (buff 8)))
+            (take-short (unwrap-panic (as-max-len? b u2))))";
+    mem_type_check(narrow).unwrap();
+
+    let unrefined = "(define-private (take-short (b (buff 2))) b)
+        (define-private (pass-through (b (buff 8)))
+            (take-short b))";
+    assert_eq!(
+        mem_type_check(unrefined).unwrap_err().err,
+        CheckErrors::TypeError(buff_type(2), buff_type(8))
+    );
+}
+
+#[test]
+fn test_as_max_len_constant_bound() {
+    let check = |program: &str| mem_type_check_v3(program).map(|(result, _)| result.unwrap());
+
+    // a constant defined as a literal uint can stand in for the literal
+    assert_eq!(
+        "(optional (list 3 int))",
+        &format!(
+            "{}",
+            check("(define-constant MAX-LEN u3) (as-max-len? (list 1 2) MAX-LEN)").unwrap()
+        )
+    );
+    assert_eq!(
+        "(optional (buff 8))",
+        &format!(
+            "{}",
+            check("(define-constant MAX-LEN u8) (as-max-len? 0x01 MAX-LEN)").unwrap()
+        )
+    );
+
+    // other expressions, even uint-valued ones, are not static
+    let bad = [
+        "(define-constant MAX-LEN (+ u1 u2)) (as-max-len? 0x01 MAX-LEN)",
+        "(define-data-var max-len uint u3) (as-max-len? 0x01 (var-get max-len))",
+        "(define-constant MAX-LEN 3) (as-max-len? 0x01 MAX-LEN)",
+    ];
+    let bad_expected = [
+        CheckErrors::TypeError(UIntType, UIntType),
+        CheckErrors::TypeError(UIntType, UIntType),
+        CheckErrors::TypeError(UIntType, IntType),
+    ];
+    for (bad_test, expected) in bad.iter().zip(bad_expected.iter()) {
+        assert_eq!(expected, &check(bad_test).unwrap_err().err);
+    }
+
+    // Clarity1 and Clarity2 only accept literals
+    for version in [ClarityVersion::Clarity1, ClarityVersion::Clarity2] {
+        assert_eq!(
+            CheckErrors::TypeError(UIntType, UIntType),
+            mem_run_analysis(
+                "(define-constant MAX-LEN u3) (as-max-len? 0x01 MAX-LEN)",
+                version,
+                StacksEpochId::Epoch21
+            )
+            .unwrap_err()
+            .err
+        );
+    }
+}
+
 #[test]
 fn test_buff_as_max_len() {
     let tests = [
@@ -1503,6 +1931,81 @@ fn test_slice_list() {
     }
 }
 
+#[test]
+fn test_sort() {
+    let good = [
+        "(sort (list 2 3 1))",
+        "(sort (list 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR 'S1G2081040G2081040G2081040G208105NK8PE5.contract))",
+        "(sort (list (some u1) none))",
+        "(sort (list))",
+    ];
+    let expected = [
+        "(list 3 int)",
+        "(list 2 principal)",
+        "(list 2 (optional uint))",
+        "(list 0 UnknownType)",
+    ];
+
+    for (good_test, expected) in good.iter().zip(expected.iter()) {
+        assert_eq!(
+            expected,
+            &format!("{}", type_check_helper_v3(good_test).unwrap())
+        );
+    }
+
+    let bad = [
+        "(sort (list 1 u2))",
+        "(sort 0x0102)",
+        "(sort (list 1) (list 2))",
+    ];
+    let bad_expected = [
+        CheckErrors::TypeError(IntType, UIntType),
+        CheckErrors::ExpectedListApplication,
+        CheckErrors::IncorrectArgumentCount(1, 2),
+    ];
+    for (bad_test, expected) in bad.iter().zip(bad_expected.iter()) {
+        assert_eq!(expected, &type_check_helper_v3(bad_test).unwrap_err().err);
+    }
+
+    for type_check in [type_check_helper_v1, type_check_helper] {
+        assert!(matches!(
+            type_check("(sort (list 2 3 1))").unwrap_err().err,
+            CheckErrors::UnknownFunction(_)
+        ));
+    }
+}
+
+#[test]
+fn test_assert_eq() {
+    let good = [
+        "(assert-eq! (+ 1 2) 3)",
+        "(assert-eq! (list 1 2) (list 1 2 3))",
+        "(assert-eq! none (some u1))",
+    ];
+    for good_test in good.iter() {
+        assert_eq!(
+            "(response bool UnknownType)",
+            &format!("{}", type_check_helper_v3(good_test).unwrap())
+        );
+    }
+
+    let bad = ["(assert-eq! 1 u1)", "(assert-eq! 1)"];
+    let bad_expected = [
+        CheckErrors::TypeError(UIntType, IntType),
+        CheckErrors::IncorrectArgumentCount(2, 1),
+    ];
+    for (bad_test, expected) in bad.iter().zip(bad_expected.iter()) {
+        assert_eq!(expected, &type_check_helper_v3(bad_test).unwrap_err().err);
+    }
+
+    for type_check in [type_check_helper_v1, type_check_helper] {
+        assert!(matches!(
+            type_check("(assert-eq! 1 1)").unwrap_err().err,
+            CheckErrors::UnknownFunction(_)
+        ));
+    }
+}
+
 #[test]
 fn test_slice_buff() {
     let good = [
@@ -1594,9 +2097,51 @@ fn test_slice_utf8() {
         CheckErrors::TypeError(UIntType, IntType),
         CheckErrors::IncorrectArgumentCount(3, 2),
     ];
-    for (bad_test, expected) in bad.iter().zip(bad_expected.iter()) {
-        assert_eq!(expected, &type_check_helper(&bad_test).unwrap_err().err);
+    for (bad_test, expected) in bad.iter().zip(bad_expected.iter()) {
+        assert_eq!(expected, &type_check_helper(&bad_test).unwrap_err().err);
+    }
+}
+
+#[test]
+fn test_slice_static_positions() {
+    let good = [
+        "(slice? (list 2 3 4 5 6 7 8) u0 u3)",
+        "(slice? (list 2 3 4 5 6 7 8) u0 u0)",
+        "(slice? (list 2 3 4 5 6 7 8) u10 u3)",
+        "(slice? (list 2 3 4 5 6 7 8) u5 u10)",
+        "(let ((left u1)) (slice? (list 2 3 4 5 6 7 8) left u3))",
+        "(slice? 0x000102030405 u0 u3)",
+        "(slice? 0x000102030405 u3 u10)",
+        "(slice? \"blockstack\" u4 u5)",
+        "(slice? u\"blockstack\" u0 u5)",
+    ];
+    let expected = [
+        "(optional (list 3 int))",
+        "(optional (list 0 int))",
+        "(optional (list 7 int))",
+        "(optional (list 5 int))",
+        "(optional (list 7 int))",
+        "(optional (buff 3))",
+        "(optional (buff 6))",
+        "(optional (string-ascii 1))",
+        "(optional (string-utf8 5))",
+    ];
+
+    for (good_test, expected) in good.iter().zip(expected.iter()) {
+        assert_eq!(
+            expected,
+            &format!("{}", type_check_helper_v3(good_test).unwrap())
+        );
     }
+
+    // before Clarity3, a slice keeps the maximum length of its sequence
+    assert_eq!(
+        "(optional (list 7 int))",
+        &format!(
+            "{}",
+            type_check_helper("(slice? (list 2 3 4 5 6 7 8) u0 u3)").unwrap()
+        )
+    );
 }
 
 #[test]
@@ -1654,6 +2199,13 @@ fn test_replace_at_list() {
     for (bad_test, expected) in bad.iter().zip(bad_expected.iter()) {
         assert_eq!(expected, &type_check_helper(&bad_test).unwrap_err().err);
     }
+
+    assert_eq!(
+        type_check_helper_v1("(replace-at? (list 2 3) u0 4)")
+            .unwrap_err()
+            .err,
+        CheckErrors::UnknownFunction("replace-at?".to_string())
+    );
 }
 
 #[test]
@@ -1876,6 +2428,32 @@ fn test_concat_append_supertypes() {
     }
 }
 
+#[test]
+fn test_buff_literals() {
+    let good = ["0xdeadbeef", "0x", "(list 0xde 0xdeadbeef)"];
+    let expected = ["(buff 4)", "(buff 0)", "(list 2 (buff 4))"];
+
+    for (good_test, expected) in good.iter().zip(expected.iter()) {
+        assert_eq!(
+            expected,
+            &format!("{}", type_check_helper(good_test).unwrap())
+        );
+    }
+
+    let err = type_check_helper("(+ 1 0xdeadbeef)").unwrap_err();
+    assert_eq!(
+        err.err,
+        CheckErrors::TypeError(
+            IntType,
+            SequenceType(BufferType(BufferLength::try_from(4_u32).unwrap()))
+        )
+    );
+    assert_eq!(
+        err.err.message(),
+        "expecting expression of type 'int', found '(buff 4)'"
+    );
+}
+
 #[test]
 fn test_buff_concat() {
     let good = ["(concat 0x010203 0x0405)"];
@@ -1901,6 +2479,87 @@ fn test_buff_filter() {
     }
 }
 
+#[test]
+fn test_higher_order_callback_signatures() {
+    let good = [
+        "(define-private (even (x int)) (is-eq 0 (mod x 2)))
+        (filter even (list 1 2 3))",
+        "(define-private (double (x int)) (* 2 x))
+        (map double (list 1 2 3))",
+        "(define-private (add (x int) (acc int)) (+ x acc))
+        (fold add (list 1 2 3) 0)",
+    ];
+    let expected = ["(list 3 int)", "(list 3 int)", "int"];
+
+    let bad = [
+        "(define-private (half (x int)) (/ x 2))
+        (filter half (list 1 2 3))",
+        "(define-private (both (x int) (y int)) (and (> x 0) (> y 0)))
+        (filter both (list 1 2 3))",
+        "(define-private (add (x int) (y int)) (+ x y))
+        (map add (list 1 2 3))",
+        "(define-private (inc (x int)) (+ x 1))
+        (fold inc (list 1 2 3) 0)",
+        "(define-private (is-big (x uint)) (> x u10))
+        (filter is-big (list 1 2 3))",
+    ];
+    let bad_expected = [
+        CheckErrors::TypeError(BoolType, IntType),
+        CheckErrors::IncorrectArgumentCount(2, 1),
+        CheckErrors::IncorrectArgumentCount(2, 1),
+        CheckErrors::IncorrectArgumentCount(1, 2),
+        CheckErrors::TypeError(UIntType, IntType),
+    ];
+
+    for (good_test, expected) in good.iter().zip(expected.iter()) {
+        let type_sig = mem_type_check(good_test).unwrap().0.unwrap();
+        assert_eq!(expected, &type_sig.to_string());
+    }
+
+    for (bad_test, expected) in bad.iter().zip(bad_expected.iter()) {
+        assert_eq!(expected, &mem_type_check(bad_test).unwrap_err().err);
+    }
+}
+
+#[test]
+fn test_higher_order_result_types() {
+    let good = [
+        "(define-private (to-key (x int)) (sha256 (to-uint x)))
+        (map to-key (list 1 2 3))",
+        "(define-private (positive (x int)) (> x 0))
+        (filter positive (list -1 2 3 -4))",
+        "(define-private (half (x uint)) (some (/ x u2)))
+        (define-private (odd (x uint)) (is-eq (mod x u2) u1))
+        (map half (filter odd (list u1 u2)))",
+        "(define-data-var keys (list 3 uint) (list))
+        (define-private (small (x int)) (< x 10))
+        (var-set keys (map to-uint (filter small (list 1 2 30))))",
+    ];
+    let expected = [
+        "(list 3 (buff 32))",
+        "(list 4 int)",
+        "(list 2 (optional uint))",
+        "bool",
+    ];
+
+    for (good_test, expected) in good.iter().zip(expected.iter()) {
+        let type_sig = mem_type_check(good_test).unwrap().0.unwrap();
+        assert_eq!(expected, &type_sig.to_string());
+    }
+
+    // the result keeps the input's max length, so it must fit the bound downstream
+    let bad = "(define-data-var keys (list 3 uint) (list))
+        (define-private (small (x int)) (< x 10))
+        (var-set keys (map to-uint (filter small (list 1 2 3 4))))";
+    assert_eq!(
+        mem_type_check(bad).unwrap_err().err,
+        CheckErrors::TypeError(
+            TypeSignature::list_of(UIntType, 3).unwrap(),
+            TypeSignature::list_of(UIntType, 4).unwrap(),
+        )
+    );
+}
+
 #[test]
 fn test_lists_in_defines() {
     let good = "
@@ -1943,6 +2602,145 @@ fn test_tuples() {
     }
 }
 
+#[test]
+fn test_mul_div() {
+    let good = ["(mul-div 1 2 3)", "(mul-div u1 u2 u3)"];
+    let expected = ["int", "uint"];
+    for (good_test, expected) in good.iter().zip(expected.iter()) {
+        assert_eq!(
+            expected,
+            &format!("{}", type_check_helper_v3(good_test).unwrap())
+        );
+    }
+
+    let bad = [
+        "(mul-div 1 u2 3)",
+        "(mul-div 1 2)",
+        "(mul-div true false true)",
+    ];
+    let bad_expected = [
+        CheckErrors::TypeError(IntType, UIntType),
+        CheckErrors::IncorrectArgumentCount(3, 2),
+        CheckErrors::UnionTypeError(vec![IntType, UIntType], BoolType),
+    ];
+    for (bad_test, expected) in bad.iter().zip(bad_expected.iter()) {
+        assert_eq!(expected, &type_check_helper_v3(bad_test).unwrap_err().err);
+    }
+
+    for type_check in [type_check_helper_v1, type_check_helper] {
+        assert!(matches!(
+            type_check("(mul-div 1 2 3)").unwrap_err().err,
+            CheckErrors::UnknownFunction(_)
+        ));
+    }
+}
+
+#[test]
+fn test_tuple_get_optional() {
+    let nested = "(define-data-var t { a: (optional { b: int }), c: uint } { a: none, c: u1 })";
+    let good = [
+        "(get? c (var-get t))",
+        "(get? a (var-get t))",
+        "(get? b (get? a (var-get t)))",
+        "(get? c (some (var-get t)))",
+    ];
+    let expected = [
+        "(optional uint)",
+        "(optional (tuple (b int)))",
+        "(optional int)",
+        "(optional uint)",
+    ];
+    for (good_test, expected) in good.iter().zip(expected.iter()) {
+        let contract = format!("{} {}", nested, good_test);
+        assert_eq!(
+            expected,
+            &format!("{}", mem_type_check_v3(&contract).unwrap().0.unwrap())
+        );
+    }
+
+    // fields are statically known, so a missing one is an analysis error
+    let contract = format!("{} (get? d (var-get t))", nested);
+    assert!(matches!(
+        mem_type_check_v3(&contract).unwrap_err().err,
+        CheckErrors::NoSuchTupleField(field, _) if field == "d"
+    ));
+
+    let bad = [
+        "(get? c (get? c (var-get t)))",
+        "(get? c u1)",
+        "(get? 1 (var-get t))",
+    ];
+    let bad_expected = [
+        CheckErrors::ExpectedTuple(UIntType),
+        CheckErrors::ExpectedTuple(UIntType),
+        CheckErrors::BadTupleFieldName,
+    ];
+    for (bad_test, expected) in bad.iter().zip(bad_expected.iter()) {
+        let contract = format!("{} {}", nested, bad_test);
+        assert_eq!(expected, &mem_type_check_v3(&contract).unwrap_err().err);
+    }
+
+    for type_check in [type_check_helper_v1, type_check_helper] {
+        assert!(matches!(
+            type_check("(get? a { a: 1 })").unwrap_err().err,
+            CheckErrors::UnknownFunction(_)
+        ));
+    }
+}
+
+#[test]
+fn test_unwrap_or_else() {
+    let defines = "(define-private (zero) 0)
+        (define-private (none-int) (if true none (some 1)))
+        (define-private (add-one (x int)) (+ x 1))
+        (define-private (empty) (list))";
+    let good = [
+        "(unwrap-or-else (some 5) zero)",
+        "(unwrap-or-else (none-int) zero)",
+        "(unwrap-or-else (some (list 1 2)) empty)",
+    ];
+    let expected = ["int", "int", "(list 2 int)"];
+    for (good_test, expected) in good.iter().zip(expected.iter()) {
+        let contract = format!("{} {}", defines, good_test);
+        assert_eq!(
+            expected,
+            &format!("{}", mem_type_check_v3(&contract).unwrap().0.unwrap())
+        );
+    }
+
+    let bad = [
+        "(unwrap-or-else (some u5) zero)",
+        "(unwrap-or-else 5 zero)",
+        "(unwrap-or-else (some 5) add-one)",
+        "(unwrap-or-else (some 5) 0)",
+        "(unwrap-or-else (some 5) undefined)",
+        "(unwrap-or-else (some (list 1 2)) list)",
+        "(unwrap-or-else (some 5))",
+    ];
+    let bad_expected = [
+        CheckErrors::DefaultTypesMustMatch(IntType, UIntType),
+        CheckErrors::ExpectedOptionalType(IntType),
+        CheckErrors::IncorrectArgumentCount(1, 0),
+        CheckErrors::NonFunctionApplication,
+        CheckErrors::UnknownFunction("undefined".to_string()),
+        CheckErrors::IllegalOrUnknownFunctionApplication("list".to_string()),
+        CheckErrors::IncorrectArgumentCount(2, 1),
+    ];
+    for (bad_test, expected) in bad.iter().zip(bad_expected.iter()) {
+        let contract = format!("{} {}", defines, bad_test);
+        assert_eq!(expected, &mem_type_check_v3(&contract).unwrap_err().err);
+    }
+
+    for type_check in [type_check_helper_v1, type_check_helper] {
+        assert!(matches!(
+            type_check("(unwrap-or-else (some 5) zero)")
+                .unwrap_err()
+                .err,
+            CheckErrors::UnknownFunction(_)
+        ));
+    }
+}
+
 #[test]
 fn test_empty_tuple_should_fail() {
     let contract_src = r#"
@@ -1992,6 +2790,51 @@ fn test_high_order_map() {
     }
 }
 
+#[test]
+fn test_map_try() {
+    let defines =
+        "(define-private (checked-half (n int)) (if (is-eq (mod n 2) 0) (ok (/ n 2)) (err u1)))
+        (define-private (always-ok (c (string-ascii 1))) (ok c))
+        (define-private (id (n int)) n)";
+    let good = [
+        "(map-try! checked-half (list 2 4 6))",
+        "(map-try! always-ok \"abc\")",
+    ];
+    let expected = [
+        "(response (list 3 int) uint)",
+        "(response (list 3 (string-ascii 1)) UnknownType)",
+    ];
+    for (good_test, expected) in good.iter().zip(expected.iter()) {
+        let contract = format!("{} {}", defines, good_test);
+        let type_sig = mem_type_check_v3(&contract).unwrap().0.unwrap();
+        assert_eq!(expected, &type_sig.to_string());
+    }
+
+    let bad = [
+        "(map-try! id (list 1 2))",
+        "(map-try! checked-half 1)",
+        "(map-try! checked-half (list u1))",
+        "(map-try! checked-half (list 1) (list 2))",
+    ];
+    let bad_expected = [
+        CheckErrors::ExpectedResponseType(IntType),
+        CheckErrors::ExpectedSequence(IntType),
+        CheckErrors::TypeError(IntType, UIntType),
+        CheckErrors::IncorrectArgumentCount(2, 3),
+    ];
+    for (bad_test, expected) in bad.iter().zip(bad_expected.iter()) {
+        let contract = format!("{} {}", defines, bad_test);
+        assert_eq!(expected, &mem_type_check_v3(&contract).unwrap_err().err);
+    }
+
+    for type_check in [type_check_helper_v1, type_check_helper] {
+        assert!(matches!(
+            type_check("(map-try! not (list true))").unwrap_err().err,
+            CheckErrors::UnknownFunction(_)
+        ));
+    }
+}
+
 #[test]
 fn test_function_order_tuples() {
     let snippet = "
@@ -2388,6 +3231,57 @@ fn test_list_nones() {
     );
 }
 
+#[test]
+fn test_option_response_inner_types() {
+    let good = [
+        "(some 1)",
+        "(ok u1)",
+        "(err true)",
+        "(some (ok (list 1 2)))",
+        "(if true (some 1) none)",
+    ];
+    let expected = [
+        "(optional int)",
+        "(response uint UnknownType)",
+        "(response UnknownType bool)",
+        "(optional (response (list 2 int) UnknownType))",
+        "(optional int)",
+    ];
+    for (good_test, expected) in good.iter().zip(expected.iter()) {
+        assert_eq!(
+            expected,
+            &format!("{}", type_check_helper(good_test).unwrap())
+        );
+    }
+
+    // mismatched inner types are caught before the program ever runs
+    assert_eq!(
+        type_check_helper("(if true (some 1) (some u1))")
+            .unwrap_err()
+            .err,
+        CheckErrors::IfArmsMustMatch(
+            TypeSignature::new_option(IntType).unwrap(),
+            TypeSignature::new_option(UIntType).unwrap()
+        )
+    );
+
+    // nesting is bounded by MAX_TYPE_DEPTH: `n` nested `some`s around an int have depth n + 1
+    let nested_somes = |n: usize| format!("{}1{}", "(some ".repeat(n), ")".repeat(n));
+    let max_nesting = MAX_TYPE_DEPTH as usize - 1;
+    assert_eq!(
+        type_check_helper(&nested_somes(max_nesting))
+            .unwrap()
+            .depth(),
+        MAX_TYPE_DEPTH
+    );
+    assert_eq!(
+        type_check_helper(&nested_somes(max_nesting + 1))
+            .unwrap_err()
+            .err,
+        CheckErrors::TypeSignatureTooDeep
+    );
+}
+
 #[test]
 fn test_set_int_variable() {
     let contract_src = r#"
@@ -2635,6 +3529,54 @@ fn test_define_constant_shadowed_by_argument_should_fail() {
     });
 }
 
+#[test]
+fn test_duplicate_definitions_should_fail() {
+    let duplicates = [
+        "(define-private (f) 1) (define-private (f) 2)",
+        "(define-private (f) 1) (define-data-var f int 0)",
+        "(define-data-var f int 0) (define-map f int int)",
+        "(define-trait f ()) (define-constant f 1)",
+    ];
+    // Earlier versions leave these to the interpreter's define checks.
+    let rejected_from_clarity3 = [
+        "(define-read-only (f) 1) (define-public (f) (ok 1))",
+        "(define-read-only (f) 1) (define-read-only (f) 1)",
+        "(define-data-var tx-sender int 0)",
+        "(define-private (+) 1)",
+        "(define-map stx-account int int)",
+        "(define-constant block-height 1)",
+        "(define-trait stx-transfer? ())",
+        "(define-private (f (block-height uint)) block-height)",
+        "(let ((tx-sender 1)) tx-sender)",
+        "(let ((block-height 1)) block-height)",
+        "(define-constant define-map 1)",
+        "(let ((define-public 1)) define-public)",
+        "(define-private (f (use-trait int)) use-trait)",
+    ];
+
+    let analyze = |contract_src: &str, version| {
+        mem_run_analysis(contract_src, version, StacksEpochId::Epoch21)
+    };
+    for contract_src in duplicates.iter().chain(rejected_from_clarity3.iter()) {
+        let res = analyze(contract_src, ClarityVersion::Clarity3).unwrap_err();
+        assert!(
+            matches!(&res.err, CheckErrors::NameAlreadyUsed(_)),
+            "{} => {}",
+            contract_src,
+            res
+        );
+    }
+    for version in [ClarityVersion::Clarity1, ClarityVersion::Clarity2] {
+        for contract_src in duplicates.iter() {
+            let res = analyze(contract_src, version).unwrap_err();
+            assert!(matches!(&res.err, CheckErrors::NameAlreadyUsed(_)));
+        }
+        for contract_src in rejected_from_clarity3.iter() {
+            analyze(contract_src, version).unwrap();
+        }
+    }
+}
+
 #[test]
 fn test_combine_tuples() {
     let ok = [
@@ -2667,6 +3609,83 @@ fn test_combine_tuples() {
     mem_type_check("(merge { a: 1, b: 2, c: 3 } 5)").unwrap_err();
 }
 
+#[test]
+fn test_merge_updates_record() {
+    let good = [
+        (
+            "(merge { a: 1, b: u2 } { a: 5 })",
+            "(tuple (a int) (b uint))",
+        ),
+        (
+            "(merge { a: 1, b: u2 } { b: u3, a: 4 })",
+            "(tuple (a int) (b uint))",
+        ),
+        (
+            "(merge { a: (some 1), b: 2 } { a: none })",
+            "(tuple (a (optional int)) (b int))",
+        ),
+        (
+            "(merge { a: \"hello\", b: 2 } { a: \"hi\" })",
+            "(tuple (a (string-ascii 5)) (b int))",
+        ),
+    ];
+
+    for (good_test, expected) in good.iter() {
+        assert_eq!(
+            expected,
+            &type_check_helper_v3(good_test).unwrap().to_string(),
+            "{}",
+            good_test
+        );
+    }
+
+    let base =
+        TupleTypeSignature::try_from(vec![("a".into(), IntType), ("b".into(), UIntType)]).unwrap();
+    let bad = [
+        (
+            "(merge { a: 1, b: u2 } { c: 3 })",
+            CheckErrors::NoSuchTupleField("c".into(), base.clone()),
+        ),
+        (
+            "(merge { a: 1, b: u2 } { a: 5, c: 3 })",
+            CheckErrors::NoSuchTupleField("c".into(), base),
+        ),
+        (
+            "(merge { a: 1, b: u2 } { a: u5 })",
+            CheckErrors::TypeError(IntType, UIntType),
+        ),
+        (
+            "(merge { a: \"hi\" } { a: \"hello\" })",
+            CheckErrors::TypeError(ascii_type(2), ascii_type(5)),
+        ),
+        (
+            "(merge { a: { x: 0, y: 1 } } { a: { x: 5 } })",
+            CheckErrors::TypeError(
+                TupleTypeSignature::try_from(vec![("x".into(), IntType), ("y".into(), IntType)])
+                    .unwrap()
+                    .into(),
+                TupleTypeSignature::try_from(vec![("x".into(), IntType)])
+                    .unwrap()
+                    .into(),
+            ),
+        ),
+    ];
+
+    for (bad_test, expected) in bad.iter() {
+        assert_eq!(expected, &type_check_helper_v3(bad_test).unwrap_err().err);
+    }
+
+    // before Clarity3, `merge` may still add or retype fields
+    for type_check in [type_check_helper_v1, type_check_helper] {
+        assert_eq!(
+            "(tuple (a uint) (b uint) (c int))",
+            type_check("(merge { a: 1, b: u2 } { a: u5, c: 3 })")
+                .unwrap()
+                .to_string()
+        );
+    }
+}
+
 #[test]
 fn test_using_merge() {
     let t = "(define-map users uint
@@ -3735,3 +4754,76 @@ fn test_principal_admits() {
         assert!(res.is_err());
     }
 }
+
+#[test]
+fn test_collect_independent_errors() {
+    let contract = "(define-private (add-one (x int)) (+ x u1))
+(define-read-only (get-len) (len 0x01 0x02))
+(define-public (call-unknown) (ok (undefined-fn 1)))
+(define-read-only (uses-add-one) (add-one 1))
+(define-read-only (fine) u1)";
+
+    let contract_identifier = QualifiedContractIdentifier::transient();
+    let mut expressions = parse(
+        &contract_identifier,
+        contract,
+        ClarityVersion::Clarity2,
+        StacksEpochId::Epoch21,
+    )
+    .unwrap();
+    let mut marf = MemoryBackingStore::new();
+    let mut analysis_db = marf.as_analysis_db();
+    let errors = run_analysis_collecting_errors(
+        &contract_identifier,
+        &mut expressions,
+        &mut analysis_db,
+        LimitedCostTracker::new_free(),
+        StacksEpochId::Epoch21,
+        ClarityVersion::Clarity2,
+    )
+    .unwrap_err()
+    .0;
+
+    // the call to `add-one`, whose body failed to check, is not reported again
+    let expected = [
+        (CheckErrors::TypeError(IntType, UIntType), (1, 35)),
+        (CheckErrors::IncorrectArgumentCount(1, 2), (2, 29)),
+        (CheckErrors::UnknownFunction("undefined-fn".into()), (3, 35)),
+    ];
+    let reported: Vec<_> = errors
+        .iter()
+        .map(|e| {
+            let span = &e.diagnostic.spans[0];
+            (&e.err, (span.start_line, span.start_column))
+        })
+        .collect();
+    assert_eq!(
+        reported,
+        expected
+            .iter()
+            .map(|(err, start)| (err, *start))
+            .collect::<Vec<_>>()
+    );
+
+    // the single-error analysis stops at one of them
+    let single = mem_type_check(contract).unwrap_err();
+    assert!(errors.iter().any(|e| e.err == single.err));
+
+    // an error-free contract analyzes as usual
+    let mut expressions = parse(
+        &contract_identifier,
+        "(define-read-only (fine) u1)",
+        ClarityVersion::Clarity2,
+        StacksEpochId::Epoch21,
+    )
+    .unwrap();
+    assert!(run_analysis_collecting_errors(
+        &contract_identifier,
+        &mut expressions,
+        &mut analysis_db,
+        LimitedCostTracker::new_free(),
+        StacksEpochId::Epoch21,
+        ClarityVersion::Clarity2,
+    )
+    .is_ok());
+}
@@ -18,7 +18,7 @@ use stacks_common::types::StacksEpochId;
 
 use crate::vm::analysis::analysis_db::AnalysisDatabase;
 use crate::vm::analysis::contract_interface_builder::ContractInterface;
-use crate::vm::analysis::errors::{CheckErrors, CheckResult};
+use crate::vm::analysis::errors::{CheckErrors, CheckResult, CheckWarning};
 use crate::vm::analysis::type_checker::contexts::TypeMap;
 use crate::vm::costs::{CostTracker, ExecutionCost, LimitedCostTracker};
 use crate::vm::types::signatures::FunctionSignature;
@@ -62,6 +62,8 @@ pub struct ContractAnalysis {
     pub type_map: Option<TypeMap>,
     #[serde(skip)]
     pub cost_track: Option<LimitedCostTracker>,
+    #[serde(skip)]
+    pub warnings: Vec<CheckWarning>,
 }
 
 impl ContractAnalysis {
@@ -91,6 +93,7 @@ impl ContractAnalysis {
             is_cost_contract_eligible: false,
             epoch,
             clarity_version,
+            warnings: vec![],
         }
     }
 
@@ -86,6 +86,7 @@ pub enum ParseErrors {
     IllegalASCIIString(String),
     IllegalUtf8String(String),
     ExpectedWhitespace,
+    UnreadableInput(String),
     // Notes
     NoteToMatchThis(Token),
 
@@ -110,6 +111,15 @@ impl ParseError {
         }
     }
 
+    /// Build an error whose diagnostic points at `spans`: where the error was
+    ///  detected first, followed by any related location (e.g., the opening
+    ///  delimiter of an unclosed list).
+    pub fn with_spans(err: ParseErrors, spans: Vec<Span>) -> ParseError {
+        let mut e = ParseError::new(err);
+        e.diagnostic.spans = spans;
+        e
+    }
+
     pub fn has_pre_expression(&self) -> bool {
         self.pre_expressions.is_some()
     }
@@ -296,6 +306,7 @@ impl DiagnosableError for ParseErrors {
             ParseErrors::IllegalASCIIString(s) => format!("illegal ascii string \"{}\"", s),
             ParseErrors::IllegalUtf8String(s) => format!("illegal UTF8 string \"{}\"", s),
             ParseErrors::ExpectedWhitespace => "expected whitespace before expression".to_string(),
+            ParseErrors::UnreadableInput(e) => format!("failed to read program input: {}", e),
             ParseErrors::NoteToMatchThis(token) => format!("to match this '{}'", token),
             ParseErrors::UnexpectedParserFailure => "unexpected failure while parsing".to_string(),
         }
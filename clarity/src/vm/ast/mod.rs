@@ -21,6 +21,7 @@ pub mod traits_resolver;
 
 pub mod errors;
 pub mod stack_depth_checker;
+pub mod substitute;
 pub mod sugar_expander;
 pub mod types;
 use crate::vm::costs::{cost_functions, runtime_cost, CostTracker, LimitedCostTracker};
@@ -1317,6 +1317,45 @@ mod test {
         });
     }
 
+    #[test]
+    fn test_parse_int_literal_bounds() {
+        let in_range = [
+            (
+                "170141183460469231731687303715884105727",
+                Value::Int(i128::MAX),
+            ),
+            (
+                "-170141183460469231731687303715884105728",
+                Value::Int(i128::MIN),
+            ),
+            (
+                "u340282366920938463463374607431768211455",
+                Value::UInt(u128::MAX),
+            ),
+            ("000123", Value::Int(123)),
+        ];
+        for (literal, expected) in in_range.iter() {
+            assert_eq!(
+                ast::parser::v1::parse(literal).unwrap()[0].match_atom_value(),
+                Some(expected)
+            );
+        }
+
+        // out-of-range literals are rejected rather than wrapped (v1 reports
+        //  uint overflows as FailedParsingIntValue too)
+        let over_range = [
+            "170141183460469231731687303715884105728",
+            "-170141183460469231731687303715884105729",
+            "u340282366920938463463374607431768211456",
+        ];
+        for literal in over_range.iter() {
+            assert_eq!(
+                ast::parser::v1::parse(literal).unwrap_err().err,
+                ParseErrors::FailedParsingIntValue(literal.trim_start_matches('u').to_string())
+            );
+        }
+    }
+
     #[test]
     fn test_long_contract_name() {
         let long_contract_name = "(define-private (transfer (id uint) (receiver principal)) (contract-call? 'SP3D6PV2ACBPEKYJTCMH7HEN02KP87QSP8KTEH335.megapont-robot-expansion-nftSPNWZ5V2TPWGQGVDR6T7B6RQ4XMGZ4PXTEE0VQ0S.guests-hosted-stacks-parrots transfer id tx-sender receiver))";
@@ -5,7 +5,7 @@ use crate::vm::{
     diagnostic::{DiagnosableError, Level},
     representations::Span,
 };
-use std::{char, str::Chars};
+use std::char;
 use token::{PlacedToken, Token};
 
 use self::error::{LexerError, PlacedError};
@@ -15,7 +15,7 @@ pub type LexResult<T> = Result<T, LexerError>;
 const EOF: char = std::char::REPLACEMENT_CHARACTER;
 
 pub struct Lexer<'a> {
-    input: Chars<'a>,
+    input: Box<dyn Iterator<Item = char> + 'a>,
     next: char,
     offset: usize,
     pub line: usize,
@@ -49,8 +49,14 @@ fn is_string_terminator(ch: char) -> bool {
 
 impl<'a> Lexer<'a> {
     pub fn new(input: &'a str, fail_fast: bool) -> LexResult<Self> {
+        Self::from_chars(input.chars(), fail_fast)
+    }
+
+    /// Lex the characters of `input` as they are produced, without requiring
+    ///  the whole program to be in memory.
+    pub fn from_chars(input: impl Iterator<Item = char> + 'a, fail_fast: bool) -> LexResult<Self> {
         let mut s = Self {
-            input: input.chars(),
+            input: Box::new(input),
             next: 0 as char,
             offset: 0,
             line: 1,
@@ -66,8 +72,11 @@ impl<'a> Lexer<'a> {
         Ok(s)
     }
 
+    /// Report `e` at `span`. In `fail_fast` mode, the placed error is still
+    ///  recorded in `diagnostics` so that the caller can locate the failure.
     fn add_diagnostic(&mut self, e: LexerError, span: Span) -> LexResult<()> {
         if self.fail_fast {
+            self.diagnostics.push(PlacedError { e: e.clone(), span });
             return Err(e);
         } else {
             if e.level() == Level::Error {
@@ -78,6 +87,28 @@ impl<'a> Lexer<'a> {
         }
     }
 
+    /// Report `e` at `span` together with the `note` at `note_span` that
+    ///  explains it. Unlike two calls to `add_diagnostic`, this records the
+    ///  note in `fail_fast` mode too.
+    fn add_diagnostic_with_note(
+        &mut self,
+        e: LexerError,
+        span: Span,
+        note: LexerError,
+        note_span: Span,
+    ) -> LexResult<()> {
+        if self.fail_fast {
+            self.diagnostics.push(PlacedError { e: e.clone(), span });
+            self.diagnostics.push(PlacedError {
+                e: note,
+                span: note_span,
+            });
+            return Err(e);
+        }
+        self.add_diagnostic(e, span)?;
+        self.add_diagnostic(note, note_span)
+    }
+
     fn read_char(&mut self) -> LexResult<()> {
         self.last_line = self.line;
         self.last_column = self.column;
@@ -263,7 +294,7 @@ impl<'a> Lexer<'a> {
                         )?));
                     }
 
-                    self.add_diagnostic(
+                    self.add_diagnostic_with_note(
                         LexerError::ExpectedClosing('>'),
                         Span {
                             start_line: self.line as u32,
@@ -271,8 +302,6 @@ impl<'a> Lexer<'a> {
                             end_line: self.line as u32,
                             end_column: self.column as u32,
                         },
-                    )?;
-                    self.add_diagnostic(
                         LexerError::NoteToMatchThis('<'),
                         Span {
                             start_line,
@@ -429,7 +458,7 @@ impl<'a> Lexer<'a> {
                     }
                     '\\' => escaped = true,
                     EOF | '\n' => {
-                        self.add_diagnostic(
+                        self.add_diagnostic_with_note(
                             LexerError::ExpectedClosing('"'),
                             Span {
                                 start_line: self.line as u32,
@@ -437,8 +466,6 @@ impl<'a> Lexer<'a> {
                                 end_line: self.line as u32,
                                 end_column: self.column as u32,
                             },
-                        )?;
-                        self.add_diagnostic(
                             LexerError::NoteToMatchThis('"'),
                             Span {
                                 start_line,
@@ -514,7 +541,7 @@ impl<'a> Lexer<'a> {
                     return Ok(Ok(code));
                 }
                 '"' => {
-                    self.add_diagnostic(
+                    self.add_diagnostic_with_note(
                         LexerError::UnterminatedUTF8Encoding,
                         Span {
                             start_line: self.line as u32,
@@ -522,8 +549,6 @@ impl<'a> Lexer<'a> {
                             end_line: self.line as u32,
                             end_column: self.column as u32,
                         },
-                    )?;
-                    self.add_diagnostic(
                         LexerError::NoteToMatchThis('}'),
                         Span {
                             start_line,
@@ -535,7 +560,7 @@ impl<'a> Lexer<'a> {
                     return Ok(Err(format!("{{{}", code)));
                 }
                 EOF => {
-                    self.add_diagnostic(
+                    self.add_diagnostic_with_note(
                         LexerError::UnterminatedUTF8Encoding,
                         Span {
                             start_line: self.line as u32,
@@ -543,8 +568,6 @@ impl<'a> Lexer<'a> {
                             end_line: self.line as u32,
                             end_column: self.column as u32,
                         },
-                    )?;
-                    self.add_diagnostic(
                         LexerError::NoteToMatchThis('}'),
                         Span {
                             start_line,
@@ -673,7 +696,7 @@ impl<'a> Lexer<'a> {
                     }
                     '\\' => escaped = true,
                     EOF | '\n' => {
-                        self.add_diagnostic(
+                        self.add_diagnostic_with_note(
                             LexerError::ExpectedClosing('"'),
                             Span {
                                 start_line: self.line as u32,
@@ -681,8 +704,6 @@ impl<'a> Lexer<'a> {
                                 end_line: self.line as u32,
                                 end_column: self.column as u32,
                             },
-                        )?;
-                        self.add_diagnostic(
                             LexerError::NoteToMatchThis('"'),
                             Span {
                                 start_line,
@@ -1,6 +1,8 @@
 pub mod lexer;
 
+use std::cell::RefCell;
 use std::convert::TryFrom;
+use std::io::{self, Read};
 use std::num::ParseIntError;
 
 use stacks_common::util::hash::hex_bytes;
@@ -70,10 +72,17 @@ enum ParserStackElement {
 }
 
 impl<'a> Parser<'a> {
-    pub fn new(input: &'a str, fail_fast: bool) -> Result<Self, ParseErrors> {
-        let lexer = match Lexer::new(input, fail_fast) {
+    pub fn new(input: &'a str, fail_fast: bool) -> ParseResult<Self> {
+        Self::from_chars(input.chars(), fail_fast)
+    }
+
+    pub fn from_chars(
+        input: impl Iterator<Item = char> + 'a,
+        fail_fast: bool,
+    ) -> ParseResult<Self> {
+        let lexer = match Lexer::from_chars(input, fail_fast) {
             Ok(lexer) => lexer,
-            Err(e) => return Err(ParseErrors::Lexer(e)),
+            Err(e) => return Err(ParseError::new(ParseErrors::Lexer(e))),
         };
         let mut p = Self {
             lexer,
@@ -94,7 +103,9 @@ impl<'a> Parser<'a> {
                         "Parser::read_token should not return an error when not in fail_fast mode"
                     );
                     p.success = false;
-                    return Err(ParseErrors::Lexer(e));
+                    // in fail_fast mode, the lexer records where it failed
+                    let spans = p.lexer.diagnostics.drain(..).map(|e| e.span).collect();
+                    return Err(ParseError::with_spans(ParseErrors::Lexer(e), spans));
                 }
             };
             if token.token == Token::Eof {
@@ -118,7 +129,7 @@ impl<'a> Parser<'a> {
 
     fn add_diagnostic(&mut self, e: ParseErrors, span: Span) -> ParseResult<()> {
         if self.fail_fast {
-            return Err(ParseError::new(e));
+            return Err(ParseError::with_spans(e, vec![span]));
         } else {
             if e.level() == Level::Error {
                 self.success = false;
@@ -128,6 +139,22 @@ impl<'a> Parser<'a> {
         Ok(())
     }
 
+    /// Report `e` at `span` together with the `note` at `note_span` that
+    ///  explains it. In `fail_fast` mode, the returned error points at both.
+    fn add_diagnostic_with_note(
+        &mut self,
+        e: ParseErrors,
+        span: Span,
+        note: ParseErrors,
+        note_span: Span,
+    ) -> ParseResult<()> {
+        if self.fail_fast {
+            return Err(ParseError::with_spans(e, vec![span, note_span]));
+        }
+        self.add_diagnostic(e, span)?;
+        self.add_diagnostic(note, note_span)
+    }
+
     fn next_token(&mut self) -> Option<PlacedToken> {
         if self.next_token >= self.tokens.len() {
             return None;
@@ -243,11 +270,9 @@ impl<'a> Parser<'a> {
                         }
                         Token::Eof => {
                             // Report an error, but return the list and attempt to continue parsing
-                            self.add_diagnostic(
+                            self.add_diagnostic_with_note(
                                 ParseErrors::ExpectedClosing(Token::Rparen),
                                 token.span.clone(),
-                            )?;
-                            self.add_diagnostic(
                                 ParseErrors::NoteToMatchThis(Token::Lparen),
                                 span.clone(),
                             )?;
@@ -293,11 +318,9 @@ impl<'a> Parser<'a> {
                         let last_token = self.peek_last_token()?.clone();
                         match last_token.token {
                             Token::Eof => {
-                                self.add_diagnostic(
+                                self.add_diagnostic_with_note(
                                     ParseErrors::ExpectedClosing(Token::Rbrace),
                                     open_tuple.diagnostic_token.span.clone(),
-                                )?;
-                                self.add_diagnostic(
                                     ParseErrors::NoteToMatchThis(Token::Lbrace),
                                     open_tuple.span.clone(),
                                 )?;
@@ -1102,16 +1125,113 @@ impl<'a> Parser<'a> {
 }
 
 pub fn parse(input: &str) -> ParseResult<Vec<PreSymbolicExpression>> {
-    let mut parser = match Parser::new(input, true) {
-        Ok(parser) => parser,
-        Err(e) => return Err(ParseError::new(e)),
-    };
+    let mut parser = Parser::new(input, true)?;
     let stmts = parser.parse()?;
     if parser.success {
         Ok(stmts)
     } else {
         let err = parser.diagnostics.remove(0);
-        Err(ParseError::new(err.e))
+        Err(ParseError::with_spans(err.e, vec![err.span]))
+    }
+}
+
+/// Decodes the characters of a `Read` source one buffer at a time, carrying a
+///  character split across two reads over to the next one. A read or decoding
+///  failure ends the input early and is recorded in `error`.
+struct ReaderChars<'e, R: Read> {
+    reader: R,
+    pending: Vec<u8>,
+    decoded: std::vec::IntoIter<char>,
+    done: bool,
+    error: &'e RefCell<Option<String>>,
+}
+
+const READER_CHUNK_SIZE: usize = 4096;
+
+impl<R: Read> ReaderChars<'_, R> {
+    fn fail(&mut self, error: String) {
+        self.done = true;
+        self.error.replace(Some(error));
+    }
+
+    /// Read the next chunk, decoding as many complete characters as it holds.
+    fn fill(&mut self) {
+        let mut chunk = [0u8; READER_CHUNK_SIZE];
+        let read = match self.reader.read(&mut chunk) {
+            Ok(0) => {
+                self.done = true;
+                if !self.pending.is_empty() {
+                    self.fail("input ends within a UTF-8 character".into());
+                }
+                return;
+            }
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => return,
+            Err(e) => return self.fail(e.to_string()),
+        };
+        self.pending.extend_from_slice(&chunk[..read]);
+
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(_) => self.pending.len(),
+            Err(e) => {
+                if e.error_len().is_some() {
+                    self.fail(format!("{}", e));
+                }
+                e.valid_up_to()
+            }
+        };
+        let rest = self.pending.split_off(valid);
+        let decoded = std::str::from_utf8(&self.pending)
+            .expect("prefix was validated as UTF-8")
+            .chars()
+            .collect::<Vec<_>>();
+        self.decoded = decoded.into_iter();
+        self.pending = rest;
+    }
+}
+
+impl<R: Read> Iterator for ReaderChars<'_, R> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        loop {
+            if let Some(ch) = self.decoded.next() {
+                return Some(ch);
+            }
+            if self.done {
+                return None;
+            }
+            self.fill();
+        }
+    }
+}
+
+/// Parse a program read incrementally from `reader`. Produces the same result
+///  as `parse` over the same bytes, or `UnreadableInput` if the bytes cannot be
+///  read or are not UTF-8.
+pub fn parse_from_reader(reader: impl Read) -> ParseResult<Vec<PreSymbolicExpression>> {
+    let error = RefCell::new(None);
+    let chars = ReaderChars {
+        reader,
+        pending: vec![],
+        decoded: vec![].into_iter(),
+        done: false,
+        error: &error,
+    };
+    let result = Parser::from_chars(chars, true).and_then(|mut parser| {
+        let stmts = parser.parse()?;
+        if parser.success {
+            Ok(stmts)
+        } else {
+            let err = parser.diagnostics.remove(0);
+            Err(ParseError::with_spans(err.e, vec![err.span]))
+        }
+    });
+    // a failed read truncates the input, so it takes precedence over any
+    //  error the truncated program produced
+    match error.into_inner() {
+        Some(e) => Err(ParseError::new(ParseErrors::UnreadableInput(e))),
+        None => result,
     }
 }
 
@@ -1375,6 +1495,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_int_literal_bounds() {
+        let in_range = [
+            (
+                "170141183460469231731687303715884105727",
+                Value::Int(i128::MAX),
+            ),
+            (
+                "-170141183460469231731687303715884105728",
+                Value::Int(i128::MIN),
+            ),
+            (
+                "u340282366920938463463374607431768211455",
+                Value::UInt(u128::MAX),
+            ),
+        ];
+        for (literal, expected) in in_range.iter() {
+            let (stmts, diagnostics, success) = parse_collect_diagnostics(literal);
+            assert!(success);
+            assert_eq!(diagnostics.len(), 0);
+            assert_eq!(stmts[0].match_atom_value(), Some(expected));
+        }
+
+        let over_range = [
+            (
+                "170141183460469231731687303715884105728",
+                "Failed to parse int literal '170141183460469231731687303715884105728'",
+            ),
+            (
+                "-170141183460469231731687303715884105729",
+                "Failed to parse int literal '-170141183460469231731687303715884105729'",
+            ),
+            (
+                "u340282366920938463463374607431768211456",
+                "Failed to parse uint literal 'u340282366920938463463374607431768211456'",
+            ),
+        ];
+        for (literal, message) in over_range.iter() {
+            let (stmts, diagnostics, success) = parse_collect_diagnostics(literal);
+            assert!(!success);
+            assert_eq!(stmts[0].match_placeholder(), Some(*literal));
+            assert_eq!(diagnostics.len(), 1);
+            assert_eq!(diagnostics[0].message, message.to_string());
+            assert_eq!(
+                diagnostics[0].spans[0],
+                Span {
+                    start_line: 1,
+                    start_column: 1,
+                    end_line: 1,
+                    end_column: literal.len() as u32
+                }
+            );
+        }
+
+        // digit separators are not part of the literal syntax
+        let (_, diagnostics, success) = parse_collect_diagnostics("1_000");
+        assert!(!success);
+        assert_eq!(
+            diagnostics[0].message,
+            "invalid character, '_', in int literal".to_string()
+        );
+    }
+
     #[test]
     fn test_parse_ascii_string() {
         let (stmts, diagnostics, success) = parse_collect_diagnostics("\"new\\nline\"");
@@ -3565,6 +3748,79 @@ mod tests {
         }
     }
 
+    /// The 1-based (line, column) at which each span of a fail_fast error starts
+    fn error_starts(e: &ParseError) -> Vec<(u32, u32)> {
+        e.diagnostic
+            .spans
+            .iter()
+            .map(|span| (span.start_line, span.start_column))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_fail_fast_stray_close_paren() {
+        let e = parse("(foo 1)\n  (bar 2))").unwrap_err();
+        assert_eq!(e.err, ParseErrors::UnexpectedToken(Token::Rparen));
+        assert_eq!(error_starts(&e), vec![(2, 10)]);
+    }
+
+    #[test]
+    fn test_parse_fail_fast_unclosed_paren() {
+        // the error is detected at the end of input, and also points at the
+        //  innermost `(` left open
+        let e = parse("(define-private (foo)\n  (bar 1)").unwrap_err();
+        assert_eq!(e.err, ParseErrors::ExpectedClosing(Token::Rparen));
+        assert_eq!(error_starts(&e), vec![(2, 10), (1, 1)]);
+
+        let e = parse("{a: 1").unwrap_err();
+        assert_eq!(e.err, ParseErrors::ExpectedClosing(Token::Rbrace));
+        assert_eq!(error_starts(&e)[1], (1, 1));
+    }
+
+    #[test]
+    fn test_parse_fail_fast_unterminated_string() {
+        let e = parse("(print 1)\n(print \"hello)").unwrap_err();
+        assert_eq!(e.err, ParseErrors::Lexer(LexerError::ExpectedClosing('"')));
+        assert_eq!(error_starts(&e), vec![(2, 15), (2, 8)]);
+
+        let e = parse("(print u\"hello)").unwrap_err();
+        assert_eq!(e.err, ParseErrors::Lexer(LexerError::ExpectedClosing('"')));
+        assert_eq!(error_starts(&e)[1], (1, 8));
+    }
+
+    #[test]
+    fn test_parse_fail_fast_principals() {
+        let stmts = parse("(print 'ST000000000000000000002AMW42H)").unwrap();
+        match stmts[0].match_list().unwrap()[1].match_atom_value() {
+            Some(Value::Principal(PrincipalData::Standard(data))) => {
+                assert_eq!(data.to_address(), "ST000000000000000000002AMW42H")
+            }
+            _ => panic!("failed to parse principal"),
+        }
+
+        // `I` is not in the c32 alphabet, the error points at it
+        let e = parse("(print 1)\n(print 'INVALID)").unwrap_err();
+        assert_eq!(
+            e.err,
+            ParseErrors::Lexer(LexerError::InvalidCharPrincipal('I'))
+        );
+        assert_eq!(error_starts(&e), vec![(2, 9)]);
+
+        // well-formed, but with a bad checksum
+        let e = parse("(print 1)\n(print 'ST000000000000000000002AMW42J)").unwrap_err();
+        assert_eq!(e.err, ParseErrors::InvalidPrincipalLiteral);
+        assert_eq!(error_starts(&e), vec![(2, 8)]);
+
+        let name = "a".repeat(MAX_CONTRACT_NAME_LEN + 1);
+        let e = parse(&format!(
+            "(print 1)\n(print 'ST000000000000000000002AMW42H.{})",
+            name
+        ))
+        .unwrap_err();
+        assert_eq!(e.err, ParseErrors::ContractNameTooLong(name));
+        assert_eq!(error_starts(&e), vec![(2, 39)]);
+    }
+
     #[test]
     fn test_empty_contract() {
         let (stmts, diagnostics, success) = parse_collect_diagnostics("");
@@ -3634,4 +3890,57 @@ mod tests {
             }
         );
     }
+
+    /// Yields at most `step` bytes per read, so that every token and
+    ///  character boundary falls across reads at some point.
+    struct SteppedReader<'a> {
+        bytes: &'a [u8],
+        step: usize,
+    }
+
+    impl Read for SteppedReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.step.min(buf.len()).min(self.bytes.len());
+            buf[..n].copy_from_slice(&self.bytes[..n]);
+            self.bytes = &self.bytes[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_parse_from_reader() {
+        let long_string = "x".repeat(READER_CHUNK_SIZE);
+        let programs = [
+            "(define-public (hello (name (string-ascii 20)))\n  ;; greet\n  (ok { greeting: \"hi\", to: name }))\n(hello \"world\")".to_string(),
+            "(list u\"\\u{1F600} a\" 0x0102 'S1G2081040G2081040G2081040G208105NK8PE5.contract)".to_string(),
+            // a string literal straddling the reader's chunk boundary
+            format!("(print \"{}\")\n(+ 1 2)", long_string),
+            // errors are reported the same way, including for non-ASCII input
+            "(+ 1 2".to_string(),
+            "(print \"caf\u{e9}\")".to_string(),
+        ];
+
+        for program in programs.iter() {
+            let expected = parse(program);
+            for step in [1, 2, 3, READER_CHUNK_SIZE] {
+                let reader = SteppedReader {
+                    bytes: program.as_bytes(),
+                    step,
+                };
+                assert_eq!(parse_from_reader(reader), expected, "{}", program);
+            }
+            assert_eq!(parse_from_reader(program.as_bytes()), expected);
+        }
+
+        let invalid_utf8: &[u8] = b"(print \"\xff\")";
+        assert!(matches!(
+            parse_from_reader(invalid_utf8).unwrap_err().err,
+            ParseErrors::UnreadableInput(_)
+        ));
+        let truncated: &[u8] = &"(print \"\u{e9}".as_bytes()[..9];
+        assert!(matches!(
+            parse_from_reader(truncated).unwrap_err().err,
+            ParseErrors::UnreadableInput(_)
+        ));
+    }
 }
@@ -0,0 +1,273 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::vm::ast::errors::ParseResult;
+use crate::vm::ast::expression_identifier::update_expression_id;
+use crate::vm::functions::define::DefineFunctions;
+use crate::vm::representations::{SymbolicExpression, SymbolicExpressionCommon};
+
+#[cfg(feature = "developer-mode")]
+use crate::vm::representations::Span;
+
+type Bindings = HashMap<String, SymbolicExpression>;
+
+/// Replace every atom of `ast` named by a key of `bindings` with a copy of the
+///  bound expression, for generating contracts from a parsed template.
+///
+/// Only atoms in value positions are replaced. Names are left alone: the head of a
+///  list (a function name, or a key of a `tuple` entry), the field name of a `get`,
+///  and the name given by a `define-constant`, `define-data-var`, `define-map` or
+///  other non-function definition. Names bound by a `let`, a `match`, or the
+///  arguments of a function definition shadow a binding of the same name within
+///  their scope. Literals (including strings) are never atoms, so their contents
+///  are never replaced.
+/// Inserted expressions take the span of the atom they replace, and the ids of
+///  the whole AST are relabeled afterwards.
+pub fn substitute(ast: &mut [SymbolicExpression], bindings: &Bindings) -> ParseResult<()> {
+    substitute_expressions(ast, bindings);
+    update_expression_id(ast)
+}
+
+fn substitute_expressions(exprs: &mut [SymbolicExpression], bindings: &Bindings) {
+    for expr in exprs.iter_mut() {
+        substitute_expression(expr, bindings);
+    }
+}
+
+fn substitute_expression(expr: &mut SymbolicExpression, bindings: &Bindings) {
+    if bindings.is_empty() {
+        return;
+    }
+
+    if let Some(name) = expr.match_atom() {
+        if let Some(bound) = bindings.get(name.as_str()) {
+            #[allow(unused_mut)]
+            let mut replacement = bound.clone();
+            #[cfg(feature = "developer-mode")]
+            set_span_recursive(&mut replacement, &expr.span);
+            *expr = replacement;
+        }
+        return;
+    }
+
+    let list = match expr.match_list_mut() {
+        Some(list) => list,
+        None => return,
+    };
+    let head = list
+        .first()
+        .and_then(|head| head.match_atom())
+        .map(|name| name.as_str());
+    match head {
+        Some("get") | Some("get?") if list.len() >= 2 => {
+            substitute_expressions(&mut list[2..], bindings);
+        }
+        Some("match") if list.len() == 5 || list.len() == 6 => substitute_match(list, bindings),
+        Some("let") if list.len() >= 2 => {
+            let (let_bindings, body) = list[1..].split_at_mut(1);
+            substitute_let(&mut let_bindings[0], body, bindings);
+        }
+        Some(name) if is_function_definition(name) && list.len() >= 2 => {
+            let (signature, body) = list[1..].split_at_mut(1);
+            let mut scoped = Cow::Borrowed(bindings);
+            if let Some([_name, args @ ..]) = signature[0].match_list() {
+                for arg in args.iter() {
+                    if let Some(arg_name) = arg.match_list().and_then(|pair| pair[0].match_atom()) {
+                        shadow(&mut scoped, arg_name.as_str());
+                    }
+                }
+            }
+            substitute_expressions(body, &scoped);
+        }
+        Some(name) if is_named_definition(name) && list.len() >= 2 => {
+            substitute_expressions(&mut list[2..], bindings);
+        }
+        _ => substitute_expressions(&mut list[1..], bindings),
+    }
+}
+
+/// `(match input some-name some-branch none-branch)` over an optional, or
+///  `(match input ok-name ok-branch err-name err-branch)` over a response: each
+///  name is bound in the branch that follows it.
+fn substitute_match(list: &mut [SymbolicExpression], bindings: &Bindings) {
+    substitute_expression(&mut list[1], bindings);
+    substitute_bound_branch(&mut list[2..4], bindings);
+    if list.len() == 5 {
+        substitute_expression(&mut list[4], bindings);
+    } else {
+        substitute_bound_branch(&mut list[4..6], bindings);
+    }
+}
+
+/// Substitute into `branch` of a `[name, branch]` pair, in which `name` is bound.
+fn substitute_bound_branch(name_and_branch: &mut [SymbolicExpression], bindings: &Bindings) {
+    let mut scoped = Cow::Borrowed(bindings);
+    if let Some(name) = name_and_branch[0].match_atom() {
+        shadow(&mut scoped, name.as_str());
+    }
+    substitute_expression(&mut name_and_branch[1], &scoped);
+}
+
+/// `let` binds sequentially: each bound value sees the names bound before it.
+fn substitute_let(
+    let_bindings: &mut SymbolicExpression,
+    body: &mut [SymbolicExpression],
+    bindings: &Bindings,
+) {
+    let mut scoped = Cow::Borrowed(bindings);
+    if let Some(pairs) = let_bindings.match_list_mut() {
+        for pair in pairs.iter_mut() {
+            match pair.match_list_mut() {
+                Some([name, value]) => {
+                    substitute_expression(value, &scoped);
+                    if let Some(name) = name.match_atom() {
+                        shadow(&mut scoped, name.as_str());
+                    }
+                }
+                _ => substitute_expression(pair, &scoped),
+            }
+        }
+    }
+    substitute_expressions(body, &scoped);
+}
+
+fn shadow(bindings: &mut Cow<Bindings>, name: &str) {
+    if bindings.contains_key(name) {
+        bindings.to_mut().remove(name);
+    }
+}
+
+fn is_function_definition(name: &str) -> bool {
+    matches!(
+        DefineFunctions::lookup_by_name(name),
+        Some(DefineFunctions::PrivateFunction)
+            | Some(DefineFunctions::PublicFunction)
+            | Some(DefineFunctions::ReadOnlyFunction)
+    )
+}
+
+/// Definitions whose first argument is the name they define, rather than a value.
+fn is_named_definition(name: &str) -> bool {
+    matches!(
+        DefineFunctions::lookup_by_name(name),
+        Some(DefineFunctions::Constant)
+            | Some(DefineFunctions::PersistedVariable)
+            | Some(DefineFunctions::Map)
+            | Some(DefineFunctions::FungibleToken)
+            | Some(DefineFunctions::NonFungibleToken)
+            | Some(DefineFunctions::Trait)
+            | Some(DefineFunctions::UseTrait)
+    )
+}
+
+#[cfg(feature = "developer-mode")]
+fn set_span_recursive(expr: &mut SymbolicExpression, span: &Span) {
+    expr.span = span.clone();
+    if let Some(list) = expr.match_list_mut() {
+        for item in list.iter_mut() {
+            set_span_recursive(item, span);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::ast::build_ast;
+    use crate::vm::representations::SymbolicExpression;
+    use crate::vm::types::{PrincipalData, QualifiedContractIdentifier, Value};
+    use crate::vm::ClarityVersion;
+    use stacks_common::types::StacksEpochId;
+
+    fn parse(source: &str) -> Vec<SymbolicExpression> {
+        build_ast(
+            &QualifiedContractIdentifier::transient(),
+            source,
+            &mut (),
+            ClarityVersion::Clarity2,
+            StacksEpochId::latest(),
+        )
+        .unwrap()
+        .expressions
+    }
+
+    fn display(exprs: &[SymbolicExpression]) -> Vec<String> {
+        exprs.iter().map(|expr| expr.to_string()).collect()
+    }
+
+    #[test]
+    fn test_substitute_placeholder_principal() {
+        let template = "(define-constant owner OWNER)
+            (define-read-only (is-owner (who principal)) (is-eq who OWNER))
+            (define-read-only (shadowed (OWNER principal)) OWNER)
+            (define-read-only (label) \"OWNER\")
+            (define-read-only (local) (let ((a OWNER) (OWNER tx-sender)) (list a OWNER)))";
+        let expected = "(define-constant owner 'SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7)
+            (define-read-only (is-owner (who principal)) (is-eq who 'SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7))
+            (define-read-only (shadowed (OWNER principal)) OWNER)
+            (define-read-only (label) \"OWNER\")
+            (define-read-only (local) (let ((a 'SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7) (OWNER tx-sender)) (list a OWNER)))";
+
+        let owner = PrincipalData::parse("SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7").unwrap();
+        let mut bindings = HashMap::new();
+        bindings.insert(
+            "OWNER".to_string(),
+            SymbolicExpression::literal_value(Value::Principal(owner)),
+        );
+
+        let mut ast = parse(template);
+        substitute(&mut ast, &bindings).unwrap();
+
+        let reparsed = parse(expected);
+        assert_eq!(display(&ast), display(&reparsed));
+        let ids = |exprs: &[SymbolicExpression]| exprs.iter().map(|e| e.id).collect::<Vec<_>>();
+        assert_eq!(ids(&ast), ids(&reparsed));
+    }
+
+    #[test]
+    fn test_substitute_skips_names() {
+        let template = "(define-data-var OWNER principal tx-sender)
+            (define-map OWNER principal principal)
+            (define-read-only (sugared) {OWNER: OWNER})
+            (define-read-only (explicit) (tuple (OWNER OWNER)))
+            (define-read-only (field (t {OWNER: principal})) (get OWNER t))
+            (define-read-only (merged) (merge {OWNER: OWNER} {a: 1}))
+            (define-read-only (opt (o (optional principal))) (match o OWNER OWNER OWNER))
+            (define-read-only (res (r (response principal int))) (match r OWNER OWNER e OWNER))";
+        let expected = "(define-data-var OWNER principal tx-sender)
+            (define-map OWNER principal principal)
+            (define-read-only (sugared) {OWNER: 'SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7})
+            (define-read-only (explicit) (tuple (OWNER 'SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7)))
+            (define-read-only (field (t {OWNER: principal})) (get OWNER t))
+            (define-read-only (merged) (merge {OWNER: 'SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7} {a: 1}))
+            (define-read-only (opt (o (optional principal))) (match o OWNER OWNER 'SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7))
+            (define-read-only (res (r (response principal int))) (match r OWNER OWNER e 'SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7))";
+
+        let owner = PrincipalData::parse("SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7").unwrap();
+        let mut bindings = HashMap::new();
+        bindings.insert(
+            "OWNER".to_string(),
+            SymbolicExpression::literal_value(Value::Principal(owner)),
+        );
+
+        let mut ast = parse(template);
+        substitute(&mut ast, &bindings).unwrap();
+        assert_eq!(display(&ast), display(&parse(expected)));
+    }
+}
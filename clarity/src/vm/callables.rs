@@ -18,10 +18,11 @@ use std::collections::{BTreeMap, HashMap};
 use std::convert::TryInto;
 use std::fmt;
 use std::iter::FromIterator;
+use std::rc::Rc;
 
 use stacks_common::types::StacksEpochId;
 
-use crate::vm::costs::{cost_functions, runtime_cost};
+use crate::vm::costs::{cost_functions, runtime_cost, ExecutionCost};
 
 use crate::vm::analysis::errors::CheckErrors;
 use crate::vm::contexts::ContractContext;
@@ -40,6 +41,10 @@ use super::costs::CostOverflowingMath;
 use super::types::signatures::CallableSubtype;
 use super::ClarityVersion;
 
+/// A native function supplied by the host embedding the VM, rather than by
+///  the Clarity language itself. See `OwnedEnvironment::register_host_function`.
+pub type HostFn = dyn Fn(&[Value], &mut Environment) -> Result<Value>;
+
 pub enum CallableType {
     UserFunction(DefinedFunction),
     NativeFunction(&'static str, NativeHandle, ClarityCostFunction),
@@ -56,6 +61,7 @@ pub enum CallableType {
         &'static str,
         &'static dyn Fn(&[SymbolicExpression], &mut Environment, &LocalContext) -> Result<Value>,
     ),
+    HostFunction(ClarityName, ExecutionCost, Rc<HostFn>),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -358,6 +364,10 @@ impl DefinedFunction {
         &self.arg_types
     }
 
+    pub fn get_body(&self) -> &SymbolicExpression {
+        &self.body
+    }
+
     pub fn canonicalize_types(&mut self, epoch: &StacksEpochId) {
         for i in 0..self.arguments.len() {
             self.arg_types[i] = self.arg_types[i].canonicalize(epoch);
@@ -379,6 +389,18 @@ impl CallableType {
             CallableType::NativeFunction205(s, _, _, _) => {
                 FunctionIdentifier::new_native_function(s)
             }
+            CallableType::HostFunction(s, _, _) => FunctionIdentifier::new_native_function(s),
+        }
+    }
+
+    /// Whether this is one of Clarity's native functions (including special functions),
+    ///  rather than a user-defined or host-provided one.
+    pub fn is_native(&self) -> bool {
+        match self {
+            CallableType::NativeFunction(..)
+            | CallableType::NativeFunction205(..)
+            | CallableType::SpecialFunction(..) => true,
+            CallableType::UserFunction(_) | CallableType::HostFunction(..) => false,
         }
     }
 }
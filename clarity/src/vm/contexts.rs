@@ -15,22 +15,32 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 use std::fmt;
 use std::mem::replace;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 
+use crate::vm::analysis::contract_interface_builder::{
+    ContractInterfaceFunctionAccess, ContractInterfaceFungibleTokens, ContractInterfaceMap,
+    ContractInterfaceNonFungibleTokens, ContractInterfaceVariable, ContractInterfaceVariableAccess,
+    ContractRuntimeInterface, ContractRuntimeInterfaceFunction,
+};
+use crate::vm::analysis::{self, AnalysisDatabase, CheckError, ContractAnalysis};
 use crate::vm::ast;
 use crate::vm::ast::ASTRules;
 use crate::vm::ast::ContractAST;
-use crate::vm::callables::{DefinedFunction, FunctionIdentifier};
-use crate::vm::contracts::Contract;
+use crate::vm::callables::{CallableType, DefineType, DefinedFunction, FunctionIdentifier, HostFn};
+use crate::vm::clarity::Error as ClarityError;
+use crate::vm::contracts::{deployment_order, Contract, ContractLimits};
 use crate::vm::costs::{
     cost_functions, runtime_cost, ClarityCostFunctionReference, CostErrors, CostTracker,
     ExecutionCost, LimitedCostTracker,
 };
 use crate::vm::database::{
-    ClarityDatabase, DataMapMetadata, DataVariableMetadata, FungibleTokenMetadata,
-    NonFungibleTokenMetadata,
+    ClarityBackingStore, ClarityDatabase, DataMapMetadata, DataVariableMetadata,
+    FungibleTokenMetadata, NonFungibleTokenMetadata, StoreType, ValueResult, WriteSet,
+    NULL_BURN_STATE_DB, NULL_HEADER_DB,
 };
 use crate::vm::errors::{
     CheckErrors, InterpreterError, InterpreterResult as Result, RuntimeErrorType,
@@ -40,10 +50,10 @@ use crate::vm::representations::{ClarityName, ContractName, SymbolicExpression};
 use crate::vm::stx_transfer_consolidated;
 use crate::vm::types::signatures::FunctionSignature;
 use crate::vm::types::{
-    AssetIdentifier, BuffData, CallableData, OptionalData, PrincipalData,
+    AssetIdentifier, BuffData, CallableData, FunctionArg, OptionalData, PrincipalData,
     QualifiedContractIdentifier, TraitIdentifier, TypeSignature, Value,
 };
-use crate::vm::{eval, is_reserved};
+use crate::vm::{eval, is_reserved, MAX_CALL_STACK_DEPTH};
 use crate::{types::chainstate::StacksBlockId, types::StacksEpochId};
 
 use crate::vm::costs::cost_functions::ClarityCostFunction;
@@ -57,6 +67,10 @@ use super::EvalHook;
 
 pub const MAX_CONTEXT_DEPTH: u16 = 256;
 
+/// How many expressions are evaluated between wall-clock checks when an
+///  execution timeout is set. See `GlobalContext::set_execution_timeout`.
+pub const EVAL_STEPS_PER_TIMEOUT_CHECK: u64 = 1024;
+
 // TODO:
 //    hide the environment's instance variables.
 //     we don't want many of these changing after instantiation.
@@ -82,6 +96,25 @@ pub struct OwnedEnvironment<'a, 'hooks> {
     call_stack: CallStack,
 }
 
+/// A point in an `OwnedEnvironment`'s database history, opened by
+///  `OwnedEnvironment::snapshot`. Writes made after it are held in memory until it
+///  is committed or rolled back.
+pub struct DatabaseSnapshot {
+    depth: usize,
+}
+
+/// How `+`, `-` and `*` treat results that do not fit their integer type.
+///  Consensus execution always uses `Abort`: `Wrapping` can only be selected
+///  by non-consensus harnesses (fuzzers, differential testers) built with the
+///  `testing` feature, and never on a mainnet context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithmeticMode {
+    /// Raise `ArithmeticOverflow` / `ArithmeticUnderflow`.
+    Abort,
+    /// Wrap around at the bounds of the integer type.
+    Wrapping,
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum AssetMapEntry {
     STX(u128),
@@ -90,9 +123,31 @@ pub enum AssetMapEntry {
     Asset(Vec<Value>),
 }
 
+/// The net movement of STX or of a fungible token for one principal: what it received
+///  minus what it sent, minted or burned. At most one of `credited` and `debited` is
+///  non-zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NetAmount {
+    pub credited: u128,
+    pub debited: u128,
+}
+
+/// The net movement of a non-fungible asset for one principal: the asset ids it ended up
+///  receiving and the ones it ended up giving away. An id that is received and then sent
+///  back (or the other way around) appears in neither.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NetAssets {
+    pub received: HashSet<Value>,
+    pub sent: HashSet<Value>,
+}
+
 /**
 The AssetMap is used to track which assets have been transfered from whom
 during the execution of a transaction.
+
+Alongside the assets sent by each principal, it keeps a reconciled net view of
+the STX, fungible and non-fungible movements of every principal (including
+mints and burns), for checking post-conditions on the outcome of a call.
 */
 #[derive(Debug, Clone)]
 pub struct AssetMap {
@@ -100,6 +155,52 @@ pub struct AssetMap {
     burn_map: HashMap<PrincipalData, u128>,
     token_map: HashMap<PrincipalData, HashMap<AssetIdentifier, u128>>,
     asset_map: HashMap<PrincipalData, HashMap<AssetIdentifier, Vec<Value>>>,
+    net_amounts: HashMap<PrincipalData, HashMap<AssetIdentifier, NetAmount>>,
+    net_assets: HashMap<PrincipalData, HashMap<AssetIdentifier, NetAssets>>,
+}
+
+impl NetAmount {
+    // the net amounts are bounded by the balances they change, so these saturating
+    //  additions never saturate in practice, and recording a movement cannot fail.
+    fn credit(&mut self, amount: u128) {
+        if amount >= self.debited {
+            self.credited = self.credited.saturating_add(amount - self.debited);
+            self.debited = 0;
+        } else {
+            self.debited -= amount;
+        }
+    }
+
+    fn debit(&mut self, amount: u128) {
+        if amount >= self.credited {
+            self.debited = self.debited.saturating_add(amount - self.credited);
+            self.credited = 0;
+        } else {
+            self.credited -= amount;
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.credited == 0 && self.debited == 0
+    }
+}
+
+impl NetAssets {
+    fn receive(&mut self, asset: Value) {
+        if !self.sent.remove(&asset) {
+            self.received.insert(asset);
+        }
+    }
+
+    fn send(&mut self, asset: Value) {
+        if !self.received.remove(&asset) {
+            self.sent.insert(asset);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.received.is_empty() && self.sent.is_empty()
+    }
 }
 
 impl AssetMap {
@@ -189,6 +290,13 @@ pub struct EventBatch {
     pub events: Vec<StacksTransactionEvent>,
 }
 
+/// The values read by `var-get` and `map-get?`, by database key, with their serialized
+///  sizes. See `GlobalContext::set_read_cache_capacity`.
+struct ReadCache {
+    capacity: usize,
+    entries: HashMap<String, (Value, u64)>,
+}
+
 /** GlobalContext represents the outermost context for a single transaction's
      execution. It tracks an asset changes that occurred during the
      processing of the transaction, whether or not the current context is read_only,
@@ -207,23 +315,47 @@ pub struct GlobalContext<'a, 'hooks> {
     /// This is the chain ID of the transaction
     pub chain_id: u32,
     pub eval_hooks: Option<Vec<&'hooks mut dyn EvalHook>>,
+    host_functions: HashMap<ClarityName, (ExecutionCost, Rc<HostFn>)>,
+    /// Maximum number of events that may be pending across all open event batches.
+    ///  `None` leaves event emission unbounded.
+    max_events: Option<usize>,
+    /// Lowers the call stack depth limit below `MAX_CALL_STACK_DEPTH`. `None` keeps
+    ///  the consensus limit.
+    max_call_stack_depth: Option<usize>,
+    arithmetic_mode: ArithmeticMode,
+    /// Enables natives meant only for in-language contract tests, such as
+    ///  `assert-eq!`. Always off in consensus execution.
+    debug_mode: bool,
+    /// Optional wall-clock budget for each outermost execution. `None` (the default)
+    ///  disables the check, as consensus execution must not depend on timing.
+    execution_timeout: Option<Duration>,
+    execution_deadline: Option<Instant>,
+    eval_steps: u64,
+    /// Size limits checked before deploying a contract. `None` (the default) checks
+    ///  nothing, as consensus deployment is only bounded by costs.
+    contract_limits: Option<ContractLimits>,
+    /// Memoized data var and map reads. `None` (the default) reads every value from
+    ///  the database.
+    read_cache: Option<ReadCache>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct ContractContext {
     pub contract_identifier: QualifiedContractIdentifier,
-    pub variables: HashMap<ClarityName, Value>,
-    pub functions: HashMap<ClarityName, DefinedFunction>,
-    pub defined_traits: HashMap<ClarityName, BTreeMap<ClarityName, FunctionSignature>>,
-    pub implemented_traits: HashSet<TraitIdentifier>,
+    // definitions are kept in sorted maps so that everything derived from iterating
+    //  them (interfaces, serialized metadata) is deterministic.
+    pub variables: BTreeMap<ClarityName, Value>,
+    pub functions: BTreeMap<ClarityName, DefinedFunction>,
+    pub defined_traits: BTreeMap<ClarityName, BTreeMap<ClarityName, FunctionSignature>>,
+    pub implemented_traits: BTreeSet<TraitIdentifier>,
     // tracks the names of NFTs, FTs, Maps, and Data Vars.
     //  used for ensuring that they never are defined twice.
-    pub persisted_names: HashSet<ClarityName>,
+    pub persisted_names: BTreeSet<ClarityName>,
     // track metadata for contract defined storage
-    pub meta_data_map: HashMap<ClarityName, DataMapMetadata>,
-    pub meta_data_var: HashMap<ClarityName, DataVariableMetadata>,
-    pub meta_nft: HashMap<ClarityName, NonFungibleTokenMetadata>,
-    pub meta_ft: HashMap<ClarityName, FungibleTokenMetadata>,
+    pub meta_data_map: BTreeMap<ClarityName, DataMapMetadata>,
+    pub meta_data_var: BTreeMap<ClarityName, DataVariableMetadata>,
+    pub meta_nft: BTreeMap<ClarityName, NonFungibleTokenMetadata>,
+    pub meta_ft: BTreeMap<ClarityName, FungibleTokenMetadata>,
     pub data_size: u64,
     /// track the clarity version of the contract
     clarity_version: ClarityVersion,
@@ -254,6 +386,8 @@ impl AssetMap {
             burn_map: HashMap::new(),
             token_map: HashMap::new(),
             asset_map: HashMap::new(),
+            net_amounts: HashMap::new(),
+            net_assets: HashMap::new(),
         }
     }
 
@@ -342,6 +476,65 @@ impl AssetMap {
         Ok(())
     }
 
+    fn net_amount_mut(
+        &mut self,
+        principal: &PrincipalData,
+        asset: &AssetIdentifier,
+    ) -> &mut NetAmount {
+        self.net_amounts
+            .entry(principal.clone())
+            .or_default()
+            .entry(asset.clone())
+            .or_default()
+    }
+
+    fn net_assets_mut(
+        &mut self,
+        principal: &PrincipalData,
+        asset: &AssetIdentifier,
+    ) -> &mut NetAssets {
+        self.net_assets
+            .entry(principal.clone())
+            .or_default()
+            .entry(asset.clone())
+            .or_default()
+    }
+
+    /// Record a movement of `amount` STX (`AssetIdentifier::STX()`) or fungible tokens in
+    ///  the net view: `from` is debited, unless this is a mint, and `to` is credited,
+    ///  unless this is a burn.
+    pub fn add_net_transfer(
+        &mut self,
+        from: Option<&PrincipalData>,
+        to: Option<&PrincipalData>,
+        asset: &AssetIdentifier,
+        amount: u128,
+    ) {
+        if let Some(from) = from {
+            self.net_amount_mut(from, asset).debit(amount);
+        }
+        if let Some(to) = to {
+            self.net_amount_mut(to, asset).credit(amount);
+        }
+    }
+
+    /// Record a movement of the non-fungible asset `transfered` in the net view, like
+    ///  `add_net_transfer`.
+    pub fn add_net_asset_transfer(
+        &mut self,
+        from: Option<&PrincipalData>,
+        to: Option<&PrincipalData>,
+        asset: &AssetIdentifier,
+        transfered: &Value,
+    ) {
+        if let Some(from) = from {
+            self.net_assets_mut(from, asset).send(transfered.clone());
+        }
+        if let Some(to) = to {
+            self.net_assets_mut(to, asset).receive(transfered.clone());
+        }
+    }
+
     // This will add any asset transfer data from other to self,
     //   aborting _all_ changes in the event of an error, leaving self unchanged
     pub fn commit_other(&mut self, mut other: AssetMap) -> Result<()> {
@@ -367,6 +560,26 @@ impl AssetMap {
         }
 
         // After this point, this function will not fail.
+        for (principal, mut principal_map) in other.net_amounts.drain() {
+            for (asset, net_amount) in principal_map.drain() {
+                let landing = self.net_amount_mut(&principal, &asset);
+                landing.credit(net_amount.credited);
+                landing.debit(net_amount.debited);
+            }
+        }
+
+        for (principal, mut principal_map) in other.net_assets.drain() {
+            for (asset, net_assets) in principal_map.drain() {
+                let landing = self.net_assets_mut(&principal, &asset);
+                for received in net_assets.received {
+                    landing.receive(received);
+                }
+                for sent in net_assets.sent {
+                    landing.send(sent);
+                }
+            }
+        }
+
         for (principal, mut principal_map) in other.asset_map.drain() {
             for (asset, mut transfers) in principal_map.drain() {
                 if !self.asset_map.contains_key(&principal) {
@@ -506,6 +719,44 @@ impl AssetMap {
             None => None,
         }
     }
+
+    /// The net movement of STX (`AssetIdentifier::STX()`) or of a fungible token for
+    ///  `principal`.
+    pub fn get_net_amount(
+        &self,
+        principal: &PrincipalData,
+        asset_identifier: &AssetIdentifier,
+    ) -> NetAmount {
+        self.net_amounts
+            .get(principal)
+            .and_then(|assets| assets.get(asset_identifier))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The net movement of a non-fungible asset for `principal`.
+    pub fn get_net_assets(
+        &self,
+        principal: &PrincipalData,
+        asset_identifier: &AssetIdentifier,
+    ) -> NetAssets {
+        self.net_assets
+            .get(principal)
+            .and_then(|assets| assets.get(asset_identifier))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Whether every movement recorded in the net view was offset by another one.
+    pub fn is_net_zero(&self) -> bool {
+        self.net_amounts
+            .values()
+            .all(|assets| assets.values().all(NetAmount::is_zero))
+            && self
+                .net_assets
+                .values()
+                .all(|assets| assets.values().all(NetAssets::is_empty))
+    }
 }
 
 impl fmt::Display for AssetMap {
@@ -627,6 +878,41 @@ impl<'a, 'hooks> OwnedEnvironment<'a, 'hooks> {
         }
     }
 
+    /// Instantiate a testnet environment like `new`, over `store`, and seed the STX balances
+    ///  of `balances` with `seed_stx_balances` before anything is executed.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn memory_with_balances(
+        store: &'a mut crate::vm::database::MemoryBackingStore,
+        epoch: StacksEpochId,
+        balances: &[(PrincipalData, u128)],
+    ) -> Result<OwnedEnvironment<'a, 'a>> {
+        let mut owned_env = OwnedEnvironment::new(store.as_clarity_db(), epoch);
+        owned_env.seed_stx_balances(balances)?;
+        Ok(owned_env)
+    }
+
+    /// Credit each principal of `balances` with its amount of uSTX, as a genesis would: no
+    ///  transfer is executed, so no events are emitted and no asset map records the
+    ///  credits, but the liquid supply grows by their total. Either every balance is
+    ///  seeded, or, if the liquid supply would overflow, none is.
+    pub fn seed_stx_balances(&mut self, balances: &[(PrincipalData, u128)]) -> Result<()> {
+        let database = &mut self.context.database;
+        database.begin();
+        for (principal, amount) in balances.iter() {
+            // the liquid supply bounds every balance, so crediting cannot overflow once
+            //  the supply has grown
+            if let Err(e) = database.increment_ustx_liquid_supply(*amount) {
+                database.roll_back();
+                return Err(e);
+            }
+            let mut snapshot = database.get_stx_balance_snapshot(principal);
+            snapshot.credit(*amount);
+            snapshot.save();
+        }
+        database.commit();
+        Ok(())
+    }
+
     pub fn get_exec_environment<'b>(
         &'b mut self,
         sender: Option<PrincipalData>,
@@ -746,6 +1032,86 @@ impl<'a, 'hooks> OwnedEnvironment<'a, 'hooks> {
         )
     }
 
+    /// Analyze and initialize several contracts, each one only after the contracts of
+    ///  `contracts` that it references, so that the input order does not matter. The
+    ///  contracts are deployed one at a time, and each analysis is stored before the
+    ///  next contract is analyzed. A reference cycle fails with
+    ///  `CheckErrors::CircularReference` before anything is deployed. Returns the
+    ///  analyses in deployment order. Like the other deployment methods, this must be
+    ///  called at the top level.
+    pub fn deploy_many(
+        &mut self,
+        contracts: &[(QualifiedContractIdentifier, &str)],
+        clarity_version: ClarityVersion,
+        ast_rules: ASTRules,
+    ) -> std::result::Result<Vec<ContractAnalysis>, ClarityError> {
+        let epoch = self.context.epoch_id;
+        let mut parsed = Vec::with_capacity(contracts.len());
+        for (contract_identifier, contract_content) in contracts.iter() {
+            if let Some(limits) = self.context.contract_limits() {
+                limits
+                    .check_source(contract_content)
+                    .map_err(CheckError::new)?;
+            }
+            let contract_ast = ast::build_ast_with_rules(
+                contract_identifier,
+                contract_content,
+                &mut self.context.cost_track,
+                clarity_version,
+                epoch,
+                ast_rules,
+            )?;
+            if let Some(limits) = self.context.contract_limits() {
+                limits
+                    .check_expressions(&contract_ast.expressions)
+                    .map_err(CheckError::new)?;
+            }
+            parsed.push((contract_identifier.clone(), contract_ast));
+        }
+        let order = deployment_order(&parsed).map_err(CheckError::new)?;
+
+        let mut analyses = Vec::with_capacity(contracts.len());
+        for index in order {
+            let (contract_identifier, contract_content) = &contracts[index];
+            let contract_ast = &mut parsed[index].1;
+
+            let cost_track = replace(&mut self.context.cost_track, LimitedCostTracker::new_free());
+            let result = self.context.database.store.with_backing_store(|store| {
+                analysis::run_analysis(
+                    contract_identifier,
+                    &mut contract_ast.expressions,
+                    &mut AnalysisDatabase::new(store),
+                    false,
+                    cost_track,
+                    epoch,
+                    clarity_version,
+                )
+            });
+            let mut contract_analysis = match result {
+                Ok(contract_analysis) => contract_analysis,
+                Err((e, cost_track)) => {
+                    self.context.cost_track = cost_track;
+                    return Err(e.into());
+                }
+            };
+            self.context.cost_track = contract_analysis.take_contract_cost_tracker();
+
+            self.initialize_contract_from_ast(
+                contract_identifier.clone(),
+                clarity_version,
+                contract_ast,
+                contract_content,
+                None,
+            )?;
+            self.context.database.store.with_backing_store(|store| {
+                AnalysisDatabase::new(store)
+                    .execute(|db| db.insert_contract(contract_identifier, &contract_analysis))
+            })?;
+            analyses.push(contract_analysis);
+        }
+        Ok(analyses)
+    }
+
     pub fn execute_transaction(
         &mut self,
         sender: PrincipalData,
@@ -850,6 +1216,58 @@ impl<'a, 'hooks> OwnedEnvironment<'a, 'hooks> {
         self.context.cost_track.get_total()
     }
 
+    /// Open a snapshot of the database, so that `diff_since` can report what is written
+    ///  after this point. Snapshots nest, and must be closed (with `commit_snapshot` or
+    ///  `rollback_snapshot`) in the reverse order that they were opened.
+    pub fn snapshot(&mut self) -> DatabaseSnapshot {
+        let depth = self.context.database.store.depth();
+        self.context.database.begin();
+        DatabaseSnapshot { depth }
+    }
+
+    /// The database keys written since `snapshot` was opened, in order of their first
+    ///  write, as `(key, value at the snapshot, current value)`. Values are in their
+    ///  serialized form, and a key that was unset at the snapshot has no old value.
+    pub fn diff_since(
+        &mut self,
+        snapshot: &DatabaseSnapshot,
+    ) -> Vec<(String, Option<String>, String)> {
+        self.context.database.store.edits_since(snapshot.depth)
+    }
+
+    /// Start recording the data writes of each transaction committed to the backing
+    ///  store, so that `last_write_set` can report them.
+    pub fn record_write_sets(&mut self) {
+        self.context.database.record_write_sets()
+    }
+
+    /// The data writes of the last transaction committed to the backing store that
+    ///  wrote any data, in the canonical order of `WriteSet`. `None` unless
+    ///  `record_write_sets` was called.
+    pub fn last_write_set(&self) -> Option<&WriteSet> {
+        self.context.database.last_write_set()
+    }
+
+    /// Close `snapshot`, keeping the writes made since it was opened.
+    pub fn commit_snapshot(&mut self, snapshot: DatabaseSnapshot) {
+        self.assert_innermost_snapshot(&snapshot);
+        self.context.database.commit();
+    }
+
+    /// Close `snapshot`, discarding the writes made since it was opened.
+    pub fn rollback_snapshot(&mut self, snapshot: DatabaseSnapshot) {
+        self.assert_innermost_snapshot(&snapshot);
+        self.context.database.roll_back();
+    }
+
+    fn assert_innermost_snapshot(&mut self, snapshot: &DatabaseSnapshot) {
+        assert_eq!(
+            self.context.database.store.depth(),
+            snapshot.depth + 1,
+            "ERROR: snapshots must be closed in the reverse order that they were opened"
+        );
+    }
+
     /// Destroys this environment, returning ownership of its database reference.
     ///  If the context wasn't top-level (i.e., it had uncommitted data), return None,
     ///   because the database is not guaranteed to be in a sane state.
@@ -857,6 +1275,83 @@ impl<'a, 'hooks> OwnedEnvironment<'a, 'hooks> {
         self.context.destruct()
     }
 
+    /// Register a host-provided native function, callable from Clarity code evaluated in
+    ///  this environment as `(name args...)`. The arguments are evaluated before `function`
+    ///  is invoked. Host functions are not visible to the static analysis passes, so
+    ///  they are only usable from code that is executed without being type-checked.
+    ///
+    /// Each call is charged `cost` against the environment's cost tracker before
+    ///  `function` runs, in addition to any costs `function` incurs itself.
+    ///
+    /// Returns `CheckErrors::NameAlreadyUsed` if `name` is reserved by the language
+    ///  (in any Clarity version) or has already been registered.
+    pub fn register_host_function<F>(
+        &mut self,
+        name: &str,
+        cost: ExecutionCost,
+        function: F,
+    ) -> Result<()>
+    where
+        F: Fn(&[Value], &mut Environment) -> Result<Value> + 'static,
+    {
+        let name = ClarityName::try_from(name.to_string())?;
+        // Clarity3 reserves every name an earlier version reserves, including
+        //  those of natives no epoch has activated yet
+        if is_reserved(&name, &ClarityVersion::Clarity3)
+            || self.context.host_functions.contains_key(&name)
+        {
+            return Err(CheckErrors::NameAlreadyUsed(name.to_string()).into());
+        }
+        self.context
+            .host_functions
+            .insert(name, (cost, Rc::new(function)));
+        Ok(())
+    }
+
+    /// Limit the number of events a transaction in this environment may emit.
+    ///  See `GlobalContext::set_max_events`.
+    pub fn set_max_events(&mut self, max_events: Option<usize>) {
+        self.context.set_max_events(max_events)
+    }
+
+    /// Lower the call stack depth limit of this environment.
+    ///  See `GlobalContext::set_max_call_stack_depth`.
+    pub fn set_max_call_stack_depth(&mut self, max_call_stack_depth: Option<usize>) {
+        self.context.set_max_call_stack_depth(max_call_stack_depth)
+    }
+
+    /// Bound the wall-clock time of each execution in this environment.
+    ///  See `GlobalContext::set_execution_timeout`.
+    pub fn set_execution_timeout(&mut self, timeout: Option<Duration>) {
+        self.context.set_execution_timeout(timeout)
+    }
+
+    /// Memoize data var and map reads within each transaction in this environment.
+    ///  See `GlobalContext::set_read_cache_capacity`.
+    pub fn set_read_cache_capacity(&mut self, capacity: Option<usize>) {
+        self.context.set_read_cache_capacity(capacity)
+    }
+
+    /// Limit the size of the contracts deployed in this environment.
+    ///  See `GlobalContext::set_contract_limits`.
+    pub fn set_contract_limits(&mut self, limits: Option<ContractLimits>) {
+        self.context.set_contract_limits(limits)
+    }
+
+    /// Select how `+`, `-` and `*` handle overflow in this environment.
+    ///  See `GlobalContext::set_arithmetic_mode`.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn set_arithmetic_mode(&mut self, mode: ArithmeticMode) {
+        self.context.set_arithmetic_mode(mode)
+    }
+
+    /// Allow debug-only natives in this environment.
+    ///  See `GlobalContext::set_debug_mode`.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn set_debug_mode(&mut self, enabled: bool) {
+        self.context.set_debug_mode(enabled)
+    }
+
     pub fn add_eval_hook(&mut self, hook: &'hooks mut dyn EvalHook) {
         if let Some(mut hooks) = self.context.eval_hooks.take() {
             hooks.push(hook);
@@ -1045,6 +1540,50 @@ impl<'a, 'b, 'hooks> Environment<'a, 'b, 'hooks> {
         self.eval_read_only_with_rules(contract_identifier, program, ast::ASTRules::Typical)
     }
 
+    /// Read the data var `var_name` of the deployed contract `contract_identifier`, for host
+    ///  embedders doing off-chain reads. Contracts read each other's state through read-only
+    ///  `contract-call?`s instead. Nothing is written, and the read is charged like a
+    ///  `var-get`. Fails with `CheckErrors::NoSuchContract` if the contract does not exist,
+    ///  and with `CheckErrors::UndefinedVariable` if it does not define `var_name`.
+    pub fn lookup_variable_in_contract(
+        &mut self,
+        contract_identifier: &QualifiedContractIdentifier,
+        var_name: &str,
+    ) -> Result<Value> {
+        if !self
+            .global_context
+            .database
+            .has_contract(contract_identifier)
+        {
+            return Err(CheckErrors::NoSuchContract(contract_identifier.to_string()).into());
+        }
+
+        let data_types = match self
+            .global_context
+            .database
+            .load_variable(contract_identifier, var_name)
+        {
+            Ok(data_types) => data_types,
+            Err(crate::vm::errors::Error::Unchecked(CheckErrors::NoSuchDataVariable(_))) => {
+                return Err(CheckErrors::UndefinedVariable(var_name.to_string()).into())
+            }
+            Err(e) => return Err(e),
+        };
+
+        let result = self.global_context.database.lookup_variable_with_size(
+            contract_identifier,
+            var_name,
+            &data_types,
+        );
+        let result_size = match &result {
+            Ok(data) => data.serialized_byte_len,
+            Err(_e) => data_types.value_type.size() as u64,
+        };
+        runtime_cost(ClarityCostFunction::FetchVar, self, result_size)?;
+
+        result.map(|data| data.value)
+    }
+
     pub fn eval_raw_with_rules(&mut self, program: &str, rules: ast::ASTRules) -> Result<Value> {
         let contract_id = QualifiedContractIdentifier::transient();
         let clarity_version = self.contract_context.clarity_version.clone();
@@ -1101,6 +1640,22 @@ impl<'a, 'b, 'hooks> Environment<'a, 'b, 'hooks> {
         &self.global_context.epoch_id
     }
 
+    /// The contract whose code is currently executing. Inside a `contract-call?`
+    ///  this is the callee.
+    pub fn contract_identifier(&self) -> &QualifiedContractIdentifier {
+        &self.contract_context.contract_identifier
+    }
+
+    /// The principal `tx-sender` evaluates to in this environment.
+    pub fn sender(&self) -> Option<&PrincipalData> {
+        self.sender.as_ref()
+    }
+
+    /// The principal `contract-caller` evaluates to in this environment.
+    pub fn caller(&self) -> Option<&PrincipalData> {
+        self.caller.as_ref()
+    }
+
     pub fn execute_contract(
         &mut self,
         contract: &QualifiedContractIdentifier,
@@ -1197,6 +1752,9 @@ impl<'a, 'b, 'hooks> Environment<'a, 'b, 'hooks> {
         })
     }
 
+    /// Runs a public or read-only function in its own checkpoint. An `(err ...)`
+    ///  result (including one propagated by `try!` or `asserts!`) rolls back exactly
+    ///  the writes made within this call, leaving the caller's earlier writes in place.
     pub fn execute_function_as_transaction(
         &mut self,
         function: &DefinedFunction,
@@ -1241,6 +1799,9 @@ impl<'a, 'b, 'hooks> Environment<'a, 'b, 'hooks> {
         local: &LocalContext,
     ) -> Result<Value> {
         self.global_context.begin_read_only();
+        // reads at another block must not be served from (or fill) the current block's
+        //  cache: this context is rolled back afterwards, which empties it again.
+        self.global_context.clear_read_cache();
 
         let result = self
             .global_context
@@ -1270,6 +1831,9 @@ impl<'a, 'b, 'hooks> Environment<'a, 'b, 'hooks> {
     ) -> Result<()> {
         let clarity_version = self.contract_context.clarity_version.clone();
 
+        if let Some(limits) = self.global_context.contract_limits() {
+            limits.check_source(contract_content)?;
+        }
         let contract_ast = ast::build_ast_with_rules(
             &contract_identifier,
             contract_content,
@@ -1278,6 +1842,9 @@ impl<'a, 'b, 'hooks> Environment<'a, 'b, 'hooks> {
             self.global_context.epoch_id,
             ast_rules,
         )?;
+        if let Some(limits) = self.global_context.contract_limits() {
+            limits.check_expressions(&contract_ast.expressions)?;
+        }
         self.initialize_contract_from_ast(
             contract_identifier,
             clarity_version,
@@ -1404,10 +1971,19 @@ impl<'a, 'b, 'hooks> Environment<'a, 'b, 'hooks> {
         }
     }
 
-    pub fn push_to_event_batch(&mut self, event: StacksTransactionEvent) {
+    /// Append `event` to the innermost open event batch. Fails with
+    ///  `RuntimeErrorType::EventBalanceExceeded` if this would exceed the global
+    ///  context's event limit.
+    pub fn push_to_event_batch(&mut self, event: StacksTransactionEvent) -> Result<()> {
+        if let Some(max_events) = self.global_context.max_events {
+            if self.global_context.pending_event_count() >= max_events {
+                return Err(RuntimeErrorType::EventBalanceExceeded.into());
+            }
+        }
         if let Some(batch) = self.global_context.event_batches.last_mut() {
             batch.events.push(event);
         }
+        Ok(())
     }
 
     pub fn construct_print_transaction_event(
@@ -1428,8 +2004,7 @@ impl<'a, 'b, 'hooks> Environment<'a, 'b, 'hooks> {
             &value,
         );
 
-        self.push_to_event_batch(event);
-        Ok(())
+        self.push_to_event_batch(event)
     }
 
     pub fn register_stx_transfer_event(
@@ -1447,16 +2022,14 @@ impl<'a, 'b, 'hooks> Environment<'a, 'b, 'hooks> {
         };
         let event = StacksTransactionEvent::STXEvent(STXEventType::STXTransferEvent(event_data));
 
-        self.push_to_event_batch(event);
-        Ok(())
+        self.push_to_event_batch(event)
     }
 
     pub fn register_stx_burn_event(&mut self, sender: PrincipalData, amount: u128) -> Result<()> {
         let event_data = STXBurnEventData { sender, amount };
         let event = StacksTransactionEvent::STXEvent(STXEventType::STXBurnEvent(event_data));
 
-        self.push_to_event_batch(event);
-        Ok(())
+        self.push_to_event_batch(event)
     }
 
     pub fn register_nft_transfer_event(
@@ -1474,8 +2047,7 @@ impl<'a, 'b, 'hooks> Environment<'a, 'b, 'hooks> {
         };
         let event = StacksTransactionEvent::NFTEvent(NFTEventType::NFTTransferEvent(event_data));
 
-        self.push_to_event_batch(event);
-        Ok(())
+        self.push_to_event_batch(event)
     }
 
     pub fn register_nft_mint_event(
@@ -1491,8 +2063,7 @@ impl<'a, 'b, 'hooks> Environment<'a, 'b, 'hooks> {
         };
         let event = StacksTransactionEvent::NFTEvent(NFTEventType::NFTMintEvent(event_data));
 
-        self.push_to_event_batch(event);
-        Ok(())
+        self.push_to_event_batch(event)
     }
 
     pub fn register_nft_burn_event(
@@ -1508,8 +2079,7 @@ impl<'a, 'b, 'hooks> Environment<'a, 'b, 'hooks> {
         };
         let event = StacksTransactionEvent::NFTEvent(NFTEventType::NFTBurnEvent(event_data));
 
-        self.push_to_event_batch(event);
-        Ok(())
+        self.push_to_event_batch(event)
     }
 
     pub fn register_ft_transfer_event(
@@ -1527,8 +2097,7 @@ impl<'a, 'b, 'hooks> Environment<'a, 'b, 'hooks> {
         };
         let event = StacksTransactionEvent::FTEvent(FTEventType::FTTransferEvent(event_data));
 
-        self.push_to_event_batch(event);
-        Ok(())
+        self.push_to_event_batch(event)
     }
 
     pub fn register_ft_mint_event(
@@ -1544,8 +2113,7 @@ impl<'a, 'b, 'hooks> Environment<'a, 'b, 'hooks> {
         };
         let event = StacksTransactionEvent::FTEvent(FTEventType::FTMintEvent(event_data));
 
-        self.push_to_event_batch(event);
-        Ok(())
+        self.push_to_event_batch(event)
     }
 
     pub fn register_ft_burn_event(
@@ -1561,8 +2129,7 @@ impl<'a, 'b, 'hooks> Environment<'a, 'b, 'hooks> {
         };
         let event = StacksTransactionEvent::FTEvent(FTEventType::FTBurnEvent(event_data));
 
-        self.push_to_event_batch(event);
-        Ok(())
+        self.push_to_event_batch(event)
     }
 }
 
@@ -1574,7 +2141,7 @@ impl<'a, 'hooks> GlobalContext<'a, 'hooks> {
         database: ClarityDatabase<'a>,
         cost_track: LimitedCostTracker,
         epoch_id: StacksEpochId,
-    ) -> GlobalContext {
+    ) -> GlobalContext<'a, 'hooks> {
         GlobalContext {
             database,
             cost_track,
@@ -1585,9 +2152,273 @@ impl<'a, 'hooks> GlobalContext<'a, 'hooks> {
             epoch_id,
             chain_id,
             eval_hooks: None,
+            host_functions: HashMap::new(),
+            max_events: None,
+            max_call_stack_depth: None,
+            arithmetic_mode: ArithmeticMode::Abort,
+            debug_mode: false,
+            execution_timeout: None,
+            execution_deadline: None,
+            eval_steps: 0,
+            contract_limits: None,
+            read_cache: None,
+        }
+    }
+
+    /// Instantiate a testnet Global Context with a free cost tracker over any
+    ///  backing store. No block headers or burnchain state are available through
+    ///  it: callers that need them should build a `ClarityDatabase` and use `new`.
+    pub fn with_database(
+        store: &'a mut dyn ClarityBackingStore,
+        epoch_id: StacksEpochId,
+    ) -> GlobalContext<'a, 'hooks> {
+        GlobalContext::new(
+            false,
+            CHAIN_ID_TESTNET,
+            ClarityDatabase::new(store, &NULL_HEADER_DB, &NULL_BURN_STATE_DB),
+            LimitedCostTracker::new_free(),
+            epoch_id,
+        )
+    }
+
+    /// Look up a host-provided function by name. Built-in natives take precedence
+    ///  over these: registration rejects any name reserved by the language.
+    pub fn lookup_host_function(&self, name: &str) -> Option<CallableType> {
+        self.host_functions
+            .get_key_value(name)
+            .map(|(name, (cost, function))| {
+                CallableType::HostFunction(name.clone(), cost.clone(), function.clone())
+            })
+    }
+
+    /// Limit the number of events that may be pending across all open event batches.
+    ///  Events in batches that are rolled back no longer count against the limit.
+    pub fn set_max_events(&mut self, max_events: Option<usize>) {
+        self.max_events = max_events;
+    }
+
+    /// Lower the maximum call stack depth of this context. The limit can only be lowered:
+    ///  a depth above `MAX_CALL_STACK_DEPTH` has no effect, as the parser's nesting limit
+    ///  is derived from it.
+    pub fn set_max_call_stack_depth(&mut self, max_call_stack_depth: Option<usize>) {
+        self.max_call_stack_depth = max_call_stack_depth;
+    }
+
+    pub fn max_call_stack_depth(&self) -> usize {
+        self.max_call_stack_depth
+            .map_or(MAX_CALL_STACK_DEPTH, |depth| {
+                depth.min(MAX_CALL_STACK_DEPTH)
+            })
+    }
+
+    /// Select how `+`, `-` and `*` handle overflow. Only available to non-consensus
+    ///  harnesses: panics if asked to wrap on a mainnet context.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn set_arithmetic_mode(&mut self, mode: ArithmeticMode) {
+        assert!(
+            !self.mainnet || mode == ArithmeticMode::Abort,
+            "wrapping arithmetic is not permitted on mainnet"
+        );
+        self.arithmetic_mode = mode;
+    }
+
+    pub fn arithmetic_mode(&self) -> ArithmeticMode {
+        self.arithmetic_mode
+    }
+
+    /// Allow debug-only natives such as `assert-eq!`. Only available to
+    ///  non-consensus harnesses: panics if enabled on a mainnet context.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn set_debug_mode(&mut self, enabled: bool) {
+        assert!(
+            !self.mainnet || !enabled,
+            "debug mode is not permitted on mainnet"
+        );
+        self.debug_mode = enabled;
+    }
+
+    pub fn is_debug_mode(&self) -> bool {
+        self.debug_mode
+    }
+
+    /// Abort any outermost execution (a transaction, contract initialization or
+    ///  raw evaluation) that runs longer than `timeout` of wall-clock time with
+    ///  `RuntimeErrorType::ExecutionTimeout`. Intended for integrators running
+    ///  untrusted code outside of consensus; `None` disables the check.
+    pub fn set_execution_timeout(&mut self, timeout: Option<Duration>) {
+        self.execution_timeout = timeout;
+        self.execution_deadline = None;
+    }
+
+    /// Reject contracts exceeding `limits` when they are deployed, before they are
+    ///  parsed or analyzed. Intended for integrators loading untrusted contracts
+    ///  outside of consensus; `None` disables the check.
+    pub fn set_contract_limits(&mut self, limits: Option<ContractLimits>) {
+        self.contract_limits = limits;
+    }
+
+    pub fn contract_limits(&self) -> Option<&ContractLimits> {
+        self.contract_limits.as_ref()
+    }
+
+    /// Memoize the values read by `var-get` and `map-get?` within each transaction, up
+    ///  to `capacity` of them, so that repeated reads of a key only reach the database
+    ///  once. Writing a key through `var-set`, `map-set`, `map-insert` or `map-delete`
+    ///  evicts it, and rolling back any context empties the cache. Writes made directly
+    ///  through `database` are not seen, so this is only intended for integrators running
+    ///  contracts outside of consensus; `None` (the default) disables the cache.
+    pub fn set_read_cache_capacity(&mut self, capacity: Option<usize>) {
+        self.read_cache = capacity.map(|capacity| ReadCache {
+            capacity,
+            entries: HashMap::new(),
+        });
+    }
+
+    /// `ClarityDatabase::lookup_variable_with_size`, through the read cache.
+    pub fn lookup_variable_with_size(
+        &mut self,
+        contract_identifier: &QualifiedContractIdentifier,
+        variable_name: &str,
+        variable_descriptor: &DataVariableMetadata,
+    ) -> Result<ValueResult> {
+        if self.read_cache.is_none() {
+            return self.database.lookup_variable_with_size(
+                contract_identifier,
+                variable_name,
+                variable_descriptor,
+            );
+        }
+        let key = ClarityDatabase::make_key_for_trip(
+            contract_identifier,
+            StoreType::Variable,
+            variable_name,
+        );
+        self.cached_read(key, |database| {
+            database.lookup_variable_with_size(
+                contract_identifier,
+                variable_name,
+                variable_descriptor,
+            )
+        })
+    }
+
+    /// `ClarityDatabase::fetch_entry_with_size`, through the read cache.
+    pub fn fetch_entry_with_size(
+        &mut self,
+        contract_identifier: &QualifiedContractIdentifier,
+        map_name: &str,
+        key_value: &Value,
+        map_descriptor: &DataMapMetadata,
+    ) -> Result<ValueResult> {
+        if self.read_cache.is_none() {
+            return self.database.fetch_entry_with_size(
+                contract_identifier,
+                map_name,
+                key_value,
+                map_descriptor,
+            );
+        }
+        let key =
+            ClarityDatabase::make_key_for_data_map_entry(contract_identifier, map_name, key_value);
+        self.cached_read(key, |database| {
+            database.fetch_entry_with_size(contract_identifier, map_name, key_value, map_descriptor)
+        })
+    }
+
+    fn cached_read<F>(&mut self, key: String, read: F) -> Result<ValueResult>
+    where
+        F: FnOnce(&mut ClarityDatabase<'a>) -> Result<ValueResult>,
+    {
+        if let Some((value, serialized_byte_len)) = self
+            .read_cache
+            .as_ref()
+            .and_then(|cache| cache.entries.get(&key))
+        {
+            return Ok(ValueResult {
+                value: value.clone(),
+                serialized_byte_len: *serialized_byte_len,
+            });
+        }
+        let result = read(&mut self.database)?;
+        if let Some(cache) = self.read_cache.as_mut() {
+            if cache.entries.len() < cache.capacity {
+                cache
+                    .entries
+                    .insert(key, (result.value.clone(), result.serialized_byte_len));
+            }
+        }
+        Ok(result)
+    }
+
+    /// Evict a data var from the read cache, when it is written.
+    pub fn evict_cached_variable(
+        &mut self,
+        contract_identifier: &QualifiedContractIdentifier,
+        variable_name: &str,
+    ) {
+        if let Some(cache) = self.read_cache.as_mut() {
+            cache.entries.remove(&ClarityDatabase::make_key_for_trip(
+                contract_identifier,
+                StoreType::Variable,
+                variable_name,
+            ));
         }
     }
 
+    /// Evict a map entry from the read cache, when it is written.
+    pub fn evict_cached_entry(
+        &mut self,
+        contract_identifier: &QualifiedContractIdentifier,
+        map_name: &str,
+        key_value: &Value,
+    ) {
+        if let Some(cache) = self.read_cache.as_mut() {
+            cache
+                .entries
+                .remove(&ClarityDatabase::make_key_for_data_map_entry(
+                    contract_identifier,
+                    map_name,
+                    key_value,
+                ));
+        }
+    }
+
+    fn clear_read_cache(&mut self) {
+        if let Some(cache) = self.read_cache.as_mut() {
+            cache.entries.clear();
+        }
+    }
+
+    /// Called once per evaluated expression. Reading the clock is comparatively
+    ///  expensive, so it is only consulted every `EVAL_STEPS_PER_TIMEOUT_CHECK` steps.
+    pub fn check_execution_deadline(&mut self) -> Result<()> {
+        if let Some(deadline) = self.execution_deadline {
+            self.eval_steps += 1;
+            if self.eval_steps >= EVAL_STEPS_PER_TIMEOUT_CHECK {
+                self.eval_steps = 0;
+                if Instant::now() > deadline {
+                    return Err(RuntimeErrorType::ExecutionTimeout.into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Start the wall-clock budget when the outermost context is opened.
+    fn start_execution_clock(&mut self) {
+        if self.asset_maps.is_empty() {
+            self.execution_deadline = self.execution_timeout.map(|t| Instant::now() + t);
+            self.eval_steps = 0;
+        }
+    }
+
+    fn pending_event_count(&self) -> usize {
+        self.event_batches
+            .iter()
+            .map(|batch| batch.events.len())
+            .sum()
+    }
+
     pub fn is_top_level(&self) -> bool {
         self.asset_maps.len() == 0
     }
@@ -1636,6 +2467,32 @@ impl<'a, 'hooks> GlobalContext<'a, 'hooks> {
         self.get_asset_map().add_stx_burn(sender, transfered)
     }
 
+    /// Record a STX or fungible token movement in the net view of the asset map. `from` is
+    ///  `None` for a mint, and `to` is `None` for a burn.
+    pub fn log_net_transfer(
+        &mut self,
+        from: Option<&PrincipalData>,
+        to: Option<&PrincipalData>,
+        asset_identifier: &AssetIdentifier,
+        amount: u128,
+    ) {
+        self.get_asset_map()
+            .add_net_transfer(from, to, asset_identifier, amount)
+    }
+
+    /// Record a non-fungible asset movement in the net view of the asset map, like
+    ///  `log_net_transfer`.
+    pub fn log_net_asset_transfer(
+        &mut self,
+        from: Option<&PrincipalData>,
+        to: Option<&PrincipalData>,
+        asset_identifier: &AssetIdentifier,
+        transfered: &Value,
+    ) {
+        self.get_asset_map()
+            .add_net_asset_transfer(from, to, asset_identifier, transfered)
+    }
+
     pub fn execute<F, T>(&mut self, f: F) -> Result<T>
     where
         F: FnOnce(&mut Self) -> Result<T>,
@@ -1693,6 +2550,7 @@ impl<'a, 'hooks> GlobalContext<'a, 'hooks> {
     }
 
     pub fn begin(&mut self) {
+        self.start_execution_clock();
         self.asset_maps.push(AssetMap::new());
         self.event_batches.push(EventBatch::new());
         self.database.begin();
@@ -1701,6 +2559,7 @@ impl<'a, 'hooks> GlobalContext<'a, 'hooks> {
     }
 
     pub fn begin_read_only(&mut self) {
+        self.start_execution_clock();
         self.asset_maps.push(AssetMap::new());
         self.event_batches.push(EventBatch::new());
         self.database.begin();
@@ -1723,6 +2582,7 @@ impl<'a, 'hooks> GlobalContext<'a, 'hooks> {
             Some(tail_back) => {
                 if let Err(e) = tail_back.commit_other(asset_map) {
                     self.database.roll_back();
+                    self.clear_read_cache();
                     return Err(e);
                 }
                 None
@@ -1739,6 +2599,10 @@ impl<'a, 'hooks> GlobalContext<'a, 'hooks> {
         };
 
         self.database.commit();
+        if self.asset_maps.is_empty() {
+            // the read cache only lives as long as the transaction
+            self.clear_read_cache();
+        }
         Ok((out_map, out_batch))
     }
 
@@ -1751,6 +2615,8 @@ impl<'a, 'hooks> GlobalContext<'a, 'hooks> {
         assert!(popped.is_some());
 
         self.database.roll_back();
+        // cached reads may have seen the rolled-back writes
+        self.clear_read_cache();
     }
 
     pub fn handle_tx_result(&mut self, result: Result<Value>) -> Result<Value> {
@@ -1793,16 +2659,16 @@ impl ContractContext {
     ) -> Self {
         Self {
             contract_identifier,
-            variables: HashMap::new(),
-            functions: HashMap::new(),
-            defined_traits: HashMap::new(),
-            implemented_traits: HashSet::new(),
-            persisted_names: HashSet::new(),
+            variables: BTreeMap::new(),
+            functions: BTreeMap::new(),
+            defined_traits: BTreeMap::new(),
+            implemented_traits: BTreeSet::new(),
+            persisted_names: BTreeSet::new(),
             data_size: 0,
-            meta_data_map: HashMap::new(),
-            meta_data_var: HashMap::new(),
-            meta_nft: HashMap::new(),
-            meta_ft: HashMap::new(),
+            meta_data_map: BTreeMap::new(),
+            meta_data_var: BTreeMap::new(),
+            meta_nft: BTreeMap::new(),
+            meta_ft: BTreeMap::new(),
             clarity_version,
         }
     }
@@ -1838,6 +2704,99 @@ impl ContractContext {
         &self.clarity_version
     }
 
+    /// The callable interface of this contract, built from its runtime definitions.
+    /// The runtime does not record the result types inferred by analysis, so functions
+    /// come without outputs: use `build_contract_interface` over the contract's
+    /// `ContractAnalysis` where result types are needed.
+    pub fn get_interface(&self, epoch: StacksEpochId) -> ContractRuntimeInterface {
+        let mut interface = ContractRuntimeInterface::new(epoch, self.clarity_version);
+
+        let mut public_functions = BTreeMap::new();
+        let mut read_only_functions = BTreeMap::new();
+        for (name, function) in self.functions.iter() {
+            let functions = match function.define_type {
+                DefineType::Public => &mut public_functions,
+                DefineType::ReadOnly => &mut read_only_functions,
+                DefineType::Private => continue,
+            };
+            let args = function
+                .get_arguments()
+                .iter()
+                .zip(function.get_arg_types())
+                .map(|(arg_name, arg_type)| FunctionArg::new(arg_type.clone(), arg_name.clone()))
+                .collect();
+            functions.insert(name.clone(), args);
+        }
+        interface
+            .functions
+            .append(&mut ContractRuntimeInterfaceFunction::from_map(
+                &public_functions,
+                ContractInterfaceFunctionAccess::public,
+            ));
+        interface
+            .functions
+            .append(&mut ContractRuntimeInterfaceFunction::from_map(
+                &read_only_functions,
+                ContractInterfaceFunctionAccess::read_only,
+            ));
+
+        let constants = self
+            .variables
+            .iter()
+            .map(|(name, value)| (name.clone(), TypeSignature::type_of(value)))
+            .collect();
+        interface
+            .variables
+            .append(&mut ContractInterfaceVariable::from_map(
+                &constants,
+                ContractInterfaceVariableAccess::constant,
+            ));
+        let data_vars = self
+            .meta_data_var
+            .iter()
+            .map(|(name, metadata)| (name.clone(), metadata.value_type.clone()))
+            .collect();
+        interface
+            .variables
+            .append(&mut ContractInterfaceVariable::from_map(
+                &data_vars,
+                ContractInterfaceVariableAccess::variable,
+            ));
+
+        let maps = self
+            .meta_data_map
+            .iter()
+            .map(|(name, metadata)| {
+                (
+                    name.clone(),
+                    (metadata.key_type.clone(), metadata.value_type.clone()),
+                )
+            })
+            .collect();
+        interface
+            .maps
+            .append(&mut ContractInterfaceMap::from_map(&maps));
+
+        let non_fungible_tokens = self
+            .meta_nft
+            .iter()
+            .map(|(name, metadata)| (name.clone(), metadata.key_type.clone()))
+            .collect();
+        interface
+            .non_fungible_tokens
+            .append(&mut ContractInterfaceNonFungibleTokens::from_map(
+                &non_fungible_tokens,
+            ));
+        let fungible_tokens = self.meta_ft.keys().cloned().collect();
+        interface
+            .fungible_tokens
+            .append(&mut ContractInterfaceFungibleTokens::from_set(
+                &fungible_tokens,
+            ));
+
+        interface
+    }
+
     /// Canonicalize the types for the specified epoch. Only functions and
     /// defined traits are exposed externally, so other types are not
     /// canonicalized.
@@ -1852,6 +2811,100 @@ impl ContractContext {
             }
         }
     }
+
+    /// A deterministic, Clarity-like listing of this contract's definitions, one per
+    ///  line and sorted by name within each kind, suitable for snapshot tests. Function
+    ///  bodies are only included if `with_bodies` is set. `Display` omits them.
+    pub fn to_canonical_string(&self, with_bodies: bool) -> String {
+        let mut out = String::new();
+        self.write_canonical(&mut out, with_bodies)
+            .expect("FATAL: failed to write to a String");
+        out
+    }
+
+    fn write_canonical<W: fmt::Write>(&self, w: &mut W, with_bodies: bool) -> fmt::Result {
+        writeln!(
+            w,
+            "(contract {} \"{}\")",
+            self.contract_identifier, self.clarity_version
+        )?;
+        for (name, value) in self.variables.iter() {
+            writeln!(w, "(define-constant {} {})", name, value)?;
+        }
+        for (name, metadata) in self.meta_data_var.iter() {
+            writeln!(w, "(define-data-var {} {})", name, metadata.value_type)?;
+        }
+        for (name, metadata) in self.meta_data_map.iter() {
+            writeln!(
+                w,
+                "(define-map {} {} {})",
+                name, metadata.key_type, metadata.value_type
+            )?;
+        }
+        for (name, metadata) in self.meta_ft.iter() {
+            match metadata.total_supply {
+                Some(supply) => writeln!(w, "(define-fungible-token {} u{})", name, supply)?,
+                None => writeln!(w, "(define-fungible-token {})", name)?,
+            }
+        }
+        for (name, metadata) in self.meta_nft.iter() {
+            writeln!(
+                w,
+                "(define-non-fungible-token {} {})",
+                name, metadata.key_type
+            )?;
+        }
+        for (name, trait_def) in self.defined_traits.iter() {
+            let signatures: Vec<String> = trait_def
+                .iter()
+                .map(|(function_name, signature)| {
+                    let args: Vec<String> = signature.args.iter().map(|t| t.to_string()).collect();
+                    format!(
+                        "({} ({}) {})",
+                        function_name,
+                        args.join(" "),
+                        signature.returns
+                    )
+                })
+                .collect();
+            writeln!(w, "(define-trait {} ({}))", name, signatures.join(" "))?;
+        }
+        let implemented_traits: BTreeSet<String> = self
+            .implemented_traits
+            .iter()
+            .map(|trait_identifier| trait_identifier.to_string())
+            .collect();
+        for trait_identifier in implemented_traits {
+            writeln!(w, "(impl-trait {})", trait_identifier)?;
+        }
+        for (name, function) in self.functions.iter() {
+            let define = match function.define_type {
+                DefineType::Public => "define-public",
+                DefineType::ReadOnly => "define-read-only",
+                DefineType::Private => "define-private",
+            };
+            write!(w, "({} ({}", define, name)?;
+            for (arg_name, arg_type) in function
+                .get_arguments()
+                .iter()
+                .zip(function.get_arg_types())
+            {
+                write!(w, " ({} {})", arg_name, arg_type)?;
+            }
+            write!(w, ")")?;
+            if with_bodies {
+                write!(w, " {}", function.get_body())?;
+            }
+            writeln!(w, ")")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ContractContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write_canonical(f, false)
+    }
 }
 
 impl<'a> LocalContext<'a> {
@@ -19,11 +19,13 @@ use stacks_common::types::StacksEpochId;
 use crate::vm::ast::ContractAST;
 use crate::vm::callables::CallableType;
 use crate::vm::contexts::{ContractContext, Environment, GlobalContext, LocalContext};
-use crate::vm::errors::InterpreterResult as Result;
-use crate::vm::representations::SymbolicExpression;
+use crate::vm::errors::{CheckErrors, InterpreterResult as Result};
+use crate::vm::functions::define::DefineFunctions;
+use crate::vm::representations::{SymbolicExpression, SymbolicExpressionType, TraitDefinition};
 use crate::vm::types::{PrincipalData, QualifiedContractIdentifier};
 use crate::vm::version::ClarityVersion;
 use crate::vm::{apply, eval_all, Value};
+use std::collections::HashSet;
 use std::convert::TryInto;
 
 #[derive(Serialize, Deserialize)]
@@ -57,3 +59,188 @@ impl Contract {
         self.contract_context.canonicalize_types(epoch);
     }
 }
+
+/// Limits on the size of a contract, checked before it is analyzed so that an oversized
+///  contract fails fast. Consensus deployment does not check them: they are opt-in, for
+///  nodes and tools loading untrusted contracts (see `GlobalContext::set_contract_limits`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContractLimits {
+    /// The maximum length of the contract source, in bytes.
+    pub max_source_bytes: usize,
+    /// The maximum number of top-level `define-*`, `use-trait` and `impl-trait` forms.
+    pub max_definitions: usize,
+    /// The maximum number of expressions in the contract's AST, counting lists and atoms.
+    pub max_ast_nodes: usize,
+}
+
+impl Default for ContractLimits {
+    /// Well above the largest boot contracts, which have a few hundred definitions.
+    fn default() -> ContractLimits {
+        ContractLimits {
+            max_source_bytes: 2 * 1024 * 1024,
+            max_definitions: 4096,
+            max_ast_nodes: 1_000_000,
+        }
+    }
+}
+
+impl ContractLimits {
+    /// Fails with `CheckErrors::ContractSourceTooLarge` if `contract_source` is longer than
+    ///  `max_source_bytes`. This can be checked before parsing.
+    pub fn check_source(&self, contract_source: &str) -> std::result::Result<(), CheckErrors> {
+        if contract_source.len() > self.max_source_bytes {
+            return Err(CheckErrors::ContractSourceTooLarge(
+                contract_source.len(),
+                self.max_source_bytes,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Fails with `CheckErrors::TooManyDefinitions` or `CheckErrors::TooManyASTNodes` if the
+    ///  parsed contract `expressions` exceed `max_definitions` or `max_ast_nodes`.
+    pub fn check_expressions(
+        &self,
+        expressions: &[SymbolicExpression],
+    ) -> std::result::Result<(), CheckErrors> {
+        let definitions = expressions
+            .iter()
+            .filter(|expression| DefineFunctions::try_parse(expression).is_some())
+            .count();
+        if definitions > self.max_definitions {
+            return Err(CheckErrors::TooManyDefinitions(
+                definitions,
+                self.max_definitions,
+            ));
+        }
+        let ast_nodes = count_ast_nodes(expressions);
+        if ast_nodes > self.max_ast_nodes {
+            return Err(CheckErrors::TooManyASTNodes(ast_nodes, self.max_ast_nodes));
+        }
+        Ok(())
+    }
+}
+
+fn count_ast_nodes(expressions: &[SymbolicExpression]) -> usize {
+    expressions
+        .iter()
+        .map(|expression| match expression.match_list() {
+            Some(list) => 1 + count_ast_nodes(list),
+            None => 1,
+        })
+        .sum()
+}
+
+/// Collect the contracts referenced by `expressions`, either through contract principals
+///  (e.g., the target of a `contract-call?`) or through trait identifiers (`use-trait`,
+///  `impl-trait`).
+fn collect_referenced_contracts(
+    expressions: &[SymbolicExpression],
+    referenced: &mut HashSet<QualifiedContractIdentifier>,
+) {
+    for expression in expressions.iter() {
+        match &expression.expr {
+            SymbolicExpressionType::AtomValue(Value::Principal(PrincipalData::Contract(id)))
+            | SymbolicExpressionType::LiteralValue(Value::Principal(PrincipalData::Contract(id))) =>
+            {
+                referenced.insert(id.clone());
+            }
+            SymbolicExpressionType::Field(trait_identifier)
+            | SymbolicExpressionType::TraitReference(
+                _,
+                TraitDefinition::Defined(trait_identifier),
+            )
+            | SymbolicExpressionType::TraitReference(
+                _,
+                TraitDefinition::Imported(trait_identifier),
+            ) => {
+                referenced.insert(trait_identifier.contract_identifier.clone());
+            }
+            SymbolicExpressionType::List(list) => collect_referenced_contracts(list, referenced),
+            SymbolicExpressionType::AtomValue(_)
+            | SymbolicExpressionType::LiteralValue(_)
+            | SymbolicExpressionType::Atom(_) => {}
+        }
+    }
+}
+
+/// The contracts referenced by `expressions`, as collected by `collect_referenced_contracts`.
+pub fn referenced_contracts(
+    expressions: &[SymbolicExpression],
+) -> HashSet<QualifiedContractIdentifier> {
+    let mut referenced = HashSet::new();
+    collect_referenced_contracts(expressions, &mut referenced);
+    referenced
+}
+
+/// Order `contracts` so that each one comes after every other contract of the slice that it
+///  references. The order only depends on the order of `contracts`, and is returned as
+///  indexes into it. Fails with `CheckErrors::CircularReference` if the references form a
+///  cycle.
+pub fn deployment_order(
+    contracts: &[(QualifiedContractIdentifier, ContractAST)],
+) -> std::result::Result<Vec<usize>, CheckErrors> {
+    let dependencies: Vec<Vec<usize>> = contracts
+        .iter()
+        .enumerate()
+        .map(|(index, (_, contract))| {
+            let mut referenced = HashSet::new();
+            collect_referenced_contracts(&contract.expressions, &mut referenced);
+            contracts
+                .iter()
+                .enumerate()
+                .filter(|(other, (identifier, _))| {
+                    *other != index && referenced.contains(identifier)
+                })
+                .map(|(other, _)| other)
+                .collect()
+        })
+        .collect();
+
+    let mut order = Vec::with_capacity(contracts.len());
+    let mut deployed = HashSet::new();
+    let mut path = vec![];
+    for index in 0..contracts.len() {
+        visit_dependencies(
+            index,
+            contracts,
+            &dependencies,
+            &mut deployed,
+            &mut path,
+            &mut order,
+        )?;
+    }
+    Ok(order)
+}
+
+/// Depth-first search appending `index` to `order` after all of its dependencies. `path`
+///  holds the contracts currently being visited, so reaching one of them again is a cycle.
+fn visit_dependencies(
+    index: usize,
+    contracts: &[(QualifiedContractIdentifier, ContractAST)],
+    dependencies: &[Vec<usize>],
+    deployed: &mut HashSet<usize>,
+    path: &mut Vec<usize>,
+    order: &mut Vec<usize>,
+) -> std::result::Result<(), CheckErrors> {
+    if deployed.contains(&index) {
+        return Ok(());
+    }
+    if let Some(cycle_start) = path.iter().position(|visiting| *visiting == index) {
+        let cycle = path[cycle_start..]
+            .iter()
+            .map(|visiting| contracts[*visiting].0.to_string())
+            .collect();
+        return Err(CheckErrors::CircularReference(cycle));
+    }
+
+    path.push(index);
+    for dependency in dependencies[index].iter() {
+        visit_dependencies(*dependency, contracts, dependencies, deployed, path, order)?;
+    }
+    path.pop();
+
+    deployed.insert(index);
+    order.push(index);
+    Ok(())
+}
@@ -14,6 +14,13 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+// The cost functions charged by the VM. Each name is a read-only function of the
+//  boot cost contract (e.g. `costs-3.clar`), which is the single source of truth for
+//  the formula: `(define-read-only (cost_sha256 (n uint)) (runtime (linear n u1 u100)))`
+//  charges `n + 100` runtime for an input of size `n`. Which cost function a native
+//  charges, and how its input size is measured, is declared alongside the native in
+//  `functions::lookup_reserved_functions`. Formulas can be changed by cost voting
+//  without a node upgrade, so they are deliberately not duplicated in Rust.
 define_named_enum!(ClarityCostFunction {
     AnalysisTypeAnnotate("cost_analysis_type_annotate"),
     AnalysisTypeCheck("cost_analysis_type_check"),
@@ -148,6 +155,15 @@ define_named_enum!(ClarityCostFunction {
     FromConsensusBuff("cost_from_consensus_buff"),
     StxTransferMemo("cost_stx_transfer_memo"),
     ReplaceAt("cost_replace_at"),
+    Sort("cost_sort"),
+    MulDiv("cost_mul_div"),
+    ToAscii("cost_to_ascii"),
+    StringToBuff("cost_string_to_buff"),
+    BitCount("cost_bit_count"),
+    LeadingZeros("cost_leading_zeros"),
+    UnwrapOrElse("cost_unwrap_or_else"),
+    AssertEq("cost_assert_eq"),
+    MapTry("cost_map_try"),
     AsContract("cost_as_contract"),
     BitwiseAnd("cost_bitwise_and"),
     BitwiseOr("cost_bitwise_or"),
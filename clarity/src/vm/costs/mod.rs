@@ -237,6 +237,8 @@ pub struct TrackerData {
     limit: ExecutionCost,
     memory: u64,
     memory_limit: u64,
+    /// the most memory in use at any point in this tracker's lifetime
+    memory_high_water: u64,
     /// if the cost tracker is non-free, this holds the StacksEpochId that should be used to evaluate
     ///  the Clarity cost functions. If the tracker *is* free, then those functions do not need to be
     ///  evaluated, so no epoch identifier is necessary.
@@ -638,6 +640,7 @@ impl LimitedCostTracker {
             memory_limit: CLARITY_MEMORY_LIMIT,
             total: ExecutionCost::zero(),
             memory: 0,
+            memory_high_water: 0,
             epoch,
             mainnet,
             chain_id,
@@ -662,6 +665,7 @@ impl LimitedCostTracker {
             memory_limit: CLARITY_MEMORY_LIMIT,
             total: ExecutionCost::zero(),
             memory: 0,
+            memory_high_water: 0,
             epoch,
             mainnet,
             chain_id,
@@ -793,6 +797,12 @@ impl LimitedCostTracker {
             Self::Free => panic!("Cannot set total on free tracker"),
         }
     }
+    pub fn set_memory_limit(&mut self, memory_limit: u64) {
+        match self {
+            Self::Limited(ref mut data) => data.memory_limit = memory_limit,
+            Self::Free => panic!("Cannot set memory limit on free tracker"),
+        }
+    }
     pub fn get_limit(&self) -> ExecutionCost {
         match self {
             Self::Limited(TrackerData { limit, .. }) => limit.clone(),
@@ -811,6 +821,15 @@ impl LimitedCostTracker {
             Self::Free => u64::MAX,
         }
     }
+    /// The most memory that was in use at once, over the lifetime of this tracker.
+    pub fn get_memory_high_water(&self) -> u64 {
+        match self {
+            Self::Limited(TrackerData {
+                memory_high_water, ..
+            }) => *memory_high_water,
+            Self::Free => 0,
+        }
+    }
 }
 
 fn parse_cost(
@@ -925,6 +944,7 @@ fn add_cost(s: &mut TrackerData, cost: ExecutionCost) -> std::result::Result<(),
 
 fn add_memory(s: &mut TrackerData, memory: u64) -> std::result::Result<(), CostErrors> {
     s.memory = s.memory.cost_overflow_add(memory)?;
+    s.memory_high_water = cmp::max(s.memory_high_water, s.memory);
     if s.memory > s.memory_limit {
         Err(CostErrors::MemoryBalanceExceeded(s.memory, s.memory_limit))
     } else {
@@ -28,7 +28,7 @@ use crate::vm::database::structures::{
     STXBalanceSnapshot, SimmedBlock,
 };
 use crate::vm::database::ClarityBackingStore;
-use crate::vm::database::RollbackWrapper;
+use crate::vm::database::{RollbackWrapper, WriteSet};
 use crate::vm::errors::{
     CheckErrors, Error, IncomparableError, InterpreterError, InterpreterResult as Result,
     RuntimeErrorType,
@@ -437,6 +437,18 @@ impl<'a> ClarityDatabase<'a> {
         self.store.rollback();
     }
 
+    /// See `RollbackWrapper::record_write_sets`.
+    pub fn record_write_sets(&mut self) {
+        self.store.record_write_sets()
+    }
+
+    /// The data writes of the most recent commit of the outermost layer that wrote any
+    ///  data, i.e., of the last such transaction committed to the backing store. `None`
+    ///  unless `record_write_sets` was called.
+    pub fn last_write_set(&self) -> Option<&WriteSet> {
+        self.store.last_write_set()
+    }
+
     pub fn set_block_hash(
         &mut self,
         bhh: StacksBlockId,
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::{clone::Clone, cmp::Eq, hash::Hash};
 
 use crate::vm::database::clarity_store::make_contract_hash_key;
@@ -106,6 +106,12 @@ pub struct ValueResult {
     pub serialized_byte_len: u64,
 }
 
+/// The data writes of a commit to the backing store, as `(key, value)` bytes: each
+///  key's final value, ordered by key. The order depends only on the keys written,
+///  so every node committing the same writes produces the same `WriteSet`.
+///  Metadata writes are not included.
+pub type WriteSet = Vec<(Vec<u8>, Vec<u8>)>;
+
 pub struct RollbackContext {
     edits: Vec<(String, RollbackValueCheck)>,
     metadata_edits: Vec<((QualifiedContractIdentifier, String), RollbackValueCheck)>,
@@ -128,6 +134,9 @@ pub struct RollbackWrapper<'a> {
     //   to indicate a given contexts "start depth".
     stack: Vec<RollbackContext>,
     query_pending_data: bool,
+    // the writes of the most recent commit to the backing store with data edits,
+    //   only recorded once a caller opts in.
+    last_write_set: Option<WriteSet>,
 }
 
 // This is used for preserving rollback data longer
@@ -198,6 +207,7 @@ impl<'a> RollbackWrapper<'a> {
             metadata_lookup_map: HashMap::new(),
             stack: Vec::new(),
             query_pending_data: true,
+            last_write_set: None,
         }
     }
 
@@ -211,6 +221,7 @@ impl<'a> RollbackWrapper<'a> {
             metadata_lookup_map: log.metadata_lookup_map,
             stack: log.stack,
             query_pending_data: true,
+            last_write_set: None,
         }
     }
 
@@ -218,6 +229,20 @@ impl<'a> RollbackWrapper<'a> {
         self.store.get_cc_special_cases_handler()
     }
 
+    /// Run `to_do` directly against the underlying backing store, e.g., to open an
+    ///  `AnalysisDatabase` over it. Panics if there are open (uncommitted) contexts, since
+    ///  their edits would not be visible to `to_do`.
+    pub fn with_backing_store<F, R>(&mut self, to_do: F) -> R
+    where
+        F: FnOnce(&mut dyn ClarityBackingStore) -> R,
+    {
+        assert!(
+            self.stack.is_empty(),
+            "ERROR: Attempted to access the backing store with uncommitted edits"
+        );
+        to_do(self.store)
+    }
+
     pub fn nest(&mut self) {
         self.stack.push(RollbackContext {
             edits: Vec::new(),
@@ -250,6 +275,68 @@ impl<'a> RollbackWrapper<'a> {
         self.stack.len()
     }
 
+    /// The keys written by the contexts nested at `depth` or deeper (i.e., since the
+    ///  stack was `depth` contexts deep), in order of their first write, each with the
+    ///  value it had before those writes (`None` if it was unset) and its latest value.
+    ///  Metadata writes are not included.
+    pub fn edits_since(&mut self, depth: usize) -> Vec<(String, Option<String>, String)> {
+        let mut writes_before: HashMap<&String, usize> = HashMap::new();
+        for (key, _) in self
+            .stack
+            .iter()
+            .take(depth)
+            .flat_map(|ctx| ctx.edits.iter())
+        {
+            *writes_before.entry(key).or_insert(0) += 1;
+        }
+
+        let mut seen = HashSet::new();
+        let mut edited = vec![];
+        for (key, _) in self
+            .stack
+            .iter()
+            .skip(depth)
+            .flat_map(|ctx| ctx.edits.iter())
+        {
+            if seen.insert(key) {
+                let history = self
+                    .lookup_map
+                    .get(key)
+                    .expect("BUG: pending edit missing from the lookup map");
+                let before = writes_before
+                    .get(key)
+                    .map(|count| Some(history[count - 1].clone()));
+                let latest = history
+                    .last()
+                    .expect("BUG: pending edit missing from the lookup map")
+                    .clone();
+                edited.push((key.clone(), before, latest));
+            }
+        }
+
+        edited
+            .into_iter()
+            .map(|(key, before, latest)| {
+                let before = before.unwrap_or_else(|| self.store.get(&key));
+                (key, before, latest)
+            })
+            .collect()
+    }
+
+    /// Start recording the `WriteSet` of each commit to the backing store. Until this is
+    ///  called, commits do not build one.
+    pub fn record_write_sets(&mut self) {
+        if self.last_write_set.is_none() {
+            self.last_write_set = Some(WriteSet::new());
+        }
+    }
+
+    /// The writes of the most recent commit to the backing store that wrote any data,
+    ///  or `None` if write sets are not being recorded.
+    pub fn last_write_set(&self) -> Option<&WriteSet> {
+        self.last_write_set.as_ref()
+    }
+
     pub fn commit(&mut self) {
         let mut last_item = self
             .stack
@@ -260,6 +347,14 @@ impl<'a> RollbackWrapper<'a> {
             // committing to the backing store
             let all_edits = rollback_check_pre_bottom_commit(last_item.edits, &mut self.lookup_map);
             if all_edits.len() > 0 {
+                if let Some(last_write_set) = self.last_write_set.as_mut() {
+                    // edits are in write order, so the last one for each key is its final value
+                    let write_set: BTreeMap<_, _> = all_edits
+                        .iter()
+                        .map(|(key, value)| (key.as_bytes().to_vec(), value.as_bytes().to_vec()))
+                        .collect();
+                    *last_write_set = write_set.into_iter().collect();
+                }
                 self.store.put_all(all_edits);
             }
 
@@ -22,7 +22,9 @@ pub use self::clarity_db::{
 };
 pub use self::clarity_store::MemoryBackingStore;
 pub use self::clarity_store::{ClarityBackingStore, SpecialCaseHandler};
-pub use self::key_value_wrapper::{RollbackWrapper, RollbackWrapperPersistedLog};
+pub use self::key_value_wrapper::{
+    RollbackWrapper, RollbackWrapperPersistedLog, ValueResult, WriteSet,
+};
 pub use self::sqlite::SqliteConnection;
 pub use self::structures::{
     ClarityDeserializable, ClaritySerializable, DataMapMetadata, DataVariableMetadata,
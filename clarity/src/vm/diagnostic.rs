@@ -52,6 +52,15 @@ impl Diagnostic {
         }
     }
 
+    pub fn warn(warning: &dyn DiagnosableError) -> Diagnostic {
+        Diagnostic {
+            spans: vec![],
+            level: Level::Warning,
+            message: warning.message(),
+            suggestion: warning.suggestion(),
+        }
+    }
+
     pub fn add_span(&mut self, start_line: u32, start_column: u32, end_line: u32, end_column: u32) {
         self.spans.push(Span {
             start_line,
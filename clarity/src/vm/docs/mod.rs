@@ -421,8 +421,11 @@ const ADD_API: SimpleFunctionAPI = SimpleFunctionAPI {
     name: Some("+ (add)"),
     snippet: "+ ${1:expr-1} ${2:expr-2}",
     signature: "(+ i1 i2...)",
-    description: "Adds a variable number of integer inputs and returns the result. In the event of an _overflow_, throws a runtime error.",
-    example: "(+ 1 2 3) ;; Returns 6"
+    description: "Adds a variable number of integer inputs and returns the result. In the event of an _overflow_, throws a runtime error.
+In Clarity 3, the inputs may instead be given as a single list, whose elements are summed. An empty list sums to `0`.",
+    example: "(+ 1 2 3) ;; Returns 6
+(+ (list 1 2 3)) ;; Returns 6
+"
 };
 
 const SUB_API: SimpleFunctionAPI = SimpleFunctionAPI {
@@ -450,10 +453,25 @@ const MUL_API: SimpleFunctionAPI = SimpleFunctionAPI {
     name: Some("* (multiply)"),
     snippet: "* ${1:expr-1} ${2:expr-2}",
     signature: "(* i1 i2...)",
-    description: "Multiplies a variable number of integer inputs and returns the result. In the event of an _overflow_, throws a runtime error.",
+    description: "Multiplies a variable number of integer inputs and returns the result. In the event of an _overflow_, throws a runtime error.
+In Clarity 3, the inputs may instead be given as a single list, whose elements are multiplied. An empty list multiplies to `1`.",
     example: "(* 2 3) ;; Returns 6
 (* 5 2) ;; Returns 10
 (* 2 2 2) ;; Returns 8
+(* (list u2 u2 u2)) ;; Returns u8
+"
+};
+
+const MUL_DIV_API: SimpleFunctionAPI = SimpleFunctionAPI {
+    name: None,
+    snippet: "mul-div ${1:expr-1} ${2:expr-2} ${3:expr-3}",
+    signature: "(mul-div i1 i2 i3)",
+    description: "Returns `(i1 * i2) / i3`, integer divided as with `/`. The product is computed exactly
+in 256-bit precision, so unlike `(/ (* i1 i2) i3)` this only throws an _overflow_ runtime error if the
+final result does not fit in the input type. In the event of division by zero, throws a runtime error.",
+    example: "(mul-div 7 3 2) ;; Returns 10
+(mul-div -7 3 2) ;; Returns -10
+(mul-div u340282366920938463463374607431768211455 u3 u6) ;; Returns u170141183460469231731687303715884105727
 "
 };
 
@@ -579,6 +597,35 @@ In other words, every bit that is `1` in ì1` will be `0` in the result.  Conver
 "
 };
 
+const BIT_COUNT_API: SimpleFunctionAPI = SimpleFunctionAPI {
+    name: None,
+    snippet: "bit-count ${1:expr-1}",
+    signature: "(bit-count i1)",
+    description: "Returns the number of bits set to `1` in `i1`, as a `uint`. A negative `int` is counted in its
+two's complement representation, so `(bit-count -1)` is `u128`.
+",
+    example: "(bit-count u7) ;; Returns u3
+(bit-count 0) ;; Returns u0
+(bit-count -1) ;; Returns u128
+(bit-count -2) ;; Returns u127
+"
+};
+
+const LEADING_ZEROS_API: SimpleFunctionAPI = SimpleFunctionAPI {
+    name: None,
+    snippet: "leading-zeros ${1:expr-1}",
+    signature: "(leading-zeros i1)",
+    description: "Returns the number of `0` bits before the most significant `1` bit in the 128-bit representation of
+`i1`, as a `uint`. The result is `u128` for zero. A negative `int` is represented in two's complement, so its highest
+bit is set and the result is `u0`.
+",
+    example: "(leading-zeros u1) ;; Returns u127
+(leading-zeros 0) ;; Returns u128
+(leading-zeros 255) ;; Returns u120
+(leading-zeros -1) ;; Returns u0
+"
+};
+
 const BITWISE_LEFT_SHIFT_API: SimpleFunctionAPI = SimpleFunctionAPI {
     name: None,
     snippet: "bit-shift-left ${1:expr-1} ${2:expr-2}",
@@ -737,7 +784,11 @@ pub fn get_input_type_string(function_type: &FunctionType) -> String {
             let in_types: Vec<String> = in_types.iter().map(|x| format!("{}", x)).collect();
             in_types.join(" | ")
         }
+        FunctionType::Hash(_) => "buff | uint | int | any (Clarity 3)".to_string(),
         FunctionType::ArithmeticVariadic => "int, ... | uint, ...".to_string(),
+        FunctionType::ArithmeticVariadicOrList => {
+            "int, ... | uint, ... | (list int) | (list uint)".to_string()
+        }
         FunctionType::ArithmeticUnary => "int | uint".to_string(),
         FunctionType::ArithmeticBinary | FunctionType::ArithmeticComparison => {
             "int, int | uint, uint | string-ascii, string-ascii | string-utf8, string-utf8 | buff, buff".to_string()
@@ -782,7 +833,9 @@ pub fn get_output_type_string(function_type: &FunctionType) -> String {
         FunctionType::Variadic(_, ref out_type) => format!("{}", out_type),
         FunctionType::Fixed(FixedFunction { ref returns, .. }) => format!("{}", returns),
         FunctionType::UnionArgs(_, ref out_type) => format!("{}", out_type),
+        FunctionType::Hash(ref out_type) => format!("{}", out_type),
         FunctionType::ArithmeticVariadic
+        | FunctionType::ArithmeticVariadicOrList
         | FunctionType::ArithmeticUnary
         | FunctionType::ArithmeticBinary => "int | uint".to_string(),
         FunctionType::ArithmeticComparison => "bool".to_string(),
@@ -882,9 +935,15 @@ const IF_API: SpecialAPI = SpecialAPI {
     description: "The `if` function admits a boolean argument and two expressions
 which must return the same type. In the case that the boolean input is `true`, the
 `if` function evaluates and returns `expr1`. If the boolean input is `false`, the
-`if` function evaluates and returns `expr2`.",
+`if` function evaluates and returns `expr2`.
+
+In Clarity3, `expr2` may be omitted. The single-arm `(if bool1 expr1)` returns
+`(some expr1)` if the boolean input is `true` and `none` otherwise, so its type is
+`(optional A)`.",
     example: "(if true 1 2) ;; Returns 1
-(if (> 1 2) 1 2) ;; Returns 2",
+(if (> 1 2) 1 2) ;; Returns 2
+(if (< 1 2) 1) ;; Returns (some 1)
+(if (> 1 2) 1) ;; Returns none",
 };
 
 const LET_API: SpecialAPI = SpecialAPI {
@@ -937,10 +996,12 @@ and outputs a _list_ of the same type containing the outputs from those function
 Applicable sequence types are `(list A)`, `buff`, `string-ascii` and `string-utf8`,
 for which the corresponding element types are, respectively, `A`, `(buff 1)`, `(string-ascii 1)` and `(string-utf8 1)`.
 The `func` argument must be a literal function name.
+If the input sequences have different lengths, `map` stops at the end of the shortest one.
 Also, note that, no matter what kind of sequences the inputs are, the output is always a list.",
     example: r#"
 (map not (list true false true false)) ;; Returns (false true false true)
 (map + (list 1 2 3) (list 1 2 3) (list 1 2 3)) ;; Returns (3 6 9)
+(map + (list 1 2 3) (list 10 20)) ;; Returns (11 22)
 (define-private (a-or-b (char (string-utf8 1))) (if (is-eq char u"a") u"a" u"b"))
 (map a-or-b u"aca") ;; Returns (u"a" u"b" u"a")
 (define-private (zero-or-one (char (buff 1))) (if (is-eq char 0x00) 0x00 0x01))
@@ -948,6 +1009,23 @@ Also, note that, no matter what kind of sequences the inputs are, the output is
 "#,
 };
 
+const MAP_TRY_API: SpecialAPI = SpecialAPI {
+    input_type: "Function(A) -> (response B C), sequence_A",
+    snippet: "map-try! ${1:func} ${2:sequence}",
+    output_type: "(response (list B) C)",
+    signature: "(map-try! func sequence)",
+    description: "The `map-try!` function applies the function `func`, which must return a response, to each
+element of the input sequence in order. If every application returns `(ok ...)`, `map-try!` returns an `ok`
+of the _list_ of the unwrapped values. Otherwise it returns the first `(err ...)`, and `func` is not applied
+to the remaining elements, whose costs are not incurred. The sequence types and the `func` argument are
+handled as by `map`.",
+    example: r#"
+(define-private (checked-half (n int)) (if (is-eq (mod n 2) 0) (ok (/ n 2)) (err n)))
+(map-try! checked-half (list 2 4 6)) ;; Returns (ok (1 2 3))
+(map-try! checked-half (list 2 3 5)) ;; Returns (err 3)
+"#,
+};
+
 const FILTER_API: SpecialAPI = SpecialAPI {
     input_type: "Function(A) -> bool, sequence_A",
     snippet: "filter ${1:func} ${2:sequence}",
@@ -1034,6 +1112,7 @@ const ASSERTS_MAX_LEN_API: SpecialAPI = SpecialAPI {
     signature: "(as-max-len? sequence max_length)",
     description:
         "The `as-max-len?` function takes a sequence argument and a uint-valued, literal length argument.
+In Clarity 3, the length argument may also be the name of a constant defined as a uint literal.
 The function returns an optional type. If the input sequence length is less than
 or equal to the supplied max_length, this returns `(some sequence)`, otherwise it returns `none`.
 Applicable sequence types are `(list A)`, `buff`, `string-ascii` and `string-utf8`.
@@ -1112,13 +1191,18 @@ const SLICE_API: SpecialAPI = SpecialAPI {
 ends at `right-position` (non-inclusive).
 If `left_position`==`right_position`, the function returns an empty sequence.
 If either `left_position` or `right_position` are out of bounds OR if `right_position` is less than
-`left_position`, the function returns `none`.",
+`left_position`, the function returns `none`.
+In Clarity3, an empty sequence is also returned at the end of the sequence, when both positions are
+equal to its length. And when both positions are literals (or constants defined as literals), the
+returned sequence's maximum length is `right_position - left_position` rather than the maximum length
+of `sequence`.",
     example: "(slice? \"blockstack\" u5 u10) ;; Returns (some \"stack\")
 (slice? (list 1 2 3 4 5) u5 u9) ;; Returns none
 (slice? (list 1 2 3 4 5) u3 u4) ;; Returns (some (4))
 (slice? \"abcd\" u1 u3) ;; Returns (some \"bc\")
 (slice? \"abcd\" u2 u2) ;; Returns (some \"\")
 (slice? \"abcd\" u3 u1) ;; Returns none
+(slice? \"abcd\" u4 u4) ;; Returns (some \"\")
 ",
 };
 
@@ -1252,12 +1336,33 @@ the tuple. If the supplied option is a `(none)` option, get returns `(none)`.",
 "
 };
 
+const TUPLE_GET_OPTIONAL_API: SpecialAPI = SpecialAPI {
+    input_type: "KeyName, (tuple) | (optional (tuple))",
+    snippet: "get? ${1:key-name} ${2:tuple}",
+    output_type: "(optional A)",
+    signature: "(get? key-name tuple)",
+    description: "The `get?` function fetches the value associated with a given key from the supplied
+tuple or optional tuple, always returning an `Optional`: `(some value)` if the tuple is present, and
+`none` if the supplied option is `none`. Unlike `get`, if the value is itself an `Optional` it is returned
+as-is rather than wrapped again, so that lookups into nested optional tuples can be chained.",
+    example: "(get? id { id: 1337 }) ;; Returns (some 1337)
+(get? id (some { id: 1337 })) ;; Returns (some 1337)
+(get? b (get? a { a: (some { b: 1 }) })) ;; Returns (some 1)
+(get? b (get? a { a: (if true none (some { b: 1 })) })) ;; Returns none
+"
+};
+
 const TUPLE_MERGE_API: SpecialAPI = SpecialAPI {
     input_type: "tuple, tuple",
     snippet: "merge ${1:tuple-1} ${2:tuple-2}",
     output_type: "tuple",
     signature: "(merge tuple { key1: val1 })",
-    description: "The `merge` function returns a new tuple with the combined fields, without mutating the supplied tuples.",
+    description: "The `merge` function returns a new tuple with the combined fields, without mutating the supplied tuples.
+
+In Clarity3, `merge` updates a record: every field of the second tuple must be a field of the first
+one, and its value must be admitted by that field's type, so the result has the type of the first
+tuple. An unknown field is a `NoSuchTupleField` error, and a value of another type is a `TypeError`.
+In Clarity1 and Clarity2, the second tuple may add fields, or change the type of existing ones.",
     example: "(define-map users { id: int } { name: (string-ascii 12), address: (optional principal) })
 (map-insert users { id: 1337 } { name: \"john\", address: none }) ;; Returns true
 (let ((user (unwrap-panic (map-get? users { id: 1337 }))))
@@ -1271,7 +1376,8 @@ const HASH160_API: SpecialAPI = SpecialAPI {
     signature: "(hash160 value)",
     description: "The `hash160` function computes `RIPEMD160(SHA256(x))` of the inputted value.
 If an integer (128 bit) is supplied the hash is computed over the little-endian representation of the
-integer.",
+integer. In Clarity 3, any other value (e.g., a tuple or a list) is hashed over its consensus
+serialization, as returned by `to-consensus-buff?`.",
     example: "(hash160 0) ;; Returns 0xe4352f72356db555721651aa612e00379167b30f"
 };
 
@@ -1282,8 +1388,10 @@ const SHA256_API: SpecialAPI = SpecialAPI {
     signature: "(sha256 value)",
     description: "The `sha256` function computes `SHA256(x)` of the inputted value.
 If an integer (128 bit) is supplied the hash is computed over the little-endian representation of the
-integer.",
-    example: "(sha256 0) ;; Returns 0x374708fff7719dd5979ec875d56cd2286f6d3cf7ec317a3b25632aab28ec37bb"
+integer. In Clarity 3, any other value (e.g., a tuple or a list) is hashed over its consensus
+serialization, as returned by `to-consensus-buff?`.",
+    example: "(sha256 0) ;; Returns 0x374708fff7719dd5979ec875d56cd2286f6d3cf7ec317a3b25632aab28ec37bb
+(sha256 0x) ;; Returns 0xe3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
 };
 
 const SHA512_API: SpecialAPI = SpecialAPI {
@@ -1293,7 +1401,8 @@ const SHA512_API: SpecialAPI = SpecialAPI {
     signature: "(sha512 value)",
     description: "The `sha512` function computes `SHA512(x)` of the inputted value.
 If an integer (128 bit) is supplied the hash is computed over the little-endian representation of the
-integer.",
+integer. In Clarity 3, any other value (e.g., a tuple or a list) is hashed over its consensus
+serialization, as returned by `to-consensus-buff?`.",
     example: "(sha512 1) ;; Returns 0x6fcee9a7b7a7b821d241c03c82377928bc6882e7a08c78a4221199bfa220cdc55212273018ee613317c8293bb8d1ce08d1e017508e94e06ab85a734c99c7cc34",
 };
 
@@ -1305,7 +1414,8 @@ const SHA512T256_API: SpecialAPI = SpecialAPI {
     description: "The `sha512/256` function computes `SHA512/256(x)` (the SHA512 algorithm with the 512/256 initialization vector, truncated
 to 256 bits) of the inputted value.
 If an integer (128 bit) is supplied the hash is computed over the little-endian representation of the
-integer.",
+integer. In Clarity 3, any other value (e.g., a tuple or a list) is hashed over its consensus
+serialization, as returned by `to-consensus-buff?`.",
     example: "(sha512/256 1) ;; Returns 0x515a7e92e7c60522db968d81ff70b80818fc17aeabbec36baf0dda2812e94a86",
 };
 
@@ -1316,7 +1426,9 @@ const KECCAK256_API: SpecialAPI = SpecialAPI {
     signature: "(keccak256 value)",
     description: "The `keccak256` function computes `KECCAK256(value)` of the inputted value.
 Note that this differs from the `NIST SHA-3` (that is, FIPS 202) standard. If an integer (128 bit)
-is supplied the hash is computed over the little-endian representation of the integer.",
+is supplied the hash is computed over the little-endian representation of the integer. In Clarity 3,
+any other value (e.g., a tuple or a list) is hashed over its consensus serialization, as returned
+by `to-consensus-buff?`.",
     example: "(keccak256 0) ;; Returns 0xf490de2920c8a35fabeb13208852aa28c76f9be9b03a4dd2b3c075f7a26923b4"
 };
 
@@ -1598,6 +1710,25 @@ a `(some ...)` option, it returns the inner value of the option. If the second a
 ",
 };
 
+const UNWRAP_OR_ELSE_API: SpecialAPI = SpecialAPI {
+    input_type: "(optional A), FallbackFunction",
+    snippet: "unwrap-or-else ${1:option-value} ${2:fallback-function}",
+    output_type: "A",
+    signature: "(unwrap-or-else option-value fallback-function)",
+    description: "The `unwrap-or-else` function attempts to 'unpack' the first argument: if the argument is
+a `(some ...)` option, it returns the inner value of the option. If the first argument is a `(none)` value,
+`unwrap-or-else` calls `fallback-function`, a native or defined function taking no arguments, and returns
+its result. Unlike `default-to`, the fallback is only evaluated when the option is `none`.",
+    example: "
+(define-data-var fallback-calls uint u0)
+(define-private (fallback) (begin (var-set fallback-calls (+ u1 (var-get fallback-calls))) 0))
+(unwrap-or-else (some 5) fallback) ;; Returns 5
+(var-get fallback-calls) ;; Returns u0
+(unwrap-or-else none fallback) ;; Returns 0
+(var-get fallback-calls) ;; Returns u1
+",
+};
+
 const CONS_OK_API: SpecialAPI = SpecialAPI {
     input_type: "A",
     snippet: "ok ${1:value}",
@@ -2266,7 +2397,8 @@ const STX_GET_ACCOUNT: SimpleFunctionAPI = SimpleFunctionAPI {
 
 This function returns a tuple with the canonical account representation for an STX account.
 This includes the current amount of unlocked STX, the current amount of locked STX, and the
-unlock height for any locked STX, all denominated in microstacks.
+unlock height for any locked STX, all denominated in microstacks. An account with no locked
+STX (including one that has never held STX) reports `u0` for both `locked` and `unlock-height`.
 ",
     example: r#"
 (stx-account 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR) ;; Returns (tuple (locked u0) (unlock-height u0) (unlocked u0))
@@ -2367,6 +2499,38 @@ consensus buffer length based on the inferred type of the supplied value.
 "#,
 };
 
+const TO_ASCII: SpecialAPI = SpecialAPI {
+    input_type: "buff",
+    snippet: "to-ascii? ${1:buffer}",
+    output_type: "(optional (string-ascii N))",
+    signature: "(to-ascii? buffer)",
+    description: "`to-ascii?` converts a buffer into an ASCII string holding the same bytes.
+If every byte is a character allowed in an ASCII string, this returns `(some string)`, where
+the string has the same length as the buffer. Otherwise, it returns `none`, so that untrusted
+data can be checked without aborting the transaction.
+",
+    example: r#"
+(to-ascii? 0x616263) ;; Returns (some "abc")
+(to-ascii? 0x) ;; Returns (some "")
+(to-ascii? 0x61ff63) ;; Returns none
+"#,
+};
+
+const STRING_TO_BUFF: SpecialAPI = SpecialAPI {
+    input_type: "string-ascii | string-utf8",
+    snippet: "string-to-buff ${1:string}",
+    output_type: "buff",
+    signature: "(string-to-buff string)",
+    description: "`string-to-buff` returns the bytes of a string as a buffer. An ASCII
+string yields one byte per character, so `(to-ascii? (string-to-buff s))` returns `(some s)`.
+A UTF-8 string yields its UTF-8 encoding, which is up to 4 bytes per character.
+",
+    example: r#"
+(string-to-buff "abc") ;; Returns 0x616263
+(string-to-buff u"\u{e9}") ;; Returns 0xc3a9
+"#,
+};
+
 const FROM_CONSENSUS_BUFF: SpecialAPI = SpecialAPI {
     input_type: "type-signature(t), buff",
     snippet: "from-consensus-buff? ${1:type-signature} ${2:buffer}",
@@ -2412,6 +2576,41 @@ If the provided index is out of bounds, this functions returns `none`.
 "#,
 };
 
+const SORT_API: SpecialAPI = SpecialAPI {
+    input_type: "(list A)",
+    output_type: "(list A)",
+    snippet: "sort ${1:list}",
+    signature: "(sort list)",
+    description: "The `sort` function takes a list and returns a new list with the same elements,
+ordered by the byte-wise order of their consensus serializations (see `to-consensus-buff?`).
+This ordering is deterministic for every type, which makes it suitable for building ordered
+key sets, but it is not the numeric ordering: negative `int`s sort after non-negative ones.
+",
+    example: r#"
+(sort (list u3 u1 u2)) ;; Returns (u1 u2 u3)
+(sort (list 2 -1 0)) ;; Returns (0 2 -1)
+(sort (list "b" "ab" "a")) ;; Returns ("a" "b" "ab")
+"#,
+};
+
+const ASSERT_EQ_API: SpecialAPI = SpecialAPI {
+    input_type: "A, A",
+    output_type: "(response bool UnknownType)",
+    snippet: "assert-eq! ${1:actual} ${2:expected}",
+    signature: "(assert-eq! actual expected)",
+    description: "The `assert-eq!` function is a test-only assertion for in-language contract
+unit tests. It returns `(ok true)` if `actual` and `expected` are equal, and otherwise aborts
+execution with a runtime error reporting both values. Both arguments must have compatible types.
+The function is only available when the environment runs in debug mode, which test harnesses
+enable; in any other environment, including every mainnet environment, calling it aborts
+execution with a runtime error.
+",
+    example: r#"
+(assert-eq! (+ 1 2) 3) ;; Returns (ok true)
+(assert-eq! (list u1 u2) (list u1 u2)) ;; Returns (ok true)
+"#,
+};
+
 pub fn make_api_reference(function: &NativeFunctions) -> FunctionAPI {
     use crate::vm::functions::NativeFunctions::*;
     let name = function.get_name();
@@ -2422,6 +2621,7 @@ pub fn make_api_reference(function: &NativeFunctions) -> FunctionAPI {
         Subtract => make_for_simple_native(&SUB_API, &function, name),
         Multiply => make_for_simple_native(&MUL_API, &function, name),
         Divide => make_for_simple_native(&DIV_API, &function, name),
+        MulDiv => make_for_simple_native(&MUL_DIV_API, &function, name),
         BuffToIntLe => make_for_simple_native(&BUFF_TO_INT_LE_API, &function, name),
         BuffToUIntLe => make_for_simple_native(&BUFF_TO_UINT_LE_API, &function, name),
         BuffToIntBe => make_for_simple_native(&BUFF_TO_INT_BE_API, &function, name),
@@ -2451,6 +2651,7 @@ pub fn make_api_reference(function: &NativeFunctions) -> FunctionAPI {
         FetchVar => make_for_special(&FETCH_VAR_API, function),
         SetVar => make_for_special(&SET_VAR_API, function),
         Map => make_for_special(&MAP_API, function),
+        MapTry => make_for_special(&MAP_TRY_API, function),
         Filter => make_for_special(&FILTER_API, function),
         Fold => make_for_special(&FOLD_API, function),
         Append => make_for_special(&APPEND_API, function),
@@ -2467,6 +2668,7 @@ pub fn make_api_reference(function: &NativeFunctions) -> FunctionAPI {
         DeleteEntry => make_for_special(&DELETE_ENTRY_API, function),
         TupleCons => make_for_special(&TUPLE_CONS_API, function),
         TupleGet => make_for_special(&TUPLE_GET_API, function),
+        TupleGetOptional => make_for_special(&TUPLE_GET_OPTIONAL_API, function),
         TupleMerge => make_for_special(&TUPLE_MERGE_API, function),
         Begin => make_for_special(&BEGIN_API, function),
         Hash160 => make_for_special(&HASH160_API, function),
@@ -2487,6 +2689,7 @@ pub fn make_api_reference(function: &NativeFunctions) -> FunctionAPI {
         ConsError => make_for_special(&CONS_ERR_API, function),
         ConsSome => make_for_special(&CONS_SOME_API, function),
         DefaultTo => make_for_special(&DEFAULT_TO_API, function),
+        UnwrapOrElse => make_for_special(&UNWRAP_OR_ELSE_API, function),
         Asserts => make_for_special(&ASSERTS_API, function),
         UnwrapRet => make_for_special(&EXPECTS_API, function),
         UnwrapErrRet => make_for_special(&EXPECTS_ERR_API, function),
@@ -2514,12 +2717,18 @@ pub fn make_api_reference(function: &NativeFunctions) -> FunctionAPI {
         StxTransferMemo => make_for_special(&STX_TRANSFER_MEMO, function),
         StxBurn => make_for_simple_native(&STX_BURN, &function, name),
         ToConsensusBuff => make_for_special(&TO_CONSENSUS_BUFF, function),
+        ToAscii => make_for_special(&TO_ASCII, function),
+        StringToBuff => make_for_special(&STRING_TO_BUFF, function),
         FromConsensusBuff => make_for_special(&FROM_CONSENSUS_BUFF, function),
         ReplaceAt => make_for_special(&REPLACE_AT, function),
+        Sort => make_for_special(&SORT_API, function),
+        AssertEq => make_for_special(&ASSERT_EQ_API, function),
         BitwiseXor2 => make_for_simple_native(&BITWISE_XOR_API, &function, name),
         BitwiseAnd => make_for_simple_native(&BITWISE_AND_API, &function, name),
         BitwiseOr => make_for_simple_native(&BITWISE_OR_API, &function, name),
         BitwiseNot => make_for_simple_native(&BITWISE_NOT_API, &function, name),
+        BitCount => make_for_simple_native(&BIT_COUNT_API, function, name),
+        LeadingZeros => make_for_simple_native(&LEADING_ZEROS_API, function, name),
         BitwiseLShift => make_for_simple_native(&BITWISE_LEFT_SHIFT_API, &function, name),
         BitwiseRShift => make_for_simple_native(&BITWISE_RIGHT_SHIFT_API, &function, name),
     }
@@ -2663,6 +2872,10 @@ mod test {
     use crate::vm::costs::ExecutionCost;
     use stacks_common::consts::CHAIN_ID_TESTNET;
 
+    /// The examples document every version's behavior, so they are run against the
+    ///  newest version, including ones no epoch has activated yet.
+    const DOCS_CLARITY_VERSION: ClarityVersion = ClarityVersion::Clarity3;
+
     struct DocHeadersDB {}
     const DOC_HEADER_DB: DocHeadersDB = DocHeadersDB {};
 
@@ -2850,7 +3063,7 @@ mod test {
                 &contract_id,
                 &whole_contract,
                 &mut (),
-                ClarityVersion::latest(),
+                DOCS_CLARITY_VERSION,
                 StacksEpochId::latest(),
             )
             .unwrap()
@@ -2862,7 +3075,7 @@ mod test {
                 &mut analysis_db,
                 false,
                 &StacksEpochId::latest(),
-                &ClarityVersion::latest(),
+                &DOCS_CLARITY_VERSION,
             )
             .expect("Failed to type check");
         }
@@ -2875,7 +3088,7 @@ mod test {
                 &contract_id,
                 &total_example,
                 &mut (),
-                ClarityVersion::latest(),
+                DOCS_CLARITY_VERSION,
                 StacksEpochId::latest(),
             )
             .unwrap()
@@ -2888,7 +3101,7 @@ mod test {
                 &mut analysis_db,
                 false,
                 &StacksEpochId::latest(),
-                &ClarityVersion::latest(),
+                &DOCS_CLARITY_VERSION,
             )
             .expect("Failed to type check");
             type_results.push(
@@ -2902,8 +3115,7 @@ mod test {
         }
 
         let conn = store.as_docs_clarity_db();
-        let mut contract_context =
-            ContractContext::new(contract_id.clone(), ClarityVersion::latest());
+        let mut contract_context = ContractContext::new(contract_id.clone(), DOCS_CLARITY_VERSION);
         let mut global_context = GlobalContext::new(
             false,
             CHAIN_ID_TESTNET,
@@ -2911,6 +3123,8 @@ mod test {
             LimitedCostTracker::new_free(),
             StacksEpochId::latest(),
         );
+        // examples of debug-only natives must run too
+        global_context.set_debug_mode(true);
 
         global_context
             .execute(|g| {
@@ -2929,7 +3143,7 @@ mod test {
                             &contract_id,
                             segment,
                             &mut (),
-                            ClarityVersion::latest(),
+                            DOCS_CLARITY_VERSION,
                             StacksEpochId::latest(),
                         )
                         .unwrap()
@@ -2962,7 +3176,7 @@ mod test {
 
     #[test]
     fn test_examples() {
-        // Execute test examples against the latest version of Clarity
+        // Execute test examples against the newest version of Clarity
         let apis = make_all_api_reference();
         let token_contract_content = include_str!("../../../../sample-contracts/tokens.clar");
         for func_api in apis.functions.iter() {
@@ -2993,7 +3207,7 @@ mod test {
                         &contract_id,
                         &token_contract_content,
                         &mut (),
-                        ClarityVersion::latest(),
+                        DOCS_CLARITY_VERSION,
                         StacksEpochId::latest(),
                     )
                     .unwrap()
@@ -3005,7 +3219,7 @@ mod test {
                         &mut analysis_db,
                         true,
                         &StacksEpochId::latest(),
-                        &ClarityVersion::latest(),
+                        &DOCS_CLARITY_VERSION,
                     )
                     .expect("Failed to type check sample-contracts/tokens");
                 }
@@ -3016,7 +3230,7 @@ mod test {
                         &trait_def_id,
                         super::DEFINE_TRAIT_API.example,
                         &mut (),
-                        ClarityVersion::latest(),
+                        DOCS_CLARITY_VERSION,
                         StacksEpochId::latest(),
                     )
                     .unwrap()
@@ -3028,7 +3242,7 @@ mod test {
                         &mut analysis_db,
                         true,
                         &StacksEpochId::latest(),
-                        &ClarityVersion::latest(),
+                        &DOCS_CLARITY_VERSION,
                     )
                     .expect("Failed to type check sample-contracts/tokens");
                 }
@@ -22,7 +22,7 @@ pub use crate::vm::analysis::errors::{
 use crate::vm::ast::errors::ParseError;
 use crate::vm::contexts::StackTrace;
 use crate::vm::costs::CostErrors;
-use crate::vm::types::{TypeSignature, Value};
+use crate::vm::types::{PrincipalParseError, TypeSignature, Value};
 use rusqlite::Error as SqliteError;
 use serde_json::Error as SerdeJSONErr;
 use std::error;
@@ -84,7 +84,10 @@ pub enum RuntimeErrorType {
     MaxContextDepthReached,
     ListDimensionTooHigh,
     BadTypeConstruction,
-    ValueTooLarge,
+    // a sequence grew past the maximum size allowed for its type (sizes in bytes)
+    ValueTooLarge { actual: u64, max: u64 },
+    // a position outside of a sequence of length `len`
+    BadIndex { index: usize, len: usize },
     BadBlockHeight(String),
     TransferNonPositiveAmount,
     NoSuchToken,
@@ -100,6 +103,13 @@ pub enum RuntimeErrorType {
     UnwrapFailure,
     DefunctPoxContract,
     PoxAlreadyLocked,
+    EventBalanceExceeded,
+    ExecutionTimeout,
+    BadPrincipal(PrincipalParseError),
+    // (actual, expected) of a failed `assert-eq!`
+    AssertionFailed(Value, Value),
+    // a debug-only native called outside of debug mode
+    DebugOnlyFunction(String),
 }
 
 #[derive(Debug, PartialEq)]
@@ -110,6 +120,121 @@ pub enum ShortReturnType {
 
 pub type InterpreterResult<R> = Result<R, Error>;
 
+/// The kind of failure an error code belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCategory {
+    /// rejected by the static analysis passes
+    Checked,
+    /// a check error that surfaced at runtime, which the analysis passes should have caught
+    Unchecked,
+    /// an error that contract execution is expected to be able to trigger
+    Runtime,
+    /// an error that should never occur
+    Interpreter,
+    /// an early exit from a function body, carrying its return value
+    ShortReturn,
+}
+
+impl Error {
+    /// A stable numeric code identifying the kind of this error. Codes of
+    ///  `Unchecked` errors are those of the corresponding `CheckErrors`.
+    ///  Each error enum numbers its variants in declaration order within its own
+    ///  range; codes are never renumbered or reused, so a new variant takes the
+    ///  next unused code of its enum.
+    pub fn error_code(&self) -> u32 {
+        match self {
+            Error::Unchecked(e) => e.error_code(),
+            Error::Interpreter(e) => e.error_code(),
+            Error::Runtime(e, _) => e.error_code(),
+            Error::ShortReturn(e) => e.error_code(),
+        }
+    }
+
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Error::Unchecked(_) => ErrorCategory::Unchecked,
+            Error::Interpreter(_) => ErrorCategory::Interpreter,
+            Error::Runtime(..) => ErrorCategory::Runtime,
+            Error::ShortReturn(_) => ErrorCategory::ShortReturn,
+        }
+    }
+}
+
+impl InterpreterError {
+    /// A stable numeric code for this kind of error, in the range 3000-3999.
+    pub fn error_code(&self) -> u32 {
+        match self {
+            InterpreterError::BadSender(..) => 3000,
+            InterpreterError::BadSymbolicRepresentation(..) => 3001,
+            InterpreterError::InterpreterError(..) => 3002,
+            InterpreterError::UninitializedPersistedVariable => 3003,
+            InterpreterError::FailedToConstructAssetTable => 3004,
+            InterpreterError::FailedToConstructEventBatch => 3005,
+            InterpreterError::SqliteError(..) => 3006,
+            InterpreterError::BadFileName => 3007,
+            InterpreterError::FailedToCreateDataDirectory => 3008,
+            InterpreterError::MarfFailure(..) => 3009,
+            InterpreterError::FailureConstructingTupleWithType => 3010,
+            InterpreterError::FailureConstructingListWithType => 3011,
+            InterpreterError::InsufficientBalance => 3012,
+            InterpreterError::CostContractLoadFailure => 3013,
+            InterpreterError::DBError(..) => 3014,
+        }
+    }
+}
+
+impl RuntimeErrorType {
+    /// A stable numeric code for this kind of error, in the range 2000-2999.
+    pub fn error_code(&self) -> u32 {
+        match self {
+            RuntimeErrorType::Arithmetic(..) => 2000,
+            RuntimeErrorType::ArithmeticOverflow => 2001,
+            RuntimeErrorType::ArithmeticUnderflow => 2002,
+            RuntimeErrorType::SupplyOverflow(..) => 2003,
+            RuntimeErrorType::SupplyUnderflow(..) => 2004,
+            RuntimeErrorType::DivisionByZero => 2005,
+            RuntimeErrorType::ParseError(..) => 2006,
+            RuntimeErrorType::ASTError(..) => 2007,
+            RuntimeErrorType::MaxStackDepthReached => 2008,
+            RuntimeErrorType::MaxContextDepthReached => 2009,
+            RuntimeErrorType::ListDimensionTooHigh => 2010,
+            RuntimeErrorType::BadTypeConstruction => 2011,
+            RuntimeErrorType::ValueTooLarge { .. } => 2012,
+            RuntimeErrorType::BadIndex { .. } => 2013,
+            RuntimeErrorType::BadBlockHeight(..) => 2014,
+            RuntimeErrorType::TransferNonPositiveAmount => 2015,
+            RuntimeErrorType::NoSuchToken => 2016,
+            RuntimeErrorType::NotImplemented => 2017,
+            RuntimeErrorType::NoCallerInContext => 2018,
+            RuntimeErrorType::NoSenderInContext => 2019,
+            RuntimeErrorType::NonPositiveTokenSupply => 2020,
+            RuntimeErrorType::JSONParseError(..) => 2021,
+            RuntimeErrorType::AttemptToFetchInTransientContext => 2022,
+            RuntimeErrorType::BadNameValue(..) => 2023,
+            RuntimeErrorType::UnknownBlockHeaderHash(..) => 2024,
+            RuntimeErrorType::BadBlockHash(..) => 2025,
+            RuntimeErrorType::UnwrapFailure => 2026,
+            RuntimeErrorType::DefunctPoxContract => 2027,
+            RuntimeErrorType::PoxAlreadyLocked => 2028,
+            RuntimeErrorType::EventBalanceExceeded => 2029,
+            RuntimeErrorType::ExecutionTimeout => 2030,
+            RuntimeErrorType::BadPrincipal(..) => 2031,
+            RuntimeErrorType::AssertionFailed(..) => 2032,
+            RuntimeErrorType::DebugOnlyFunction(..) => 2033,
+        }
+    }
+}
+
+impl ShortReturnType {
+    /// A stable numeric code for this kind of early return, in the range 4000-4999.
+    pub fn error_code(&self) -> u32 {
+        match self {
+            ShortReturnType::ExpectedValue(..) => 4000,
+            ShortReturnType::AssertionFailed(..) => 4001,
+        }
+    }
+}
+
 impl<T> PartialEq<IncomparableError<T>> for IncomparableError<T> {
     fn eq(&self, _other: &IncomparableError<T>) -> bool {
         return false;
@@ -254,4 +379,34 @@ _native_:native_div
                 != Error::Interpreter(InterpreterError::InterpreterError("".to_string()))
         );
     }
+
+    #[test]
+    fn error_codes() {
+        let division_by_zero = execute("(/ 10 0)").unwrap_err();
+        assert_eq!(division_by_zero.error_code(), 2005);
+        assert_eq!(division_by_zero.category(), ErrorCategory::Runtime);
+
+        let overflow = execute("(+ u340282366920938463463374607431768211455 u1)").unwrap_err();
+        assert_eq!(overflow.error_code(), 2001);
+        assert_eq!(overflow.category(), ErrorCategory::Runtime);
+
+        let bad_arg_count = Error::Unchecked(CheckErrors::IncorrectArgumentCount(2, 1));
+        assert_eq!(bad_arg_count.error_code(), 1087);
+        assert_eq!(bad_arg_count.category(), ErrorCategory::Unchecked);
+        assert_eq!(
+            crate::vm::analysis::CheckError::new(CheckErrors::IncorrectArgumentCount(3, 0))
+                .error_code(),
+            1087
+        );
+
+        // the code identifies the kind of error, not its payload
+        assert_eq!(
+            RuntimeErrorType::Arithmetic("a".into()).error_code(),
+            RuntimeErrorType::Arithmetic("b".into()).error_code()
+        );
+        assert_eq!(
+            ShortReturnType::ExpectedValue(Value::Bool(true)).error_code(),
+            ShortReturnType::ExpectedValue(Value::Int(1)).error_code()
+        );
+    }
 }
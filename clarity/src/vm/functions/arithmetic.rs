@@ -22,8 +22,10 @@ use crate::vm::types::{
 use std::cmp;
 use std::convert::TryFrom;
 
+use crate::vm::contexts::ArithmeticMode;
 use crate::vm::costs::runtime_cost;
 use integer_sqrt::IntegerSquareRoot;
+use stacks_common::util::uint::Uint256;
 
 use crate::vm::representations::{SymbolicExpression, SymbolicExpressionType};
 use crate::vm::types::{signatures::ListTypeData, ListData, TypeSignature::BoolType};
@@ -277,6 +279,31 @@ macro_rules! make_arithmetic_ops {
                     .ok_or(RuntimeErrorType::ArithmeticOverflow)?;
                 Self::make_value(result)
             }
+            fn wrapping_add(args: &[$type]) -> InterpreterResult<Value> {
+                let result = args
+                    .iter()
+                    .fold(0, |acc: $type, x: &$type| acc.wrapping_add(*x));
+                Self::make_value(result)
+            }
+            fn wrapping_sub(args: &[$type]) -> InterpreterResult<Value> {
+                let (first, rest) = args
+                    .split_first()
+                    .ok_or(CheckErrors::IncorrectArgumentCount(1, 0))?;
+                if rest.len() == 0 {
+                    return Self::make_value(first.wrapping_neg());
+                }
+
+                let result = rest
+                    .iter()
+                    .fold(*first, |acc: $type, x: &$type| acc.wrapping_sub(*x));
+                Self::make_value(result)
+            }
+            fn wrapping_mul(args: &[$type]) -> InterpreterResult<Value> {
+                let result = args
+                    .iter()
+                    .fold(1, |acc: $type, x: &$type| acc.wrapping_mul(*x));
+                Self::make_value(result)
+            }
             fn div(args: &[$type]) -> InterpreterResult<Value> {
                 let (first, rest) = args
                     .split_first()
@@ -353,6 +380,46 @@ macro_rules! make_arithmetic_ops {
 make_arithmetic_ops!(U128Ops, u128);
 make_arithmetic_ops!(I128Ops, i128);
 
+/// Computes `(a * b) / c`, truncating, over an exact 256-bit intermediate product, so
+///  that only a quotient which does not fit in a u128 overflows.
+fn checked_mul_div(a: u128, b: u128, c: u128) -> InterpreterResult<u128> {
+    if c == 0 {
+        return Err(RuntimeErrorType::DivisionByZero.into());
+    }
+    let quotient = (Uint256::from_u128(a) * Uint256::from_u128(b)) / Uint256::from_u128(c);
+    if quotient.bits() > 128 {
+        return Err(RuntimeErrorType::ArithmeticOverflow.into());
+    }
+    Ok(((quotient.0[1] as u128) << 64) | (quotient.0[0] as u128))
+}
+
+impl U128Ops {
+    fn mul_div(args: &[u128]) -> InterpreterResult<Value> {
+        check_argument_count(3, args)?;
+        Self::make_value(checked_mul_div(args[0], args[1], args[2])?)
+    }
+}
+
+impl I128Ops {
+    fn mul_div(args: &[i128]) -> InterpreterResult<Value> {
+        check_argument_count(3, args)?;
+        let magnitude = checked_mul_div(
+            args[0].unsigned_abs(),
+            args[1].unsigned_abs(),
+            args[2].unsigned_abs(),
+        )?;
+        let negative = args.iter().filter(|x| **x < 0).count() % 2 == 1;
+        let result = match i128::try_from(magnitude) {
+            Ok(x) if negative => -x,
+            Ok(x) => x,
+            // -2^127 is the only result whose magnitude does not fit in an i128
+            Err(_) if negative && magnitude == i128::MIN.unsigned_abs() => i128::MIN,
+            Err(_) => return Err(RuntimeErrorType::ArithmeticOverflow.into()),
+        };
+        Self::make_value(result)
+    }
+}
+
 make_comparison_ops!(U128Ops, u128);
 make_comparison_ops!(I128Ops, i128);
 make_comparison_ops!(ASCIIOps, Vec<u8>);
@@ -381,6 +448,34 @@ pub fn native_bitwise_not(a: Value) -> InterpreterResult<Value> {
     type_force_unary_arithmetic!(bitwise_not, a)
 }
 
+/// Counts the bits set in `input`. A negative `int` is counted in its two's complement
+///  representation, so `(bit-count -1)` is `u128`.
+pub fn native_bit_count(input: Value) -> InterpreterResult<Value> {
+    match input {
+        Value::Int(input) => Ok(Value::UInt(input.count_ones().into())),
+        Value::UInt(input) => Ok(Value::UInt(input.count_ones().into())),
+        _ => Err(CheckErrors::UnionTypeError(
+            vec![TypeSignature::IntType, TypeSignature::UIntType],
+            TypeSignature::type_of(&input),
+        )
+        .into()),
+    }
+}
+
+/// Counts the zero bits above the highest set bit of the 128-bit representation of
+///  `input`: `u128` for zero, and `u0` for any negative `int`.
+pub fn native_leading_zeros(input: Value) -> InterpreterResult<Value> {
+    match input {
+        Value::Int(input) => Ok(Value::UInt(input.leading_zeros().into())),
+        Value::UInt(input) => Ok(Value::UInt(input.leading_zeros().into())),
+        _ => Err(CheckErrors::UnionTypeError(
+            vec![TypeSignature::IntType, TypeSignature::UIntType],
+            TypeSignature::type_of(&input),
+        )
+        .into()),
+    }
+}
+
 // This function is 'special', because it must access the context to determine
 // the clarity version.
 fn special_geq_v1(
@@ -550,18 +645,92 @@ pub fn special_less(
     }
 }
 
+/// Since Clarity3, `+` and `*` also accept a single list of ints or uints. This unpacks such
+///  a list into the operands of the variadic form. An empty list is replaced by `identity`,
+///  in the list's element type, so that it reduces to the identity of the operation.
+fn list_operands(args: Vec<Value>, identity: u8) -> InterpreterResult<Vec<Value>> {
+    match <[Value; 1]>::try_from(args) {
+        Ok([Value::Sequence(SequenceData::List(list))]) => {
+            if !list.data.is_empty() {
+                return Ok(list.data);
+            }
+            match list.type_signature.get_list_item_type() {
+                TypeSignature::IntType => Ok(vec![Value::Int(identity.into())]),
+                TypeSignature::UIntType => Ok(vec![Value::UInt(identity.into())]),
+                _ => Err(CheckErrors::UnionTypeValueError(
+                    vec![TypeSignature::IntType, TypeSignature::UIntType],
+                    Value::Sequence(SequenceData::List(list)),
+                )
+                .into()),
+            }
+        }
+        Ok([arg]) => Ok(vec![arg]),
+        Err(args) => Ok(args),
+    }
+}
+
+/// Cost input for the Clarity3 `+` and `*`: the number of operands, counting the elements
+///  of a single list argument.
+pub fn cost_input_list_operands(args: &[Value]) -> InterpreterResult<u64> {
+    match args {
+        [Value::Sequence(SequenceData::List(list))] => Ok(list.data.len() as u64),
+        _ => Ok(args.len() as u64),
+    }
+}
+
+/// Dispatch to the aborting or wrapping implementation of an operator, according to
+///  the arithmetic mode of the executing environment.
+fn in_arithmetic_mode(
+    args: Vec<Value>,
+    env: &Environment,
+    aborting: fn(Vec<Value>) -> InterpreterResult<Value>,
+    wrapping: fn(Vec<Value>) -> InterpreterResult<Value>,
+) -> InterpreterResult<Value> {
+    match env.global_context.arithmetic_mode() {
+        ArithmeticMode::Abort => aborting(args),
+        ArithmeticMode::Wrapping => wrapping(args),
+    }
+}
+
 pub fn native_add(mut args: Vec<Value>) -> InterpreterResult<Value> {
     type_force_variadic_arithmetic!(add, args)
 }
+fn native_wrapping_add(mut args: Vec<Value>) -> InterpreterResult<Value> {
+    type_force_variadic_arithmetic!(wrapping_add, args)
+}
+pub fn native_add_env(args: Vec<Value>, env: &mut Environment) -> InterpreterResult<Value> {
+    in_arithmetic_mode(args, env, native_add, native_wrapping_add)
+}
+pub fn native_add_v2(args: Vec<Value>, env: &mut Environment) -> InterpreterResult<Value> {
+    native_add_env(list_operands(args, 0)?, env)
+}
 pub fn native_sub(mut args: Vec<Value>) -> InterpreterResult<Value> {
     type_force_variadic_arithmetic!(sub, args)
 }
+fn native_wrapping_sub(mut args: Vec<Value>) -> InterpreterResult<Value> {
+    type_force_variadic_arithmetic!(wrapping_sub, args)
+}
+pub fn native_sub_env(args: Vec<Value>, env: &mut Environment) -> InterpreterResult<Value> {
+    in_arithmetic_mode(args, env, native_sub, native_wrapping_sub)
+}
 pub fn native_mul(mut args: Vec<Value>) -> InterpreterResult<Value> {
     type_force_variadic_arithmetic!(mul, args)
 }
+fn native_wrapping_mul(mut args: Vec<Value>) -> InterpreterResult<Value> {
+    type_force_variadic_arithmetic!(wrapping_mul, args)
+}
+pub fn native_mul_env(args: Vec<Value>, env: &mut Environment) -> InterpreterResult<Value> {
+    in_arithmetic_mode(args, env, native_mul, native_wrapping_mul)
+}
+pub fn native_mul_v2(args: Vec<Value>, env: &mut Environment) -> InterpreterResult<Value> {
+    native_mul_env(list_operands(args, 1)?, env)
+}
 pub fn native_div(mut args: Vec<Value>) -> InterpreterResult<Value> {
     type_force_variadic_arithmetic!(div, args)
 }
+pub fn native_mul_div(mut args: Vec<Value>) -> InterpreterResult<Value> {
+    type_force_variadic_arithmetic!(mul_div, args)
+}
 pub fn native_pow(a: Value, b: Value) -> InterpreterResult<Value> {
     type_force_binary_arithmetic!(pow, a, b)
 }
@@ -0,0 +1,126 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::vm::errors::CheckErrors;
+use crate::vm::functions::NativeFunctions;
+use crate::vm::ClarityVersion;
+
+/// The number of arguments a native function may be applied to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NativeArity {
+    Exactly(usize),
+    AtLeast(usize),
+    Between(usize, usize),
+}
+
+impl NativeArity {
+    pub fn check(&self, found: usize) -> Result<(), CheckErrors> {
+        match *self {
+            NativeArity::Exactly(expected) if found != expected => {
+                Err(CheckErrors::IncorrectArgumentCount(expected, found))
+            }
+            NativeArity::AtLeast(min) | NativeArity::Between(min, _) if found < min => {
+                Err(CheckErrors::RequiresAtLeastArguments(min, found))
+            }
+            NativeArity::Between(_, max) if found > max => {
+                Err(CheckErrors::RequiresAtMostArguments(max, found))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl NativeFunctions {
+    /// The arity of this native, independent of the types of its arguments.
+    pub fn arity(&self) -> NativeArity {
+        use crate::vm::functions::NativeFunctions::*;
+        use NativeArity::*;
+        match self {
+            Add | Subtract | Multiply | Divide => AtLeast(1),
+            CmpGeq | CmpLeq | CmpLess | CmpGreater => Exactly(2),
+            ToInt | ToUInt => Exactly(1),
+            Modulo | Power => Exactly(2),
+            MulDiv => Exactly(3),
+            Sqrti | Log2 => Exactly(1),
+            BitwiseXor => Exactly(2),
+            And | Or => AtLeast(1),
+            Not => Exactly(1),
+            Equals => AtLeast(1),
+            If => Exactly(3),
+            Let => AtLeast(2),
+            Map => AtLeast(2),
+            MapTry => Exactly(2),
+            Fold => Exactly(3),
+            Filter => Exactly(2),
+            Append | Concat | AsMaxLen => Exactly(2),
+            Len => Exactly(1),
+            ElementAt | ElementAtAlias | IndexOf | IndexOfAlias => Exactly(2),
+            Slice | ReplaceAt => Exactly(3),
+            Sort => Exactly(1),
+            AssertEq => Exactly(2),
+            BuffToIntLe | BuffToUIntLe | BuffToIntBe | BuffToUIntBe => Exactly(1),
+            IsStandard | PrincipalDestruct => Exactly(1),
+            PrincipalConstruct => Between(2, 3),
+            StringToInt | StringToUInt | IntToAscii | IntToUtf8 => Exactly(1),
+            ListCons => AtLeast(0),
+            FetchVar => Exactly(1),
+            SetVar => Exactly(2),
+            FetchEntry | DeleteEntry => Exactly(2),
+            SetEntry | InsertEntry => Exactly(3),
+            TupleCons => AtLeast(1),
+            TupleGet | TupleGetOptional | TupleMerge => Exactly(2),
+            Begin => AtLeast(1),
+            Hash160 | Sha256 | Sha512 | Sha512Trunc256 | Keccak256 => Exactly(1),
+            Secp256k1Recover => Exactly(2),
+            Secp256k1Verify => Exactly(3),
+            Print => Exactly(1),
+            ContractCall => AtLeast(2),
+            AsContract | ContractOf | PrincipalOf => Exactly(1),
+            AtBlock => Exactly(2),
+            GetBlockInfo | GetBurnBlockInfo => Exactly(2),
+            ConsError | ConsOkay | ConsSome => Exactly(1),
+            DefaultTo | UnwrapOrElse | Asserts | UnwrapRet | UnwrapErrRet => Exactly(2),
+            Unwrap | UnwrapErr | TryRet => Exactly(1),
+            // `match` takes 4 arguments over an optional and 5 over a response: the
+            //  type checker reports the exact count once the input type is known.
+            Match => AtLeast(1),
+            IsOkay | IsNone | IsErr | IsSome => Exactly(1),
+            GetTokenBalance | GetAssetOwner => Exactly(2),
+            TransferToken | TransferAsset => Exactly(4),
+            MintAsset | MintToken | BurnToken | BurnAsset => Exactly(3),
+            GetTokenSupply => Exactly(1),
+            GetStxBalance | StxGetAccount => Exactly(1),
+            StxTransfer => Exactly(3),
+            StxTransferMemo => Exactly(4),
+            StxBurn => Exactly(2),
+            BitwiseAnd | BitwiseOr | BitwiseXor2 => AtLeast(1),
+            BitwiseNot | BitCount | LeadingZeros => Exactly(1),
+            BitwiseLShift | BitwiseRShift => Exactly(2),
+            ToConsensusBuff | ToAscii | StringToBuff => Exactly(1),
+            FromConsensusBuff => Exactly(2),
+        }
+    }
+
+    /// The arity of this native when applied in a contract of the given Clarity version.
+    pub fn arity_at_version(&self, version: &ClarityVersion) -> NativeArity {
+        match self {
+            NativeFunctions::If if *version >= ClarityVersion::Clarity3 => {
+                NativeArity::Between(2, 3)
+            }
+            _ => self.arity(),
+        }
+    }
+}
@@ -157,6 +157,8 @@ pub fn stx_transfer_consolidated(
     sender_snapshot.transfer_to(to, amount)?;
 
     env.global_context.log_stx_transfer(&from, amount)?;
+    env.global_context
+        .log_net_transfer(Some(from), Some(to), &AssetIdentifier::STX(), amount);
     env.register_stx_transfer_event(from.clone(), to.clone(), amount, memo.clone())?;
     Ok(Value::okay_true())
 }
@@ -291,6 +293,8 @@ pub fn special_stx_burn(
             .decrement_ustx_liquid_supply(amount)?;
 
         env.global_context.log_stx_burn(&from, amount)?;
+        env.global_context
+            .log_net_transfer(Some(from), None, &AssetIdentifier::STX(), amount);
         env.register_stx_burn_event(from.clone(), amount)?;
 
         Ok(Value::okay_true())
@@ -354,6 +358,8 @@ pub fn special_mint_token(
             contract_identifier: env.contract_context.contract_identifier.clone(),
             asset_name: token_name.clone(),
         };
+        env.global_context
+            .log_net_transfer(None, Some(to_principal), &asset_identifier, amount);
         env.register_ft_mint_event(to_principal.clone(), amount, asset_identifier)?;
 
         Ok(Value::okay_true())
@@ -418,6 +424,12 @@ pub fn special_mint_asset_v200(
             contract_identifier: env.contract_context.contract_identifier.clone(),
             asset_name: asset_name.clone(),
         };
+        env.global_context.log_net_asset_transfer(
+            None,
+            Some(to_principal),
+            &asset_identifier,
+            &asset,
+        );
         env.register_nft_mint_event(to_principal.clone(), asset, asset_identifier)?;
 
         Ok(Value::okay_true())
@@ -481,6 +493,12 @@ pub fn special_mint_asset_v205(
             contract_identifier: env.contract_context.contract_identifier.clone(),
             asset_name: asset_name.clone(),
         };
+        env.global_context.log_net_asset_transfer(
+            None,
+            Some(to_principal),
+            &asset_identifier,
+            &asset,
+        );
         env.register_nft_mint_event(to_principal.clone(), asset, asset_identifier)?;
 
         Ok(Value::okay_true())
@@ -563,6 +581,12 @@ pub fn special_transfer_asset_v200(
             contract_identifier: env.contract_context.contract_identifier.clone(),
             asset_name: asset_name.clone(),
         };
+        env.global_context.log_net_asset_transfer(
+            Some(from_principal),
+            Some(to_principal),
+            &asset_identifier,
+            &asset,
+        );
         env.register_nft_transfer_event(
             from_principal.clone(),
             to_principal.clone(),
@@ -649,6 +673,12 @@ pub fn special_transfer_asset_v205(
             contract_identifier: env.contract_context.contract_identifier.clone(),
             asset_name: asset_name.clone(),
         };
+        env.global_context.log_net_asset_transfer(
+            Some(from_principal),
+            Some(to_principal),
+            &asset_identifier,
+            &asset,
+        );
         env.register_nft_transfer_event(
             from_principal.clone(),
             to_principal.clone(),
@@ -750,6 +780,12 @@ pub fn special_transfer_token(
             contract_identifier: env.contract_context.contract_identifier.clone(),
             asset_name: token_name.clone(),
         };
+        env.global_context.log_net_transfer(
+            Some(from_principal),
+            Some(to_principal),
+            &asset_identifier,
+            amount,
+        );
         env.register_ft_transfer_event(
             from_principal.clone(),
             to_principal.clone(),
@@ -947,6 +983,8 @@ pub fn special_burn_token(
             contract_identifier: env.contract_context.contract_identifier.clone(),
             asset_name: token_name.clone(),
         };
+        env.global_context
+            .log_net_transfer(Some(burner), None, &asset_identifier, amount);
         env.register_ft_burn_event(burner.clone(), amount, asset_identifier)?;
 
         env.add_memory(TypeSignature::PrincipalType.size() as u64)?;
@@ -1035,6 +1073,12 @@ pub fn special_burn_asset_v200(
             contract_identifier: env.contract_context.contract_identifier.clone(),
             asset_name: asset_name.clone(),
         };
+        env.global_context.log_net_asset_transfer(
+            Some(sender_principal),
+            None,
+            &asset_identifier,
+            &asset,
+        );
         env.register_nft_burn_event(sender_principal.clone(), asset, asset_identifier)?;
 
         Ok(Value::okay_true())
@@ -1112,6 +1156,12 @@ pub fn special_burn_asset_v205(
             contract_identifier: env.contract_context.contract_identifier.clone(),
             asset_name: asset_name.clone(),
         };
+        env.global_context.log_net_asset_transfer(
+            Some(sender_principal),
+            None,
+            &asset_identifier,
+            &asset,
+        );
         env.register_nft_burn_event(sender_principal.clone(), asset, asset_identifier)?;
 
         Ok(Value::okay_true())
@@ -31,6 +31,8 @@ fn type_force_bool(value: &Value) -> Result<bool> {
     }
 }
 
+/// Evaluates its operands left to right and stops at the first `true`:
+///  later operands are never evaluated, so none of their effects occur.
 pub fn special_or(
     args: &[SymbolicExpression],
     env: &mut Environment,
@@ -51,6 +53,8 @@ pub fn special_or(
     Ok(Value::Bool(false))
 }
 
+/// Evaluates its operands left to right and stops at the first `false`:
+///  later operands are never evaluated, so none of their effects occur.
 pub fn special_and(
     args: &[SymbolicExpression],
     env: &mut Environment,
@@ -212,6 +212,41 @@ pub fn native_int_to_utf8(value: Value) -> Result<Value> {
     native_int_to_string_generic(value, Value::string_utf8_from_bytes)
 }
 
+/// Returns `(some string-ascii)` holding the bytes of the buffer `value` if every
+/// byte is a character allowed in an ASCII string, and `none` otherwise.
+pub fn native_to_ascii(value: Value) -> Result<Value> {
+    match value {
+        Value::Sequence(SequenceData::Buffer(BuffData { data })) => {
+            match Value::string_ascii_from_bytes(data) {
+                Ok(ascii) => Value::some(ascii),
+                Err(_) => Ok(Value::none()),
+            }
+        }
+        _ => Err(CheckErrors::TypeValueError(TypeSignature::max_buffer(), value).into()),
+    }
+}
+
+/// Returns the bytes of the string `value` as a buffer: one byte per character
+/// of an ASCII string, and the UTF-8 encoding of a UTF-8 string.
+pub fn native_string_to_buff(value: Value) -> Result<Value> {
+    match value {
+        Value::Sequence(SequenceData::String(CharType::ASCII(ASCIIData { data }))) => {
+            Value::buff_from(data)
+        }
+        Value::Sequence(SequenceData::String(CharType::UTF8(UTF8Data { data }))) => {
+            Value::buff_from(data.concat())
+        }
+        _ => Err(CheckErrors::UnionTypeValueError(
+            vec![
+                TypeSignature::max_string_ascii(),
+                TypeSignature::max_string_utf8(),
+            ],
+            value,
+        )
+        .into()),
+    }
+}
+
 /// Returns `value` consensus serialized into a `(optional buff)` object.
 /// If the value cannot fit as serialized into the maximum buffer size,
 /// this returns `none`, otherwise, it will be `(some consensus-serialized-buffer)`
@@ -30,43 +30,43 @@ use crate::vm::types::{
     BuffData, CharType, PrincipalData, ResponseData, SequenceData, TypeSignature, Value, BUFF_32,
     BUFF_33, BUFF_65,
 };
+use crate::vm::util::hash::{hash_value, HashFunction};
 use crate::vm::{eval, ClarityVersion, Environment, LocalContext};
 use stacks_common::address::AddressHashMode;
 use stacks_common::address::{
     C32_ADDRESS_VERSION_MAINNET_SINGLESIG, C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
 };
+use stacks_common::codec::StacksMessageCodec;
 use stacks_common::util::hash;
 use stacks_common::util::secp256k1::{secp256k1_recover, secp256k1_verify, Secp256k1PublicKey};
 
 use crate::types::chainstate::StacksAddress;
 
 macro_rules! native_hash_func {
-    ($name:ident, $module:ty) => {
+    ($name:ident, $name_v3:ident, $function:expr) => {
         pub fn $name(input: Value) -> Result<Value> {
-            let bytes = match input {
-                Value::Int(value) => Ok(value.to_le_bytes().to_vec()),
-                Value::UInt(value) => Ok(value.to_le_bytes().to_vec()),
-                Value::Sequence(SequenceData::Buffer(value)) => Ok(value.data),
-                _ => Err(CheckErrors::UnionTypeValueError(
-                    vec![
-                        TypeSignature::IntType,
-                        TypeSignature::UIntType,
-                        TypeSignature::max_buffer(),
-                    ],
-                    input,
-                )),
-            }?;
-            let hash = <$module>::from_data(&bytes);
-            Value::buff_from(hash.as_bytes().to_vec())
+            Value::buff_from(hash_value($function, &input, &ClarityVersion::Clarity1)?)
+        }
+
+        pub fn $name_v3(input: Value) -> Result<Value> {
+            Value::buff_from(hash_value($function, &input, &ClarityVersion::Clarity3)?)
         }
     };
 }
 
-native_hash_func!(native_hash160, hash::Hash160);
-native_hash_func!(native_sha256, hash::Sha256Sum);
-native_hash_func!(native_sha512, hash::Sha512Sum);
-native_hash_func!(native_sha512trunc256, hash::Sha512Trunc256Sum);
-native_hash_func!(native_keccak256, hash::Keccak256Hash);
+native_hash_func!(native_hash160, native_hash160_v3, HashFunction::Hash160);
+native_hash_func!(native_sha256, native_sha256_v3, HashFunction::Sha256);
+native_hash_func!(native_sha512, native_sha512_v3, HashFunction::Sha512);
+native_hash_func!(
+    native_sha512trunc256,
+    native_sha512trunc256_v3,
+    HashFunction::Sha512Trunc256
+);
+native_hash_func!(
+    native_keccak256,
+    native_keccak256_v3,
+    HashFunction::Keccak256
+);
 
 // Note: Clarity1 had a bug in how the address is computed (issues/2619).
 // This method preserves the old, incorrect behavior for those running Clarity1.
@@ -267,7 +267,6 @@ pub fn special_fetch_variable_v205(
 
     let result = env
         .global_context
-        .database
         .lookup_variable_with_size(contract, var_name, data_types);
 
     let result_size = match &result {
@@ -342,6 +341,7 @@ pub fn special_set_variable_v205(
         .get(var_name)
         .ok_or(CheckErrors::NoSuchDataVariable(var_name.to_string()))?;
 
+    env.global_context.evict_cached_variable(contract, var_name);
     let result = env
         .global_context
         .database
@@ -412,7 +412,6 @@ pub fn special_fetch_entry_v205(
 
     let result = env
         .global_context
-        .database
         .fetch_entry_with_size(contract, map_name, &key, data_types);
 
     let result_size = match &result {
@@ -519,6 +518,8 @@ pub fn special_set_entry_v205(
         .get(map_name)
         .ok_or(CheckErrors::NoSuchMap(map_name.to_string()))?;
 
+    env.global_context
+        .evict_cached_entry(contract, map_name, &key);
     let result = env
         .global_context
         .database
@@ -603,6 +604,8 @@ pub fn special_insert_entry_v205(
         .get(map_name)
         .ok_or(CheckErrors::NoSuchMap(map_name.to_string()))?;
 
+    env.global_context
+        .evict_cached_entry(contract, map_name, &key);
     let result = env
         .global_context
         .database
@@ -682,6 +685,8 @@ pub fn special_delete_entry_v205(
         .get(map_name)
         .ok_or(CheckErrors::NoSuchMap(map_name.to_string()))?;
 
+    env.global_context
+        .evict_cached_entry(contract, map_name, &key);
     let result = env
         .global_context
         .database
@@ -20,7 +20,7 @@ use crate::vm::costs::{
     constants as cost_constants, cost_functions, runtime_cost, CostTracker, MemoryConsumer,
 };
 use crate::vm::errors::{
-    check_argument_count, check_arguments_at_least, CheckErrors, Error,
+    check_argument_count, check_arguments_at_least, check_arguments_at_most, CheckErrors, Error,
     InterpreterResult as Result, RuntimeErrorType, ShortReturnType,
 };
 pub use crate::vm::functions::assets::stx_transfer_consolidated;
@@ -64,6 +64,7 @@ macro_rules! switch_on_global_epoch {
 use crate::vm::ClarityVersion;
 
 mod arithmetic;
+pub mod arity;
 mod assets;
 mod boolean;
 mod conversions;
@@ -184,6 +185,16 @@ define_versioned_named_enum!(NativeFunctions(ClarityVersion) {
     ToConsensusBuff("to-consensus-buff?", ClarityVersion::Clarity2),
     FromConsensusBuff("from-consensus-buff?", ClarityVersion::Clarity2),
     ReplaceAt("replace-at?", ClarityVersion::Clarity2),
+    Sort("sort", ClarityVersion::Clarity3),
+    AssertEq("assert-eq!", ClarityVersion::Clarity3),
+    TupleGetOptional("get?", ClarityVersion::Clarity3),
+    MulDiv("mul-div", ClarityVersion::Clarity3),
+    ToAscii("to-ascii?", ClarityVersion::Clarity3),
+    StringToBuff("string-to-buff", ClarityVersion::Clarity3),
+    UnwrapOrElse("unwrap-or-else", ClarityVersion::Clarity3),
+    BitCount("bit-count", ClarityVersion::Clarity3),
+    LeadingZeros("leading-zeros", ClarityVersion::Clarity3),
+    MapTry("map-try!", ClarityVersion::Clarity3),
 });
 
 impl NativeFunctions {
@@ -210,26 +221,47 @@ pub fn lookup_reserved_functions(name: &str, version: &ClarityVersion) -> Option
     use crate::vm::functions::NativeFunctions::*;
     if let Some(native_function) = NativeFunctions::lookup_by_name_at_version(name, version) {
         let callable = match native_function {
-            Add => NativeFunction(
-                "native_add",
-                NativeHandle::MoreArg(&arithmetic::native_add),
-                ClarityCostFunction::Add,
-            ),
+            Add => match version {
+                ClarityVersion::Clarity1 | ClarityVersion::Clarity2 => NativeFunction(
+                    "native_add",
+                    NativeHandle::MoreArgEnv(&arithmetic::native_add_env),
+                    ClarityCostFunction::Add,
+                ),
+                ClarityVersion::Clarity3 => NativeFunction205(
+                    "native_add",
+                    NativeHandle::MoreArgEnv(&arithmetic::native_add_v2),
+                    ClarityCostFunction::Add,
+                    &arithmetic::cost_input_list_operands,
+                ),
+            },
             Subtract => NativeFunction(
                 "native_sub",
-                NativeHandle::MoreArg(&arithmetic::native_sub),
+                NativeHandle::MoreArgEnv(&arithmetic::native_sub_env),
                 ClarityCostFunction::Sub,
             ),
-            Multiply => NativeFunction(
-                "native_mul",
-                NativeHandle::MoreArg(&arithmetic::native_mul),
-                ClarityCostFunction::Mul,
-            ),
+            Multiply => match version {
+                ClarityVersion::Clarity1 | ClarityVersion::Clarity2 => NativeFunction(
+                    "native_mul",
+                    NativeHandle::MoreArgEnv(&arithmetic::native_mul_env),
+                    ClarityCostFunction::Mul,
+                ),
+                ClarityVersion::Clarity3 => NativeFunction205(
+                    "native_mul",
+                    NativeHandle::MoreArgEnv(&arithmetic::native_mul_v2),
+                    ClarityCostFunction::Mul,
+                    &arithmetic::cost_input_list_operands,
+                ),
+            },
             Divide => NativeFunction(
                 "native_div",
                 NativeHandle::MoreArg(&arithmetic::native_div),
                 ClarityCostFunction::Div,
             ),
+            MulDiv => NativeFunction(
+                "native_mul_div",
+                NativeHandle::MoreArg(&arithmetic::native_mul_div),
+                ClarityCostFunction::MulDiv,
+            ),
             CmpGeq => SpecialFunction("special_geq", &arithmetic::special_geq),
             CmpLeq => SpecialFunction("special_leq", &arithmetic::special_leq),
             CmpLess => SpecialFunction("special_le", &arithmetic::special_less),
@@ -287,6 +319,7 @@ pub fn lookup_reserved_functions(name: &str, version: &ClarityVersion) -> Option
             FetchVar => SpecialFunction("special_var-get", &database::special_fetch_variable),
             SetVar => SpecialFunction("special_set-var", &database::special_set_variable),
             Map => SpecialFunction("special_map", &sequences::special_map),
+            MapTry => SpecialFunction("special_map_try", &sequences::special_map_try),
             Filter => SpecialFunction("special_filter", &sequences::special_filter),
             BuffToIntLe => NativeFunction(
                 "native_buff_to_int_le",
@@ -365,6 +398,9 @@ pub fn lookup_reserved_functions(name: &str, version: &ClarityVersion) -> Option
             DeleteEntry => SpecialFunction("special_delete-entry", &database::special_delete_entry),
             TupleCons => SpecialFunction("special_tuple", &tuples::tuple_cons),
             TupleGet => SpecialFunction("special_get-tuple", &tuples::tuple_get),
+            TupleGetOptional => {
+                SpecialFunction("special_get-tuple-optional", &tuples::tuple_get_optional)
+            }
             TupleMerge => NativeFunction205(
                 "native_merge-tuple",
                 NativeHandle::DoubleArg(&tuples::tuple_merge),
@@ -378,31 +414,50 @@ pub fn lookup_reserved_functions(name: &str, version: &ClarityVersion) -> Option
             ),
             Hash160 => NativeFunction205(
                 "native_hash160",
-                NativeHandle::SingleArg(&crypto::native_hash160),
+                NativeHandle::SingleArg(match version {
+                    ClarityVersion::Clarity1 | ClarityVersion::Clarity2 => &crypto::native_hash160,
+                    ClarityVersion::Clarity3 => &crypto::native_hash160_v3,
+                }),
                 ClarityCostFunction::Hash160,
                 &cost_input_sized_vararg,
             ),
             Sha256 => NativeFunction205(
                 "native_sha256",
-                NativeHandle::SingleArg(&crypto::native_sha256),
+                NativeHandle::SingleArg(match version {
+                    ClarityVersion::Clarity1 | ClarityVersion::Clarity2 => &crypto::native_sha256,
+                    ClarityVersion::Clarity3 => &crypto::native_sha256_v3,
+                }),
                 ClarityCostFunction::Sha256,
                 &cost_input_sized_vararg,
             ),
             Sha512 => NativeFunction205(
                 "native_sha512",
-                NativeHandle::SingleArg(&crypto::native_sha512),
+                NativeHandle::SingleArg(match version {
+                    ClarityVersion::Clarity1 | ClarityVersion::Clarity2 => &crypto::native_sha512,
+                    ClarityVersion::Clarity3 => &crypto::native_sha512_v3,
+                }),
                 ClarityCostFunction::Sha512,
                 &cost_input_sized_vararg,
             ),
             Sha512Trunc256 => NativeFunction205(
                 "native_sha512trunc256",
-                NativeHandle::SingleArg(&crypto::native_sha512trunc256),
+                NativeHandle::SingleArg(match version {
+                    ClarityVersion::Clarity1 | ClarityVersion::Clarity2 => {
+                        &crypto::native_sha512trunc256
+                    }
+                    ClarityVersion::Clarity3 => &crypto::native_sha512trunc256_v3,
+                }),
                 ClarityCostFunction::Sha512t256,
                 &cost_input_sized_vararg,
             ),
             Keccak256 => NativeFunction205(
                 "native_keccak256",
-                NativeHandle::SingleArg(&crypto::native_keccak256),
+                NativeHandle::SingleArg(match version {
+                    ClarityVersion::Clarity1 | ClarityVersion::Clarity2 => {
+                        &crypto::native_keccak256
+                    }
+                    ClarityVersion::Clarity3 => &crypto::native_keccak256_v3,
+                }),
                 ClarityCostFunction::Keccak256,
                 &cost_input_sized_vararg,
             ),
@@ -442,6 +497,9 @@ pub fn lookup_reserved_functions(name: &str, version: &ClarityVersion) -> Option
                 NativeHandle::SingleArg(&options::native_error),
                 ClarityCostFunction::ErrCons,
             ),
+            UnwrapOrElse => {
+                SpecialFunction("special_unwrap_or_else", &options::special_unwrap_or_else)
+            }
             DefaultTo => NativeFunction(
                 "native_default_to",
                 NativeHandle::DoubleArg(&options::native_default_to),
@@ -525,10 +583,34 @@ pub fn lookup_reserved_functions(name: &str, version: &ClarityVersion) -> Option
                 ClarityCostFunction::ToConsensusBuff,
                 &cost_input_sized_vararg,
             ),
+            ToAscii => NativeFunction205(
+                "native_to_ascii",
+                NativeHandle::SingleArg(&conversions::native_to_ascii),
+                ClarityCostFunction::ToAscii,
+                &cost_input_sized_vararg,
+            ),
+            StringToBuff => NativeFunction205(
+                "native_string_to_buff",
+                NativeHandle::SingleArg(&conversions::native_string_to_buff),
+                ClarityCostFunction::StringToBuff,
+                &cost_input_sized_vararg,
+            ),
             FromConsensusBuff => {
                 SpecialFunction("from_consensus_buff", &conversions::from_consensus_buff)
             }
             ReplaceAt => SpecialFunction("replace_at", &sequences::special_replace_at),
+            Sort => NativeFunction205(
+                "native_sort",
+                NativeHandle::SingleArg(&sequences::native_sort),
+                ClarityCostFunction::Sort,
+                &cost_input_sized_vararg,
+            ),
+            AssertEq => NativeFunction205(
+                "native_assert_eq",
+                NativeHandle::MoreArgEnv(&native_assert_eq),
+                ClarityCostFunction::AssertEq,
+                &cost_input_sized_vararg,
+            ),
             BitwiseAnd => NativeFunction(
                 "native_bitwise_and",
                 NativeHandle::MoreArg(&arithmetic::native_bitwise_and),
@@ -544,6 +626,16 @@ pub fn lookup_reserved_functions(name: &str, version: &ClarityVersion) -> Option
                 NativeHandle::SingleArg(&arithmetic::native_bitwise_not),
                 ClarityCostFunction::BitwiseNot,
             ),
+            BitCount => NativeFunction(
+                "native_bit_count",
+                NativeHandle::SingleArg(&arithmetic::native_bit_count),
+                ClarityCostFunction::BitCount,
+            ),
+            LeadingZeros => NativeFunction(
+                "native_leading_zeros",
+                NativeHandle::SingleArg(&arithmetic::native_leading_zeros),
+                ClarityCostFunction::LeadingZeros,
+            ),
             BitwiseLShift => NativeFunction(
                 "native_bitwise_left_shift",
                 NativeHandle::DoubleArg(&arithmetic::native_bitwise_left_shift),
@@ -566,6 +658,9 @@ pub fn lookup_reserved_functions(name: &str, version: &ClarityVersion) -> Option
     }
 }
 
+/// `is-eq` is strict: every argument must share a least supertype with the others,
+///  or a `TypeError` is raised rather than returning `false`. So `(ok 1)` and `(some 1)`
+///  never compare, while `(ok 1)` and `(err 1)` do (both are responses) and are unequal.
 fn native_eq(args: Vec<Value>, env: &mut Environment) -> Result<Value> {
     // TODO: this currently uses the derived equality checks of Value,
     //   however, that's probably not how we want to implement equality
@@ -588,6 +683,28 @@ fn native_eq(args: Vec<Value>, env: &mut Environment) -> Result<Value> {
     }
 }
 
+fn native_assert_eq(mut args: Vec<Value>, env: &mut Environment) -> Result<Value> {
+    check_argument_count(2, &args)?;
+    if !env.global_context.is_debug_mode() {
+        return Err(
+            RuntimeErrorType::DebugOnlyFunction(NativeFunctions::AssertEq.get_name()).into(),
+        );
+    }
+
+    let expected = args.pop().unwrap();
+    let actual = args.pop().unwrap();
+    TypeSignature::least_supertype(
+        env.epoch(),
+        &TypeSignature::type_of(&actual),
+        &TypeSignature::type_of(&expected),
+    )?;
+    if actual == expected {
+        Ok(Value::okay_true())
+    } else {
+        Err(RuntimeErrorType::AssertionFailed(actual, expected).into())
+    }
+}
+
 fn native_begin(mut args: Vec<Value>) -> Result<Value> {
     match args.pop() {
         Some(v) => Ok(v),
@@ -617,14 +734,27 @@ fn special_if(
     env: &mut Environment,
     context: &LocalContext,
 ) -> Result<Value> {
-    check_argument_count(3, args)?;
+    // Clarity3 admits a single-arm `(if cond expr)`, which evaluates to
+    //  `(some expr)` or `none`.
+    if *env.contract_context.get_clarity_version() >= ClarityVersion::Clarity3 {
+        check_arguments_at_least(2, args)?;
+        check_arguments_at_most(3, args)?;
+    } else {
+        check_argument_count(3, args)?;
+    }
 
     runtime_cost(ClarityCostFunction::If, env, 0)?;
     // handle the conditional clause.
     let conditional = eval(&args[0], env, context)?;
     match conditional {
         Value::Bool(result) => {
-            if result {
+            if args.len() == 2 {
+                if result {
+                    Ok(Value::some(eval(&args[1], env, context)?)?)
+                } else {
+                    Ok(Value::none())
+                }
+            } else if result {
                 eval(&args[1], env, context)
             } else {
                 eval(&args[2], env, context)
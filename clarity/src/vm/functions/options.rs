@@ -259,6 +259,30 @@ pub fn native_error(input: Value) -> Result<Value> {
     Ok(Value::error(input)?)
 }
 
+/// Executes the Clarity3 function `unwrap-or-else`. Unlike `default-to`, the fallback is
+///  only evaluated, by calling the named function without arguments, if the optional is
+///  `none`.
+pub fn special_unwrap_or_else(
+    args: &[SymbolicExpression],
+    env: &mut Environment,
+    context: &LocalContext,
+) -> Result<Value> {
+    check_argument_count(2, args)?;
+
+    runtime_cost(ClarityCostFunction::UnwrapOrElse, env, 0)?;
+
+    let fallback_name = args[1].match_atom().ok_or(CheckErrors::ExpectedName)?;
+    let input = vm::eval(&args[0], env, context)?;
+    match input {
+        Value::Optional(OptionalData { data: Some(data) }) => Ok(*data),
+        Value::Optional(OptionalData { data: None }) => {
+            let fallback = vm::lookup_function(fallback_name, env)?;
+            vm::apply(&fallback, &[], env, context)
+        }
+        _ => Err(CheckErrors::ExpectedOptionalValue(input).into()),
+    }
+}
+
 pub fn native_default_to(default: Value, input: Value) -> Result<Value> {
     match input {
         Value::Optional(data) => match data.data {
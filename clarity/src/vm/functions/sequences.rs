@@ -22,10 +22,12 @@ use crate::vm::errors::{
 };
 use crate::vm::representations::{SymbolicExpression, SymbolicExpressionType};
 use crate::vm::types::{
-    signatures::ListTypeData, CharType, ListData, SequenceData, TypeSignature,
+    signatures::ListTypeData, CharType, ListData, ResponseData, SequenceData, TypeSignature,
     TypeSignature::BoolType, Value,
 };
-use crate::vm::{apply, eval, lookup_function, CallableType, Environment, LocalContext};
+use crate::vm::{
+    apply, eval, lookup_function, CallableType, ClarityVersion, Environment, LocalContext,
+};
 use stacks_common::types::StacksEpochId;
 use std::cmp;
 use std::convert::{TryFrom, TryInto};
@@ -170,6 +172,43 @@ pub fn special_map(
     Value::list_from(mapped_results)
 }
 
+/// Executes the Clarity3 function `map-try!`, which applies a function returning a response
+///  to each element of a sequence, in order. The first `(err ...)` result is returned without
+///  applying the function to the remaining elements. Otherwise, the `(ok ...)` values are
+///  collected into `(ok (list ...))`.
+pub fn special_map_try(
+    args: &[SymbolicExpression],
+    env: &mut Environment,
+    context: &LocalContext,
+) -> Result<Value> {
+    check_argument_count(2, args)?;
+
+    runtime_cost(ClarityCostFunction::MapTry, env, args.len())?;
+
+    let function_name = args[0].match_atom().ok_or(CheckErrors::ExpectedName)?;
+    let function = lookup_function(function_name, env)?;
+
+    let mut sequence = eval(&args[1], env, context)?;
+    let elements = match sequence {
+        Value::Sequence(ref mut sequence_data) => sequence_data.atom_values(),
+        _ => return Err(CheckErrors::ExpectedSequence(TypeSignature::type_of(&sequence)).into()),
+    };
+
+    let mut mapped_results = Vec::with_capacity(elements.len());
+    for element in elements.into_iter() {
+        match apply(&function, &[element], env, context)? {
+            Value::Response(ResponseData {
+                committed: true,
+                data,
+            }) => mapped_results.push(*data),
+            err_response @ Value::Response(_) => return Ok(err_response),
+            result => return Err(CheckErrors::ExpectedResponseValue(result).into()),
+        }
+    }
+
+    Value::okay(Value::list_from(mapped_results)?)
+}
+
 pub fn special_append(
     args: &[SymbolicExpression],
     env: &mut Environment,
@@ -281,18 +320,33 @@ pub fn special_as_max_len(
 
     runtime_cost(ClarityCostFunction::AsMaxLen, env, 0)?;
 
-    if let Some(Value::UInt(expected_len)) = args[1].match_literal_value() {
+    let static_len = match args[1].expr {
+        SymbolicExpressionType::LiteralValue(Value::UInt(expected_len)) => Some(expected_len),
+        // Clarity3 also accepts a constant, which analysis has checked was defined as a
+        //  literal uint
+        SymbolicExpressionType::Atom(ref name)
+            if *env.contract_context.get_clarity_version() >= ClarityVersion::Clarity3 =>
+        {
+            match env.contract_context.lookup_variable(name) {
+                Some(Value::UInt(expected_len)) => Some(*expected_len),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+
+    if let Some(expected_len) = static_len {
         let sequence_len = match sequence {
             Value::Sequence(ref sequence_data) => sequence_data.len() as u128,
             _ => {
                 return Err(CheckErrors::ExpectedSequence(TypeSignature::type_of(&sequence)).into())
             }
         };
-        if sequence_len > *expected_len {
+        if sequence_len > expected_len {
             Ok(Value::none())
         } else {
             if let Value::Sequence(SequenceData::List(ref mut list)) = sequence {
-                list.type_signature.reduce_max_len(*expected_len as u32);
+                list.type_signature.reduce_max_len(expected_len as u32);
             }
             Ok(Value::some(sequence)?)
         }
@@ -358,6 +412,9 @@ pub fn special_slice(
     let seq = eval(&args[0], env, context)?;
     let left_position = eval(&args[1], env, context)?;
     let right_position = eval(&args[2], env, context)?;
+    // from Clarity3, an empty slice at the end of the sequence is in bounds
+    let allow_empty_at_end =
+        *env.contract_context.get_clarity_version() >= ClarityVersion::Clarity3;
 
     let sliced_seq_res = (|| {
         match (seq, left_position, right_position) {
@@ -369,7 +426,12 @@ pub fn special_slice(
                     };
 
                 // Perform bound checks. Not necessary to check if positions are less than 0 since the vars are unsigned.
-                if left_position as usize >= seq.len() || right_position as usize > seq.len() {
+                let left_out_of_bounds = if allow_empty_at_end {
+                    left_position as usize > seq.len()
+                } else {
+                    left_position as usize >= seq.len()
+                };
+                if left_out_of_bounds || right_position as usize > seq.len() {
                     return Ok(Value::none());
                 }
                 if right_position < left_position {
@@ -397,6 +459,16 @@ pub fn special_slice(
     }
 }
 
+pub fn native_sort(list: Value) -> Result<Value> {
+    match list {
+        Value::Sequence(SequenceData::List(mut list)) => {
+            list.data.sort_by(Value::canonical_cmp);
+            Ok(Value::Sequence(SequenceData::List(list)))
+        }
+        _ => Err(CheckErrors::ExpectedListApplication.into()),
+    }
+}
+
 pub fn special_replace_at(
     args: &[SymbolicExpression],
     env: &mut Environment,
@@ -76,6 +76,43 @@ pub fn tuple_get(
     }
 }
 
+pub fn tuple_get_optional(
+    args: &[SymbolicExpression],
+    env: &mut Environment,
+    context: &LocalContext,
+) -> Result<Value> {
+    // (get? arg-name tuple-or-optional-tuple)
+    //    like `get`, but an optional field is returned as-is rather than wrapped
+    //    in a second `some`, so that lookups can be chained.
+    check_argument_count(2, args)?;
+
+    let arg_name = args[0].match_atom().ok_or(CheckErrors::ExpectedName)?;
+
+    let value = eval(&args[1], env, context)?;
+
+    let tuple_data = match value {
+        Value::Tuple(tuple_data) => tuple_data,
+        Value::Optional(opt_data) => match opt_data.data {
+            Some(data) => match *data {
+                Value::Tuple(tuple_data) => tuple_data,
+                data => {
+                    return Err(CheckErrors::ExpectedTuple(TypeSignature::type_of(&data)).into())
+                }
+            },
+            None => return Ok(Value::none()),
+        },
+        _ => return Err(CheckErrors::ExpectedTuple(TypeSignature::type_of(&value)).into()),
+    };
+
+    runtime_cost(ClarityCostFunction::TupleGet, env, tuple_data.len())?;
+    match tuple_data.get_owned(arg_name)? {
+        field @ Value::Optional(_) => Ok(field),
+        field => {
+            Ok(Value::some(field).expect("Tuple contents should *always* fit in a some wrapper"))
+        }
+    }
+}
+
 pub fn tuple_merge(base: Value, update: Value) -> Result<Value> {
     let initial_values = match base {
         Value::Tuple(initial_values) => Ok(initial_values),
@@ -39,6 +39,8 @@ pub mod analysis;
 pub mod docs;
 pub mod version;
 
+pub mod util;
+
 pub mod coverage;
 
 pub mod events;
@@ -67,7 +69,7 @@ use crate::vm::costs::{
 use crate::vm::errors::{
     CheckErrors, Error, InterpreterError, InterpreterResult as Result, RuntimeErrorType,
 };
-use crate::vm::functions::define::DefineResult;
+use crate::vm::functions::define::{DefineFunctions, DefineResult};
 pub use crate::vm::types::Value;
 use crate::vm::types::{
     PrincipalData, QualifiedContractIdentifier, TraitIdentifier, TypeSignature,
@@ -166,6 +168,20 @@ pub trait EvalHook {
 
     // Called upon completion of the execution
     fn did_complete(&mut self, _result: core::result::Result<&mut ExecutionResult, String>);
+
+    // Called before a native function is applied, i.e., before its arguments are evaluated
+    fn will_begin_native(&mut self, _env: &mut Environment, _name: &str) {}
+
+    // Called after a native function returns, with the cost it incurred, including the
+    //  cost of evaluating its arguments
+    fn did_finish_native(
+        &mut self,
+        _env: &mut Environment,
+        _name: &str,
+        _cost: &ExecutionCost,
+        _res: &core::result::Result<Value, crate::vm::errors::Error>,
+    ) {
+    }
 }
 
 fn lookup_variable(name: &str, context: &LocalContext, env: &mut Environment) -> Result<Value> {
@@ -210,12 +226,12 @@ pub fn lookup_function(name: &str, env: &mut Environment) -> Result<CallableType
         functions::lookup_reserved_functions(name, env.contract_context.get_clarity_version())
     {
         Ok(result)
-    } else {
-        let user_function = env
-            .contract_context
-            .lookup_function(name)
-            .ok_or(CheckErrors::UndefinedFunction(name.to_string()))?;
+    } else if let Some(user_function) = env.contract_context.lookup_function(name) {
         Ok(CallableType::UserFunction(user_function))
+    } else {
+        env.global_context
+            .lookup_host_function(name)
+            .ok_or(CheckErrors::UndefinedFunction(name.to_string()).into())
     }
 }
 
@@ -247,7 +263,7 @@ pub fn apply(
         return Err(CheckErrors::CircularReference(vec![identifier.to_string()]).into());
     }
 
-    if env.call_stack.depth() >= MAX_CALL_STACK_DEPTH {
+    if env.call_stack.depth() >= env.global_context.max_call_stack_depth() {
         return Err(RuntimeErrorType::MaxStackDepthReached.into());
     }
 
@@ -302,6 +318,10 @@ pub fn apply(
                     .and_then(|_| function.apply(evaluated_args, env))
             }
             CallableType::UserFunction(function) => function.apply(&evaluated_args, env),
+            CallableType::HostFunction(_, cost, function) => env
+                .add_cost(cost.clone())
+                .map_err(Error::from)
+                .and_then(|_| function(&evaluated_args, env)),
             _ => panic!("Should be unreachable."),
         };
         add_stack_trace(&mut resp, env);
@@ -311,6 +331,40 @@ pub fn apply(
     }
 }
 
+// Apply a native function, notifying the eval hooks on entry and on exit with the
+//  cost it incurred.
+fn apply_native_with_hooks(
+    name: &str,
+    function: &CallableType,
+    args: &[SymbolicExpression],
+    env: &mut Environment,
+    context: &LocalContext,
+) -> Result<Value> {
+    if let Some(mut eval_hooks) = env.global_context.eval_hooks.take() {
+        for hook in eval_hooks.iter_mut() {
+            hook.will_begin_native(env, name);
+        }
+        env.global_context.eval_hooks = Some(eval_hooks);
+    }
+
+    let cost_before = env.global_context.cost_track.get_total();
+    let res = apply(function, args, env, context);
+    let mut cost = env.global_context.cost_track.get_total();
+    if cost.sub(&cost_before).is_err() {
+        // the tracker was swapped out during the call, so there is no meaningful delta
+        cost = ExecutionCost::zero();
+    }
+
+    if let Some(mut eval_hooks) = env.global_context.eval_hooks.take() {
+        for hook in eval_hooks.iter_mut() {
+            hook.did_finish_native(env, name, &cost, &res);
+        }
+        env.global_context.eval_hooks = Some(eval_hooks);
+    }
+
+    res
+}
+
 pub fn eval<'a>(
     exp: &SymbolicExpression,
     env: &'a mut Environment,
@@ -320,6 +374,8 @@ pub fn eval<'a>(
         Atom, AtomValue, Field, List, LiteralValue, TraitReference,
     };
 
+    env.global_context.check_execution_deadline()?;
+
     if let Some(mut eval_hooks) = env.global_context.eval_hooks.take() {
         for hook in eval_hooks.iter_mut() {
             hook.will_begin_eval(env, context, exp);
@@ -339,7 +395,11 @@ pub fn eval<'a>(
                 .match_atom()
                 .ok_or(CheckErrors::BadFunctionName)?;
             let f = lookup_function(&function_name, env)?;
-            apply(&f, &rest, env, context)
+            if env.global_context.eval_hooks.is_some() && f.is_native() {
+                apply_native_with_hooks(function_name, &f, rest, env, context)
+            } else {
+                apply(&f, rest, env, context)
+            }
         }
         TraitReference(_, _) | Field(_) => unreachable!("can't be evaluated"),
     };
@@ -354,11 +414,18 @@ pub fn eval<'a>(
     res
 }
 
+/// Whether `name` is reserved by the language at `version`. This is the one check used
+///  wherever a contract binds or defines a name (`let` and `match` bindings, function
+///  arguments and every `define-*`): reserved names are the native functions and special
+///  forms, the native variables (`tx-sender`, `block-height`, ...) and, since Clarity3,
+///  the `define-*` forms themselves.
 pub fn is_reserved(name: &str, version: &ClarityVersion) -> bool {
     if let Some(_result) = functions::lookup_reserved_functions(name, version) {
         true
     } else if variables::is_reserved_name(name, version) {
         true
+    } else if *version >= ClarityVersion::Clarity3 {
+        DefineFunctions::lookup_by_name(name).is_some()
     } else {
         false
     }
@@ -582,6 +649,18 @@ pub fn execute_v2(program: &str) -> Result<Option<Value>> {
     )
 }
 
+/// Execute for test in Clarity3, Epoch21, testnet.
+#[cfg(any(test, feature = "testing"))]
+pub fn execute_v3(program: &str) -> Result<Option<Value>> {
+    execute_with_parameters(
+        program,
+        ClarityVersion::Clarity3,
+        StacksEpochId::Epoch21,
+        ASTRules::PrecheckSize,
+        false,
+    )
+}
+
 #[cfg(test)]
 mod test {
     use crate::types::StacksEpochId;
@@ -14,18 +14,23 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::collections::HashSet;
+
 use crate::vm::ast::ASTRules;
-use crate::vm::contexts::{AssetMap, AssetMapEntry, GlobalContext, OwnedEnvironment};
+use crate::vm::contexts::{
+    AssetMap, AssetMapEntry, GlobalContext, NetAmount, NetAssets, OwnedEnvironment,
+};
 use crate::vm::contracts::Contract;
+use crate::vm::database::MemoryBackingStore;
 use crate::vm::errors::{CheckErrors, Error, RuntimeErrorType};
-use crate::vm::events::StacksTransactionEvent;
+use crate::vm::events::{STXEventType, StacksTransactionEvent};
 use crate::vm::execute as vm_execute;
 use crate::vm::representations::SymbolicExpression;
 use crate::vm::tests::{
     execute, is_committed, is_err_code, symbols_from_values, with_memory_environment,
 };
 use crate::vm::types::{
-    AssetIdentifier, PrincipalData, QualifiedContractIdentifier, ResponseData, Value,
+    AssetIdentifier, PrincipalData, QualifiedContractIdentifier, ResponseData, TupleData, Value,
 };
 use crate::vm::version::ClarityVersion;
 use crate::vm::ContractContext;
@@ -515,6 +520,210 @@ fn test_native_stx_ops(owned_env: &mut OwnedEnvironment) {
     );
 }
 
+fn test_stx_burn(owned_env: &mut OwnedEnvironment) {
+    let contract = r#"(define-public (burn-stx (amount uint)) (stx-burn? amount tx-sender))
+                    (define-public (burn-then-abort (amount uint))
+                      (begin (try! (stx-burn? amount tx-sender)) (err u9)))
+                    (define-read-only (balance-stx (p principal)) (stx-get-balance p))
+                    (define-read-only (supply) stx-liquid-supply)"#;
+
+    let p1 = execute("'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR");
+    let p1_principal = match p1 {
+        Value::Principal(ref data) => data.clone(),
+        _ => panic!(),
+    };
+    let token_contract_id = QualifiedContractIdentifier::local("burner").unwrap();
+
+    owned_env
+        .initialize_contract(
+            token_contract_id.clone(),
+            contract,
+            None,
+            ASTRules::PrecheckSize,
+        )
+        .unwrap();
+    owned_env.stx_faucet(&p1_principal, 1000);
+
+    let read = |owned_env: &mut OwnedEnvironment, function: &str, args: &[Value]| {
+        execute_transaction(
+            owned_env,
+            p1_principal.clone(),
+            &token_contract_id,
+            function,
+            &symbols_from_values(args.to_vec()),
+        )
+        .unwrap()
+        .0
+    };
+    let starting_supply = match read(owned_env, "supply", &[]) {
+        Value::UInt(supply) => supply,
+        _ => panic!(),
+    };
+
+    let (result, asset_map, events) = execute_transaction(
+        owned_env,
+        p1_principal.clone(),
+        &token_contract_id,
+        "burn-stx",
+        &symbols_from_values(vec![Value::UInt(300)]),
+    )
+    .unwrap();
+
+    assert!(is_committed(&result));
+    assert_eq!(
+        asset_map.to_table()[&p1_principal][&AssetIdentifier::STX_burned()],
+        AssetMapEntry::Burn(300)
+    );
+    match &events[..] {
+        [StacksTransactionEvent::STXEvent(STXEventType::STXBurnEvent(data))] => {
+            assert_eq!(data.sender, p1_principal);
+            assert_eq!(data.amount, 300);
+        }
+        _ => panic!("expected a single burn event, got {:?}", events),
+    }
+    assert_eq!(
+        read(owned_env, "balance-stx", std::slice::from_ref(&p1)),
+        Value::UInt(700)
+    );
+    assert_eq!(
+        read(owned_env, "supply", &[]),
+        Value::UInt(starting_supply - 300)
+    );
+
+    // a burn followed by an abort leaves the balance and supply untouched
+    let (result, asset_map, events) = execute_transaction(
+        owned_env,
+        p1_principal.clone(),
+        &token_contract_id,
+        "burn-then-abort",
+        &symbols_from_values(vec![Value::UInt(100)]),
+    )
+    .unwrap();
+
+    assert!(is_err_code(&result, 9));
+    assert_eq!(asset_map.to_table().len(), 0);
+    assert!(events.is_empty());
+    assert_eq!(
+        read(owned_env, "balance-stx", std::slice::from_ref(&p1)),
+        Value::UInt(700)
+    );
+    assert_eq!(
+        read(owned_env, "supply", &[]),
+        Value::UInt(starting_supply - 300)
+    );
+
+    // burning more than the balance fails with NOT_ENOUGH_BALANCE
+    let (result, asset_map, _events) = execute_transaction(
+        owned_env,
+        p1_principal.clone(),
+        &token_contract_id,
+        "burn-stx",
+        &symbols_from_values(vec![Value::UInt(701)]),
+    )
+    .unwrap();
+
+    assert!(is_err_code(&result, 1));
+    assert_eq!(asset_map.to_table().len(), 0);
+    assert_eq!(
+        read(owned_env, "balance-stx", std::slice::from_ref(&p1)),
+        Value::UInt(700)
+    );
+}
+
+fn test_stx_account(owned_env: &mut OwnedEnvironment) {
+    let contract = r#"(define-read-only (account (p principal)) (stx-account p))
+                    (define-read-only (balance-stx (p principal)) (stx-get-balance p))
+                    (define-public (xfer-stx (amount uint) (t principal)) (stx-transfer? amount tx-sender t))"#;
+
+    let p1 = execute("'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR");
+    let p2 = execute("'SM2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQVX8X0G");
+    let p3 = execute("'SP3X6QWWETNBZWGBK6DRGTR1KX50S74D3433WDGJY");
+    let (p1_principal, p2_principal) = match (&p1, &p2) {
+        (Value::Principal(p1), Value::Principal(p2)) => (p1.clone(), p2.clone()),
+        _ => panic!(),
+    };
+    let contract_id = QualifiedContractIdentifier::local("accounts").unwrap();
+
+    owned_env
+        .initialize_versioned_contract(
+            contract_id.clone(),
+            ClarityVersion::Clarity2,
+            contract,
+            None,
+            ASTRules::PrecheckSize,
+        )
+        .unwrap();
+    owned_env.stx_faucet(&p1_principal, 1000);
+    owned_env.stx_faucet(&p2_principal, 500);
+
+    // lock part of p1's balance, as a stacking lockup would
+    owned_env
+        .execute_in_env::<_, _, Error>(p1_principal.clone(), None, None, |env| {
+            let mut snapshot = env
+                .global_context
+                .database
+                .get_stx_balance_snapshot(&p1_principal);
+            snapshot.lock_tokens_v2(600, 100);
+            snapshot.save();
+            Ok(())
+        })
+        .unwrap();
+
+    let call = |owned_env: &mut OwnedEnvironment, function: &str, args: Vec<Value>| {
+        execute_transaction(
+            owned_env,
+            p1_principal.clone(),
+            &contract_id,
+            function,
+            &symbols_from_values(args),
+        )
+        .unwrap()
+        .0
+    };
+    let account = |unlocked: u128, locked: u128, unlock_height: u128| {
+        Value::from(
+            TupleData::from_data(vec![
+                ("unlocked".into(), Value::UInt(unlocked)),
+                ("locked".into(), Value::UInt(locked)),
+                ("unlock-height".into(), Value::UInt(unlock_height)),
+            ])
+            .unwrap(),
+        )
+    };
+
+    assert_eq!(
+        call(owned_env, "account", vec![p1.clone()]),
+        account(400, 600, 100)
+    );
+    assert_eq!(
+        call(owned_env, "balance-stx", vec![p1.clone()]),
+        Value::UInt(400)
+    );
+
+    // plain and unfunded accounts report nothing locked
+    assert_eq!(
+        call(owned_env, "account", vec![p2.clone()]),
+        account(500, 0, 0)
+    );
+    assert_eq!(
+        call(owned_env, "account", vec![p3.clone()]),
+        account(0, 0, 0)
+    );
+
+    // locked funds cannot be spent
+    assert!(is_err_code(
+        &call(owned_env, "xfer-stx", vec![Value::UInt(401), p2.clone()]),
+        1
+    ));
+    assert!(is_committed(&call(
+        owned_env,
+        "xfer-stx",
+        vec![Value::UInt(400), p2.clone()]
+    )));
+    assert_eq!(call(owned_env, "account", vec![p1]), account(0, 600, 100));
+    assert_eq!(call(owned_env, "account", vec![p2]), account(900, 0, 0));
+}
+
 fn test_simple_token_system(owned_env: &mut OwnedEnvironment) {
     let tokens_contract = FIRST_CLASS_TOKENS;
 
@@ -1373,6 +1582,244 @@ fn test_simple_naming_system(owned_env: &mut OwnedEnvironment) {
     }
 }
 
+fn test_net_asset_map(owned_env: &mut OwnedEnvironment) {
+    let contract = r#"(define-fungible-token coins)
+                    (define-non-fungible-token widgets uint)
+                    (define-public (round-trip (amount uint))
+                      (let ((sender tx-sender) (vault (as-contract tx-sender)))
+                        (try! (stx-transfer? amount sender vault))
+                        (try! (ft-transfer? coins amount sender vault))
+                        (try! (nft-transfer? widgets u1 sender vault))
+                        (try! (as-contract (stx-transfer? amount tx-sender sender)))
+                        (try! (as-contract (ft-transfer? coins amount tx-sender sender)))
+                        (as-contract (nft-transfer? widgets u1 tx-sender sender))))
+                    (define-public (send (amount uint) (to principal))
+                      (begin (try! (stx-transfer? amount tx-sender to))
+                             (try! (ft-transfer? coins amount tx-sender to))
+                             (nft-transfer? widgets u1 tx-sender to)))
+                    (define-public (send-then-abort (amount uint) (to principal))
+                      (begin (try! (send amount to)) (err u9)))
+                    (define-public (mint-and-burn (amount uint))
+                      (begin (try! (ft-mint? coins amount tx-sender))
+                             (try! (nft-burn? widgets u1 tx-sender))
+                             (stx-burn? amount tx-sender)))
+                    (ft-mint? coins u1000 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR)
+                    (nft-mint? widgets u1 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR)"#;
+
+    let p1 = execute("'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR");
+    let p2 = execute("'SM2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQVX8X0G");
+    let (p1_principal, p2_principal) = match (&p1, &p2) {
+        (Value::Principal(p1), Value::Principal(p2)) => (p1.clone(), p2.clone()),
+        _ => panic!(),
+    };
+    let contract_id = QualifiedContractIdentifier::local("net").unwrap();
+    let coins = AssetIdentifier {
+        contract_identifier: contract_id.clone(),
+        asset_name: "coins".into(),
+    };
+    let widgets = AssetIdentifier {
+        contract_identifier: contract_id.clone(),
+        asset_name: "widgets".into(),
+    };
+
+    owned_env
+        .initialize_contract(contract_id.clone(), contract, None, ASTRules::PrecheckSize)
+        .unwrap();
+    owned_env.stx_faucet(&p1_principal, 1000);
+
+    // offsetting transfers net to zero, although each leg is still recorded as spent
+    let (result, asset_map, _events) = execute_transaction(
+        owned_env,
+        p1_principal.clone(),
+        &contract_id,
+        "round-trip",
+        &symbols_from_values(vec![Value::UInt(100)]),
+    )
+    .unwrap();
+
+    assert!(is_committed(&result));
+    assert!(asset_map.is_net_zero());
+    assert!(asset_map
+        .get_net_amount(&p1_principal, &AssetIdentifier::STX())
+        .is_zero());
+    assert!(asset_map.get_net_assets(&p1_principal, &widgets).is_empty());
+    assert_eq!(asset_map.get_stx(&p1_principal), Some(100));
+
+    // a single transfer debits the sender and credits the recipient
+    let (result, asset_map, _events) = execute_transaction(
+        owned_env,
+        p1_principal.clone(),
+        &contract_id,
+        "send",
+        &symbols_from_values(vec![Value::UInt(30), p2.clone()]),
+    )
+    .unwrap();
+
+    assert!(is_committed(&result));
+    assert!(!asset_map.is_net_zero());
+    for asset in [AssetIdentifier::STX(), coins.clone()].iter() {
+        assert_eq!(
+            asset_map.get_net_amount(&p1_principal, asset),
+            NetAmount {
+                credited: 0,
+                debited: 30
+            }
+        );
+        assert_eq!(
+            asset_map.get_net_amount(&p2_principal, asset),
+            NetAmount {
+                credited: 30,
+                debited: 0
+            }
+        );
+    }
+    assert_eq!(
+        asset_map.get_net_assets(&p1_principal, &widgets),
+        NetAssets {
+            received: HashSet::new(),
+            sent: HashSet::from([Value::UInt(1)])
+        }
+    );
+    assert_eq!(
+        asset_map.get_net_assets(&p2_principal, &widgets),
+        NetAssets {
+            received: HashSet::from([Value::UInt(1)]),
+            sent: HashSet::new()
+        }
+    );
+
+    // the net view rolls back with the transaction
+    let (result, asset_map, _events) = execute_transaction(
+        owned_env,
+        p2_principal.clone(),
+        &contract_id,
+        "send-then-abort",
+        &symbols_from_values(vec![Value::UInt(30), p1.clone()]),
+    )
+    .unwrap();
+
+    assert!(is_err_code(&result, 9));
+    assert!(asset_map.is_net_zero());
+
+    // mints are credits without a matching debit, and burns debits without a credit
+    let (result, asset_map, _events) = execute_transaction(
+        owned_env,
+        p2_principal.clone(),
+        &contract_id,
+        "mint-and-burn",
+        &symbols_from_values(vec![Value::UInt(10)]),
+    )
+    .unwrap();
+
+    assert!(is_committed(&result));
+    assert_eq!(
+        asset_map.get_net_amount(&p2_principal, &coins),
+        NetAmount {
+            credited: 10,
+            debited: 0
+        }
+    );
+    assert_eq!(
+        asset_map.get_net_amount(&p2_principal, &AssetIdentifier::STX()),
+        NetAmount {
+            credited: 0,
+            debited: 10
+        }
+    );
+    assert_eq!(
+        asset_map.get_net_assets(&p2_principal, &widgets),
+        NetAssets {
+            received: HashSet::new(),
+            sent: HashSet::from([Value::UInt(1)])
+        }
+    );
+}
+
+#[test]
+fn test_seeded_stx_balances() {
+    let contract = r#"(define-read-only (balance-stx (p principal)) (stx-get-balance p))
+                    (define-read-only (liquid-supply) stx-liquid-supply)
+                    (define-public (xfer-stx (amount uint) (t principal)) (stx-transfer? amount tx-sender t))"#;
+
+    let p1 = execute("'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR");
+    let p2 = execute("'SM2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQVX8X0G");
+    let (p1_principal, p2_principal) = match (&p1, &p2) {
+        (Value::Principal(p1), Value::Principal(p2)) => (p1.clone(), p2.clone()),
+        _ => panic!(),
+    };
+    let contract_id = QualifiedContractIdentifier::local("ledger").unwrap();
+
+    let mut store = MemoryBackingStore::new();
+    let mut owned_env = OwnedEnvironment::memory_with_balances(
+        &mut store,
+        StacksEpochId::latest(),
+        &[(p1_principal.clone(), 1000), (p2_principal.clone(), 500)],
+    )
+    .unwrap();
+    owned_env
+        .initialize_contract(contract_id.clone(), contract, None, ASTRules::PrecheckSize)
+        .unwrap();
+
+    let call = |owned_env: &mut OwnedEnvironment, function: &str, args: Vec<Value>| {
+        execute_transaction(
+            owned_env,
+            p1_principal.clone(),
+            &contract_id,
+            function,
+            &symbols_from_values(args),
+        )
+        .unwrap()
+    };
+
+    assert_eq!(
+        call(&mut owned_env, "balance-stx", vec![p1.clone()]).0,
+        Value::UInt(1000)
+    );
+    assert_eq!(
+        call(&mut owned_env, "balance-stx", vec![p2.clone()]).0,
+        Value::UInt(500)
+    );
+    assert_eq!(
+        call(&mut owned_env, "liquid-supply", vec![]).0,
+        Value::UInt(1500)
+    );
+
+    let (result, _, events) = call(
+        &mut owned_env,
+        "xfer-stx",
+        vec![Value::UInt(300), p2.clone()],
+    );
+    assert!(is_committed(&result));
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        call(&mut owned_env, "balance-stx", vec![p1]).0,
+        Value::UInt(700)
+    );
+    assert_eq!(
+        call(&mut owned_env, "balance-stx", vec![p2]).0,
+        Value::UInt(800)
+    );
+
+    // an overflowing liquid supply seeds nothing
+    let p3_principal = execute("'SP3X6QWWETNBZWGBK6DRGTR1KX50S74D3433WDGJY").expect_principal();
+    assert!(owned_env
+        .seed_stx_balances(&[(p3_principal.clone(), 1), (p1_principal.clone(), u128::MAX)])
+        .is_err());
+    assert_eq!(
+        call(
+            &mut owned_env,
+            "balance-stx",
+            vec![Value::Principal(p3_principal)]
+        )
+        .0,
+        Value::UInt(0)
+    );
+    assert_eq!(
+        call(&mut owned_env, "liquid-supply", vec![]).0,
+        Value::UInt(1500)
+    );
+}
+
 #[test]
 fn test_all() {
     let to_test = [
@@ -1381,6 +1828,9 @@ fn test_all() {
         test_simple_naming_system,
         test_total_supply,
         test_native_stx_ops,
+        test_stx_burn,
+        test_stx_account,
+        test_net_asset_map,
     ];
     for test in to_test.iter() {
         with_memory_environment(test, StacksEpochId::latest(), true);
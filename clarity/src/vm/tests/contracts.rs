@@ -23,13 +23,17 @@ use rstest::rstest;
 use rstest_reuse::{self, *};
 use stacks_common::types::StacksEpochId;
 
+use crate::vm::analysis::contract_interface_builder::{
+    ContractInterfaceAtomType, ContractInterfaceFunctionAccess,
+};
 use crate::vm::ast;
 use crate::vm::ast::errors::ParseErrors;
 use crate::vm::ast::ASTRules;
-use crate::vm::contexts::{Environment, GlobalContext, OwnedEnvironment};
-use crate::vm::contracts::Contract;
+use crate::vm::clarity::Error as ClarityError;
+use crate::vm::contexts::{DatabaseSnapshot, Environment, GlobalContext, OwnedEnvironment};
+use crate::vm::contracts::{Contract, ContractLimits};
 use crate::vm::costs::ExecutionCost;
-use crate::vm::database::ClarityDatabase;
+use crate::vm::database::{ClarityDatabase, StoreType};
 use crate::vm::errors::{CheckErrors, Error, RuntimeErrorType};
 use crate::vm::execute as vm_execute;
 use crate::vm::representations::SymbolicExpression;
@@ -47,10 +51,13 @@ use stacks_common::util::hash::hex_bytes;
 
 use crate::vm::types::serialization::TypePrefix::Buffer;
 use crate::vm::types::BuffData;
-use crate::vm::ContractContext;
 use crate::vm::Value::Sequence;
+use crate::vm::{eval, ContractContext, LocalContext};
 
-use crate::vm::database::MemoryBackingStore;
+use crate::vm::database::{HeadersDB, MemoryBackingStore};
+use stacks_common::types::chainstate::{BurnchainHeaderHash, StacksAddress, VRFSeed};
+use stacks_common::util::hash::Sha512Trunc256Sum;
+use std::time::Duration;
 
 const FACTORIAL_CONTRACT: &str = "(define-map factorials { id: int } { current: int, index: int })
          (define-private (init-factorial (id int) (factorial int))
@@ -175,6 +182,109 @@ fn test_get_block_info_eval() {
     }
 }
 
+/// Headers DB that derives each block's VRF seed from its index block hash,
+///  the way a chain would, and otherwise defers to the unit-test headers.
+struct SeededHeadersDB;
+
+impl HeadersDB for SeededHeadersDB {
+    fn get_stacks_block_header_hash_for_block(
+        &self,
+        id_bhh: &StacksBlockId,
+    ) -> Option<BlockHeaderHash> {
+        TEST_HEADER_DB.get_stacks_block_header_hash_for_block(id_bhh)
+    }
+    fn get_burn_header_hash_for_block(
+        &self,
+        id_bhh: &StacksBlockId,
+    ) -> Option<BurnchainHeaderHash> {
+        TEST_HEADER_DB.get_burn_header_hash_for_block(id_bhh)
+    }
+    fn get_consensus_hash_for_block(&self, id_bhh: &StacksBlockId) -> Option<ConsensusHash> {
+        TEST_HEADER_DB.get_consensus_hash_for_block(id_bhh)
+    }
+    fn get_vrf_seed_for_block(&self, id_bhh: &StacksBlockId) -> Option<VRFSeed> {
+        Some(VRFSeed(Sha512Trunc256Sum::from_data(id_bhh.as_bytes()).0))
+    }
+    fn get_burn_block_time_for_block(&self, id_bhh: &StacksBlockId) -> Option<u64> {
+        TEST_HEADER_DB.get_burn_block_time_for_block(id_bhh)
+    }
+    fn get_burn_block_height_for_block(&self, id_bhh: &StacksBlockId) -> Option<u32> {
+        TEST_HEADER_DB.get_burn_block_height_for_block(id_bhh)
+    }
+    fn get_miner_address(&self, id_bhh: &StacksBlockId) -> Option<StacksAddress> {
+        TEST_HEADER_DB.get_miner_address(id_bhh)
+    }
+    fn get_burnchain_tokens_spent_for_block(&self, id_bhh: &StacksBlockId) -> Option<u128> {
+        TEST_HEADER_DB.get_burnchain_tokens_spent_for_block(id_bhh)
+    }
+    fn get_burnchain_tokens_spent_for_winning_block(&self, id_bhh: &StacksBlockId) -> Option<u128> {
+        TEST_HEADER_DB.get_burnchain_tokens_spent_for_winning_block(id_bhh)
+    }
+    fn get_tokens_earned_for_block(&self, id_bhh: &StacksBlockId) -> Option<u128> {
+        TEST_HEADER_DB.get_tokens_earned_for_block(id_bhh)
+    }
+}
+
+#[test]
+fn test_vrf_seed_is_deterministic() {
+    let contract = "(define-read-only (seed) (get-block-info? vrf-seed u0))
+        (define-read-only (roll) (sha256 (unwrap-panic (seed))))";
+    let contract_identifier = QualifiedContractIdentifier::local("lottery").unwrap();
+    let expected_seed = Value::some(
+        Value::buff_from(
+            Sha512Trunc256Sum::from_data(StacksBlockId([255; 32]).as_bytes())
+                .as_bytes()
+                .to_vec(),
+        )
+        .unwrap(),
+    )
+    .unwrap();
+
+    let mut rolls = vec![];
+    // two independent databases stand in for two nodes on the same chain
+    for _ in 0..2 {
+        let mut marf = MemoryBackingStore::new();
+        let db = ClarityDatabase::new(&mut marf, &SeededHeadersDB, &TEST_BURN_STATE_DB);
+        let mut owned_env = OwnedEnvironment::new(db, StacksEpochId::latest());
+        owned_env
+            .initialize_contract(
+                contract_identifier.clone(),
+                contract,
+                None,
+                ASTRules::PrecheckSize,
+            )
+            .unwrap();
+
+        let mut placeholder_context = ContractContext::new(
+            QualifiedContractIdentifier::transient(),
+            ClarityVersion::Clarity2,
+        );
+        let mut env = owned_env.get_exec_environment(None, None, &mut placeholder_context);
+        for _ in 0..2 {
+            assert_eq!(
+                env.eval_read_only(&contract_identifier, "(seed)").unwrap(),
+                expected_seed
+            );
+        }
+        rolls.push(env.eval_read_only(&contract_identifier, "(roll)").unwrap());
+    }
+    assert_eq!(rolls[0], rolls[1]);
+
+    // blocks at or past the current height have no seed yet
+    let mut marf = MemoryBackingStore::new();
+    let db = ClarityDatabase::new(&mut marf, &SeededHeadersDB, &TEST_BURN_STATE_DB);
+    let mut owned_env = OwnedEnvironment::new(db, StacksEpochId::latest());
+    let mut placeholder_context = ContractContext::new(
+        QualifiedContractIdentifier::transient(),
+        ClarityVersion::Clarity2,
+    );
+    let mut env = owned_env.get_exec_environment(None, None, &mut placeholder_context);
+    assert_eq!(
+        env.eval_raw("(get-block-info? vrf-seed u1)").unwrap(),
+        Value::none()
+    );
+}
+
 fn test_block_headers(n: u8) -> StacksBlockId {
     StacksBlockId([n as u8; 32])
 }
@@ -265,6 +375,784 @@ fn test_contract_caller(owned_env: &mut OwnedEnvironment) {
     }
 }
 
+#[test]
+fn test_environment_reports_executing_contract() {
+    let contract_a = "(define-read-only (where) (whereami))";
+    let contract_b = "(define-read-only (where) (whereami))
+         (define-read-only (cc-where) (contract-call? .contract-a where))
+         (define-read-only (as-contract-cc-where) (as-contract (contract-call? .contract-a where)))";
+
+    let mut marf = MemoryBackingStore::new();
+    let mut owned_env = OwnedEnvironment::new(marf.as_clarity_db(), StacksEpochId::latest());
+    owned_env.begin();
+    owned_env
+        .register_host_function("whereami", ExecutionCost::zero(), |_args, env| {
+            let principals = vec![
+                Value::from(PrincipalData::Contract(env.contract_identifier().clone())),
+                Value::from(env.caller().cloned().unwrap()),
+                Value::from(env.sender().cloned().unwrap()),
+            ];
+            Value::list_from(principals)
+        })
+        .unwrap();
+
+    let p1 = execute("'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR");
+    let c_a = QualifiedContractIdentifier::local("contract-a").unwrap();
+    let c_b = QualifiedContractIdentifier::local("contract-b").unwrap();
+    let mut placeholder_context = ContractContext::new(
+        QualifiedContractIdentifier::transient(),
+        ClarityVersion::Clarity2,
+    );
+
+    {
+        let mut env = owned_env.get_exec_environment(None, None, &mut placeholder_context);
+        env.initialize_contract(c_a.clone(), contract_a, ASTRules::PrecheckSize)
+            .unwrap();
+        env.initialize_contract(c_b.clone(), contract_b, ASTRules::PrecheckSize)
+            .unwrap();
+    }
+
+    let mut env = owned_env.get_exec_environment(
+        Some(p1.clone().expect_principal()),
+        None,
+        &mut placeholder_context,
+    );
+    assert_eq!(
+        env.contract_identifier(),
+        &QualifiedContractIdentifier::transient()
+    );
+    assert_eq!(env.sender(), Some(&p1.clone().expect_principal()));
+
+    let a = Value::from(PrincipalData::Contract(c_a));
+    let b = Value::from(PrincipalData::Contract(c_b.clone()));
+    let expected = [
+        ("where", vec![b.clone(), p1.clone(), p1.clone()]),
+        ("cc-where", vec![a.clone(), b.clone(), p1.clone()]),
+        ("as-contract-cc-where", vec![a, b.clone(), b]),
+    ];
+    for (function, principals) in expected {
+        assert_eq!(
+            env.execute_contract(&c_b, function, &[], false).unwrap(),
+            Value::list_from(principals).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_deploy_many_in_dependency_order() {
+    let contract_a = "(define-trait getter ((get-value () (response int int))))
+         (define-read-only (value) 7)";
+    let contract_b = "(use-trait getter .contract-a.getter)
+         (define-read-only (value-plus-one) (+ (contract-call? .contract-a value) 1))";
+    let contract_c =
+        "(define-read-only (value-plus-two) (+ (contract-call? .contract-b value-plus-one) 1))";
+
+    with_memory_environment(
+        |owned_env| {
+            let p1 = execute("'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR").expect_principal();
+            let c_a = QualifiedContractIdentifier::local("contract-a").unwrap();
+            let c_b = QualifiedContractIdentifier::local("contract-b").unwrap();
+            let c_c = QualifiedContractIdentifier::local("contract-c").unwrap();
+
+            let analyses = owned_env
+                .deploy_many(
+                    &[
+                        (c_c.clone(), contract_c),
+                        (c_b.clone(), contract_b),
+                        (c_a.clone(), contract_a),
+                    ],
+                    ClarityVersion::Clarity2,
+                    ASTRules::PrecheckSize,
+                )
+                .unwrap();
+            let deployed: Vec<_> = analyses
+                .iter()
+                .map(|analysis| analysis.contract_identifier.clone())
+                .collect();
+            assert_eq!(deployed, vec![c_a, c_b, c_c.clone()]);
+
+            let (result, _, _) = owned_env
+                .execute_transaction(p1, None, c_c, "value-plus-two", &[])
+                .unwrap();
+            assert_eq!(result, Value::Int(9));
+        },
+        StacksEpochId::latest(),
+        true,
+    );
+
+    let contract_x = "(define-read-only (x) (contract-call? .contract-y y))";
+    let contract_y = "(define-read-only (y) (contract-call? .contract-x x))";
+
+    with_memory_environment(
+        |owned_env| {
+            let c_x = QualifiedContractIdentifier::local("contract-x").unwrap();
+            let c_y = QualifiedContractIdentifier::local("contract-y").unwrap();
+
+            let err = owned_env
+                .deploy_many(
+                    &[(c_x.clone(), contract_x), (c_y.clone(), contract_y)],
+                    ClarityVersion::Clarity2,
+                    ASTRules::PrecheckSize,
+                )
+                .unwrap_err();
+            match err {
+                ClarityError::Analysis(check_error) => assert_eq!(
+                    check_error.err,
+                    CheckErrors::CircularReference(vec![c_x.to_string(), c_y.to_string()])
+                ),
+                e => panic!("Unexpected error: {}", e),
+            }
+        },
+        StacksEpochId::latest(),
+        true,
+    );
+}
+
+#[test]
+fn test_read_data_var_of_other_contract() {
+    let contract_a = "(define-data-var counter int 41)
+         (define-public (increment) (ok (var-set counter (+ (var-get counter) 1))))
+         (define-read-only (get-counter) (var-get counter))";
+    let contract_b = "(define-read-only (counter-of-a) (contract-call? .contract-a get-counter))";
+    let contract_c = "(define-read-only (bump-a) (contract-call? .contract-a increment))";
+
+    with_memory_environment(
+        |owned_env| {
+            let p1 = execute("'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR").expect_principal();
+            let c_a = QualifiedContractIdentifier::local("contract-a").unwrap();
+            let c_b = QualifiedContractIdentifier::local("contract-b").unwrap();
+            let c_c = QualifiedContractIdentifier::local("contract-c").unwrap();
+
+            owned_env
+                .deploy_many(
+                    &[(c_a.clone(), contract_a), (c_b.clone(), contract_b)],
+                    ClarityVersion::Clarity2,
+                    ASTRules::PrecheckSize,
+                )
+                .unwrap();
+            owned_env
+                .execute_transaction(p1.clone(), None, c_a.clone(), "increment", &[])
+                .unwrap();
+
+            let (result, _, _) = owned_env
+                .execute_transaction(p1.clone(), None, c_b, "counter-of-a", &[])
+                .unwrap();
+            assert_eq!(result, Value::Int(42));
+
+            // a read-only function cannot reach a writing function of another contract
+            let err = owned_env
+                .deploy_many(
+                    &[(c_c, contract_c)],
+                    ClarityVersion::Clarity2,
+                    ASTRules::PrecheckSize,
+                )
+                .unwrap_err();
+            match err {
+                ClarityError::Analysis(check_error) => {
+                    assert_eq!(check_error.err, CheckErrors::WriteAttemptedInReadOnly)
+                }
+                e => panic!("Unexpected error: {}", e),
+            }
+
+            // embedders can read the var directly
+            let (value, _, _) = owned_env
+                .execute_in_env::<_, _, Error>(p1.clone(), None, None, |env| {
+                    env.lookup_variable_in_contract(&c_a, "counter")
+                })
+                .unwrap();
+            assert_eq!(value, Value::Int(42));
+
+            let err = owned_env
+                .execute_in_env::<_, _, Error>(p1.clone(), None, None, |env| {
+                    env.lookup_variable_in_contract(&c_a, "missing")
+                })
+                .unwrap_err();
+            assert_eq!(err, CheckErrors::UndefinedVariable("missing".into()).into());
+
+            let c_missing = QualifiedContractIdentifier::local("contract-missing").unwrap();
+            let err = owned_env
+                .execute_in_env::<_, _, Error>(p1, None, None, |env| {
+                    env.lookup_variable_in_contract(&c_missing, "counter")
+                })
+                .unwrap_err();
+            assert_eq!(
+                err,
+                CheckErrors::NoSuchContract(c_missing.to_string()).into()
+            );
+        },
+        StacksEpochId::latest(),
+        true,
+    );
+}
+
+#[test]
+fn test_max_events_limit() {
+    let contract_a = "(define-public (emit-and-fail)
+           (begin (print u1) (print u2) (print u3) (print u4) (err u1)))";
+    let contract_b = "(define-private (emit (item uint) (count uint))
+           (begin (print item) (+ count u1)))
+         (define-public (emit-few) (ok (fold emit (list u1 u2 u3) u0)))
+         (define-public (emit-many) (ok (fold emit (list u1 u2 u3 u4 u5 u6 u7 u8 u9 u10) u0)))
+         (define-public (retry-then-emit)
+           (begin
+             (unwrap-err-panic (contract-call? .contract-a emit-and-fail))
+             (unwrap-err-panic (contract-call? .contract-a emit-and-fail))
+             (emit-few)))";
+
+    with_memory_environment(
+        |owned_env| {
+            let p1 = execute("'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR").expect_principal();
+            let c_a = QualifiedContractIdentifier::local("contract-a").unwrap();
+            let c_b = QualifiedContractIdentifier::local("contract-b").unwrap();
+            owned_env
+                .initialize_contract(c_a, contract_a, None, ASTRules::PrecheckSize)
+                .unwrap();
+            owned_env
+                .initialize_contract(c_b.clone(), contract_b, None, ASTRules::PrecheckSize)
+                .unwrap();
+
+            owned_env.set_max_events(Some(5));
+
+            let (result, _, events) = owned_env
+                .execute_transaction(p1.clone(), None, c_b.clone(), "emit-few", &[])
+                .unwrap();
+            assert_eq!(result, Value::okay(Value::UInt(3)).unwrap());
+            assert_eq!(events.len(), 3);
+
+            let err = owned_env
+                .execute_transaction(p1.clone(), None, c_b.clone(), "emit-many", &[])
+                .unwrap_err();
+            assert!(matches!(
+                err,
+                Error::Runtime(RuntimeErrorType::EventBalanceExceeded, _)
+            ));
+
+            // the events of the rolled back contract-calls do not count against the limit
+            let (result, _, events) = owned_env
+                .execute_transaction(p1, None, c_b, "retry-then-emit", &[])
+                .unwrap();
+            assert_eq!(result, Value::okay(Value::UInt(3)).unwrap());
+            assert_eq!(events.len(), 3);
+        },
+        StacksEpochId::latest(),
+        true,
+    );
+}
+
+#[test]
+fn test_contract_limits() {
+    let limits = ContractLimits {
+        max_source_bytes: 1024,
+        max_definitions: 3,
+        max_ast_nodes: 40,
+    };
+    let definitions = |count: usize| {
+        (0..count)
+            .map(|i| format!("(define-constant c{} {})", i, i))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    with_memory_environment(
+        |owned_env| {
+            owned_env.set_contract_limits(Some(limits.clone()));
+            let contract = |name: &str| QualifiedContractIdentifier::local(name).unwrap();
+
+            owned_env
+                .initialize_contract(
+                    contract("just-under"),
+                    &definitions(3),
+                    None,
+                    ASTRules::PrecheckSize,
+                )
+                .unwrap();
+
+            let err = owned_env
+                .initialize_contract(
+                    contract("too-many"),
+                    &definitions(4),
+                    None,
+                    ASTRules::PrecheckSize,
+                )
+                .unwrap_err();
+            assert_eq!(err, CheckErrors::TooManyDefinitions(4, 3).into());
+
+            // definitions are only counted at the top level
+            let nested = "(define-private (f) (let ((a 1) (b 2) (c 3) (d 4)) (+ a b c d)))";
+            owned_env
+                .initialize_contract(contract("nested"), nested, None, ASTRules::PrecheckSize)
+                .unwrap();
+
+            let big = format!("(define-constant l (list {}))", "1 ".repeat(40));
+            let err = owned_env
+                .initialize_contract(contract("big"), &big, None, ASTRules::PrecheckSize)
+                .unwrap_err();
+            assert_eq!(err, CheckErrors::TooManyASTNodes(45, 40).into());
+
+            let long = format!("{};; {}", definitions(1), "-".repeat(1024));
+            let err = owned_env
+                .initialize_contract(contract("long"), &long, None, ASTRules::PrecheckSize)
+                .unwrap_err();
+            assert_eq!(
+                err,
+                CheckErrors::ContractSourceTooLarge(long.len(), 1024).into()
+            );
+
+            // `deploy_many` checks every contract before analyzing any of them
+            let err = owned_env
+                .deploy_many(
+                    &[
+                        (contract("first"), definitions(1).as_str()),
+                        (contract("second"), definitions(4).as_str()),
+                    ],
+                    ClarityVersion::Clarity2,
+                    ASTRules::PrecheckSize,
+                )
+                .unwrap_err();
+            match err {
+                ClarityError::Analysis(check_error) => {
+                    assert_eq!(check_error.err, CheckErrors::TooManyDefinitions(4, 3))
+                }
+                e => panic!("Unexpected error: {}", e),
+            }
+            assert!(owned_env
+                .initialize_contract(
+                    contract("first"),
+                    &definitions(1),
+                    None,
+                    ASTRules::PrecheckSize
+                )
+                .is_ok());
+
+            // without limits, deployment is only bounded by costs
+            owned_env.set_contract_limits(None);
+            owned_env
+                .initialize_contract(contract("big"), &big, None, ASTRules::PrecheckSize)
+                .unwrap();
+        },
+        StacksEpochId::latest(),
+        true,
+    );
+
+    assert!(ContractLimits::default()
+        .check_source(include_str!(
+            "../../../../src/chainstate/stacks/boot/pox-2.clar"
+        ))
+        .is_ok());
+}
+
+#[test]
+fn test_execution_timeout() {
+    let items: Vec<String> = (0..300).map(|i| i.to_string()).collect();
+    let contract = format!(
+        "(define-constant items (list {}))
+         (define-private (inner (item int) (acc int)) (+ acc 1))
+         (define-private (outer (item int) (acc int)) (fold inner items acc))
+         (define-public (spin) (ok (fold outer items 0)))",
+        items.join(" ")
+    );
+
+    with_memory_environment(
+        |owned_env| {
+            let p1 = execute("'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR").expect_principal();
+            let c = QualifiedContractIdentifier::local("spinner").unwrap();
+            owned_env
+                .initialize_contract(c.clone(), &contract, None, ASTRules::PrecheckSize)
+                .unwrap();
+
+            owned_env.set_execution_timeout(Some(Duration::from_millis(1)));
+            let err = owned_env
+                .execute_transaction(p1.clone(), None, c.clone(), "spin", &[])
+                .unwrap_err();
+            assert!(matches!(
+                err,
+                Error::Runtime(RuntimeErrorType::ExecutionTimeout, _)
+            ));
+
+            // the budget applies to each execution, not to the environment's lifetime
+            owned_env.set_execution_timeout(Some(Duration::from_secs(600)));
+            let (result, _, _) = owned_env
+                .execute_transaction(p1.clone(), None, c.clone(), "spin", &[])
+                .unwrap();
+            assert_eq!(result, Value::okay(Value::Int(90_000)).unwrap());
+
+            owned_env.set_execution_timeout(None);
+            let (result, _, _) = owned_env
+                .execute_transaction(p1, None, c, "spin", &[])
+                .unwrap();
+            assert_eq!(result, Value::okay(Value::Int(90_000)).unwrap());
+        },
+        StacksEpochId::latest(),
+        true,
+    );
+}
+
+#[test]
+fn test_contract_context_canonical_string() {
+    let definitions = [
+        "(define-constant owner tx-sender)",
+        "(define-constant limit u10)",
+        "(define-data-var counter uint u0)",
+        "(define-map balances principal uint)",
+        "(define-fungible-token points u100)",
+        "(define-fungible-token credits)",
+        "(define-non-fungible-token badge (buff 8))",
+        "(define-trait bumper ((bump (uint) (response bool uint))))",
+        "(define-private (bump (by uint)) (var-set counter (+ (var-get counter) by)))",
+        "(define-public (deposit (who principal) (amount uint)) (ok (map-set balances who amount)))",
+        "(define-read-only (get-counter) (var-get counter))",
+    ];
+
+    let canonical = |definitions: &[&str], with_bodies: bool| {
+        let mut canonical = String::new();
+        with_memory_environment(
+            |owned_env| {
+                let c = QualifiedContractIdentifier::local("snapshot").unwrap();
+                owned_env
+                    .initialize_contract(
+                        c.clone(),
+                        &definitions.join("\n"),
+                        None,
+                        ASTRules::PrecheckSize,
+                    )
+                    .unwrap();
+
+                let mut placeholder_context = ContractContext::new(
+                    QualifiedContractIdentifier::transient(),
+                    ClarityVersion::Clarity2,
+                );
+                let env = owned_env.get_exec_environment(None, None, &mut placeholder_context);
+                let db = &mut env.global_context.database;
+                db.begin();
+                let contract_context = db.get_contract(&c).unwrap().contract_context;
+                canonical = contract_context.to_canonical_string(with_bodies);
+                if !with_bodies {
+                    assert_eq!(canonical, contract_context.to_string());
+                }
+                db.roll_back();
+            },
+            StacksEpochId::Epoch21,
+            true,
+        );
+        canonical
+    };
+
+    let expected = "(contract S1G2081040G2081040G2081040G208105NK8PE5.snapshot \"Clarity 1\")
+(define-constant limit u10)
+(define-constant owner S1G2081040G2081040G2081040G208105NK8PE5)
+(define-data-var counter uint)
+(define-map balances principal uint)
+(define-fungible-token credits)
+(define-fungible-token points u100)
+(define-non-fungible-token badge (buff 8))
+(define-trait bumper ((bump (uint) (response bool uint))))
+(define-private (bump (by uint)))
+(define-public (deposit (who principal) (amount uint)))
+(define-read-only (get-counter))
+";
+    // the listing is independent of definition (and so HashMap insertion) order
+    let mut reversed_definitions = definitions;
+    reversed_definitions.reverse();
+    assert_eq!(expected, canonical(&definitions, false));
+    assert_eq!(expected, canonical(&reversed_definitions, false));
+
+    // bodies are only listed on request
+    let with_bodies = canonical(&definitions, true);
+    assert!(with_bodies.contains("(define-read-only (get-counter) ( var-get counter ))\n"));
+    assert_eq!(with_bodies, canonical(&reversed_definitions, true));
+}
+
+#[test]
+fn test_contract_context_interface() {
+    let contract = "(define-constant owner tx-sender)
+         (define-data-var counter uint u0)
+         (define-map balances principal uint)
+         (define-fungible-token points)
+         (define-non-fungible-token badge (buff 8))
+         (define-private (bump (by uint)) (var-set counter (+ (var-get counter) by)))
+         (define-public (deposit (who principal) (amount uint))
+             (ok (map-set balances who amount)))
+         (define-public (tick) (ok (bump u1)))
+         (define-read-only (get-counter) (var-get counter))";
+
+    with_memory_environment(
+        |owned_env| {
+            let c = QualifiedContractIdentifier::local("abi").unwrap();
+            owned_env
+                .initialize_contract(c.clone(), contract, None, ASTRules::PrecheckSize)
+                .unwrap();
+
+            let mut placeholder_context = ContractContext::new(
+                QualifiedContractIdentifier::transient(),
+                ClarityVersion::Clarity2,
+            );
+            let env = owned_env.get_exec_environment(None, None, &mut placeholder_context);
+            let db = &mut env.global_context.database;
+            db.begin();
+            let interface = db
+                .get_contract(&c)
+                .unwrap()
+                .contract_context
+                .get_interface(StacksEpochId::latest());
+            db.roll_back();
+
+            let functions: Vec<_> = interface
+                .functions
+                .iter()
+                .map(|f| (f.name.as_str(), &f.access, f.args.len()))
+                .collect();
+            assert_eq!(
+                functions,
+                vec![
+                    ("deposit", &ContractInterfaceFunctionAccess::public, 2),
+                    ("tick", &ContractInterfaceFunctionAccess::public, 0),
+                    (
+                        "get-counter",
+                        &ContractInterfaceFunctionAccess::read_only,
+                        0
+                    ),
+                ]
+            );
+            let deposit = &interface.functions[0];
+            assert_eq!(deposit.args[0].name, "who");
+            assert_eq!(deposit.args[0].type_f, ContractInterfaceAtomType::principal);
+            assert_eq!(deposit.args[1].name, "amount");
+            assert_eq!(deposit.args[1].type_f, ContractInterfaceAtomType::uint128);
+
+            let variables: Vec<_> = interface
+                .variables
+                .iter()
+                .map(|v| (v.name.as_str(), &v.type_f))
+                .collect();
+            assert_eq!(
+                variables,
+                vec![
+                    ("owner", &ContractInterfaceAtomType::principal),
+                    ("counter", &ContractInterfaceAtomType::uint128),
+                ]
+            );
+            assert_eq!(interface.maps[0].name, "balances");
+            assert_eq!(interface.maps[0].key, ContractInterfaceAtomType::principal);
+            assert_eq!(interface.fungible_tokens[0].name, "points");
+            assert_eq!(interface.non_fungible_tokens[0].name, "badge");
+            assert_eq!(
+                interface.non_fungible_tokens[0].type_f,
+                ContractInterfaceAtomType::buffer { length: 8 }
+            );
+
+            let json: serde_json::Value = serde_json::from_str(&interface.serialize()).unwrap();
+            assert_eq!(json["functions"][0]["name"], "deposit");
+            assert_eq!(json["functions"][0]["access"], "public");
+            assert_eq!(json["functions"][0]["args"][1]["type"], "uint128");
+            // the runtime definitions carry no result types
+            assert!(json["functions"][0].get("outputs").is_none());
+        },
+        StacksEpochId::latest(),
+        true,
+    );
+}
+
+#[test]
+fn test_contract_context_definition_order() {
+    let definitions = [
+        "(define-public (withdraw (amount uint)) (ok amount))",
+        "(define-read-only (get-total) (var-get total))",
+        "(define-data-var total uint u0)",
+        "(define-constant owner tx-sender)",
+        "(define-public (deposit (amount uint)) (ok (var-set total amount)))",
+        "(define-map ledger principal uint)",
+        "(define-private (helper) true)",
+    ];
+
+    let describe = |contract: String| {
+        let mut described = None;
+        with_memory_environment(
+            |owned_env| {
+                let c = QualifiedContractIdentifier::local("ordered").unwrap();
+                owned_env
+                    .initialize_contract(c.clone(), &contract, None, ASTRules::PrecheckSize)
+                    .unwrap();
+
+                let mut placeholder_context = ContractContext::new(
+                    QualifiedContractIdentifier::transient(),
+                    ClarityVersion::Clarity2,
+                );
+                let env = owned_env.get_exec_environment(None, None, &mut placeholder_context);
+                let db = &mut env.global_context.database;
+                db.begin();
+                let contract_context = db.get_contract(&c).unwrap().contract_context;
+                db.roll_back();
+
+                let names: Vec<_> = contract_context
+                    .functions
+                    .keys()
+                    .chain(contract_context.variables.keys())
+                    .chain(contract_context.meta_data_var.keys())
+                    .chain(contract_context.meta_data_map.keys())
+                    .map(|name| name.to_string())
+                    .collect();
+                let interface = contract_context
+                    .get_interface(StacksEpochId::latest())
+                    .serialize();
+                described = Some((names, interface));
+            },
+            StacksEpochId::latest(),
+            true,
+        );
+        described.unwrap()
+    };
+
+    let forward = describe(definitions.join("\n"));
+    let reversed = describe(
+        definitions
+            .iter()
+            .rev()
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n"),
+    );
+    assert_eq!(
+        forward.0,
+        vec![
+            "deposit",
+            "get-total",
+            "helper",
+            "withdraw",
+            "owner",
+            "total",
+            "ledger"
+        ]
+    );
+    assert_eq!(forward, reversed);
+}
+
+#[test]
+fn test_err_rolls_back_only_the_failing_frame() {
+    let contract_b = "(define-data-var y int 0)
+         (define-public (write-then-fail) (begin (var-set y 2) (err u1)))
+         (define-public (write-then-assert)
+             (begin (var-set y 3) (asserts! false (err u2)) (ok true)))
+         (define-read-only (get-y) (var-get y))";
+    let contract_a = "(define-data-var x int 0)
+         (define-public (call-and-recover)
+             (begin
+               (var-set x 1)
+               (ok (match (contract-call? .contract-b write-then-fail) v v e false))))
+         (define-public (call-and-recover-assert)
+             (begin
+               (var-set x 1)
+               (ok (is-ok (contract-call? .contract-b write-then-assert)))))
+         (define-public (call-and-propagate)
+             (begin
+               (var-set x 1)
+               (try! (contract-call? .contract-b write-then-fail))
+               (ok true)))
+         (define-read-only (get-x) (var-get x))";
+
+    with_memory_environment(
+        |owned_env| {
+            let p1 = execute("'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR").expect_principal();
+            let a = QualifiedContractIdentifier::local("contract-a").unwrap();
+            let b = QualifiedContractIdentifier::local("contract-b").unwrap();
+            owned_env
+                .initialize_contract(b.clone(), contract_b, None, ASTRules::PrecheckSize)
+                .unwrap();
+            owned_env
+                .initialize_contract(a.clone(), contract_a, None, ASTRules::PrecheckSize)
+                .unwrap();
+
+            let call = |owned_env: &mut OwnedEnvironment, contract, name| {
+                owned_env
+                    .execute_transaction(p1.clone(), None, contract, name, &[])
+                    .unwrap()
+                    .0
+            };
+
+            // propagating the error with try! fails the caller's frame too
+            let result = call(owned_env, a.clone(), "call-and-propagate");
+            assert_eq!(result, Value::error(Value::UInt(1)).unwrap());
+            assert_eq!(call(owned_env, a.clone(), "get-x"), Value::Int(0));
+            assert_eq!(call(owned_env, b.clone(), "get-y"), Value::Int(0));
+
+            // recovering from it keeps the caller's write but not the callee's
+            for function in ["call-and-recover", "call-and-recover-assert"] {
+                let result = call(owned_env, a.clone(), function);
+                assert_eq!(result, Value::okay(Value::Bool(false)).unwrap());
+                assert_eq!(call(owned_env, a.clone(), "get-x"), Value::Int(1));
+                assert_eq!(call(owned_env, b.clone(), "get-y"), Value::Int(0));
+            }
+        },
+        StacksEpochId::latest(),
+        true,
+    );
+}
+
+#[test]
+fn test_same_names_isolated_per_contract() {
+    let contract = "(define-data-var cursor int 0)
+         (define-map entries int int)
+         (define-fungible-token points)
+         (define-public (write (value int))
+             (begin
+               (var-set cursor value)
+               (map-set entries 0 value)
+               (ft-mint? points (to-uint value) tx-sender)))
+         (define-read-only (read)
+             (list (var-get cursor)
+                   (default-to 0 (map-get? entries 0))
+                   (to-int (ft-get-supply points))))";
+
+    with_memory_environment(
+        |owned_env| {
+            let p1 = execute("'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR").expect_principal();
+            let a = QualifiedContractIdentifier::local("contract-a").unwrap();
+            let b = QualifiedContractIdentifier::local("contract-b").unwrap();
+            for id in [&a, &b] {
+                owned_env
+                    .initialize_contract(id.clone(), contract, None, ASTRules::PrecheckSize)
+                    .unwrap();
+            }
+
+            let mut call = |contract: &QualifiedContractIdentifier, name, args: Vec<Value>| {
+                owned_env
+                    .execute_transaction(
+                        p1.clone(),
+                        None,
+                        contract.clone(),
+                        name,
+                        &symbols_from_values(args),
+                    )
+                    .unwrap()
+                    .0
+            };
+
+            call(&a, "write", vec![Value::Int(7)]);
+            call(&b, "write", vec![Value::Int(11)]);
+
+            let values = |xs: [i128; 3]| {
+                Value::list_from(xs.iter().map(|x| Value::Int(*x)).collect()).unwrap()
+            };
+            assert_eq!(call(&a, "read", vec![]), values([7, 7, 7]));
+            assert_eq!(call(&b, "read", vec![]), values([11, 11, 11]));
+        },
+        StacksEpochId::latest(),
+        true,
+    );
+
+    assert_ne!(
+        ClarityDatabase::make_key_for_trip(
+            &QualifiedContractIdentifier::local("contract-a").unwrap(),
+            StoreType::Variable,
+            "cursor"
+        ),
+        ClarityDatabase::make_key_for_trip(
+            &QualifiedContractIdentifier::local("contract-b").unwrap(),
+            StoreType::Variable,
+            "cursor"
+        )
+    );
+}
+
 fn tx_sponsor_contract_asserts(env: &mut Environment, sponsor: Option<PrincipalData>) {
     let sponsor = match sponsor {
         None => Value::none(),
@@ -1080,6 +1968,36 @@ fn test_arg_stack_depth() {
     );
 }
 
+#[test]
+fn test_eval_depth_is_bounded_without_parser() {
+    // the parser rejects deep nesting, but eval itself must also stop at
+    //  MAX_CALL_STACK_DEPTH rather than recursing until the native stack overflows
+    let mut expr = SymbolicExpression::atom_value(Value::Int(1));
+    for _ in 0..10_000 {
+        expr = SymbolicExpression::list(Box::new([
+            SymbolicExpression::atom("+".into()),
+            expr,
+            SymbolicExpression::atom_value(Value::Int(1)),
+        ]));
+    }
+
+    with_memory_environment(
+        |owned_env| {
+            let mut placeholder_context = ContractContext::new(
+                QualifiedContractIdentifier::transient(),
+                ClarityVersion::Clarity2,
+            );
+            let mut env = owned_env.get_exec_environment(None, None, &mut placeholder_context);
+            assert_eq!(
+                eval(&expr, &mut env, &LocalContext::new()).unwrap_err(),
+                RuntimeErrorType::MaxStackDepthReached.into()
+            );
+        },
+        StacksEpochId::latest(),
+        false,
+    );
+}
+
 #[test]
 fn test_cc_stack_depth() {
     let contract_one = "(define-public (foo) 
@@ -1164,6 +2082,127 @@ fn test_cc_trait_stack_depth() {
     );
 }
 
+#[test]
+fn test_snapshot_diff() {
+    let contract = "(define-data-var a int 1)
+        (define-data-var b (string-ascii 8) \"old\")
+        (define-data-var c uint u0)
+        (define-public (set-both) (begin (var-set a 2) (var-set b \"new\") (ok true)))";
+
+    with_memory_environment(
+        |owned_env| {
+            let p1: PrincipalData = StandardPrincipalData::transient().into();
+            let c = QualifiedContractIdentifier::local("vars").unwrap();
+            owned_env
+                .initialize_contract(c.clone(), contract, None, ASTRules::PrecheckSize)
+                .unwrap();
+            let var_key =
+                |name: &str| ClarityDatabase::make_key_for_trip(&c, StoreType::Variable, name);
+            let ascii = |s: &str| Value::string_ascii_from_bytes(s.as_bytes().to_vec()).unwrap();
+            let diff = |owned_env: &mut OwnedEnvironment, snapshot: &DatabaseSnapshot| {
+                owned_env
+                    .diff_since(snapshot)
+                    .into_iter()
+                    .map(|(key, old, new)| {
+                        (
+                            key,
+                            old.map(|old| Value::try_deserialize_hex_untyped(&old).unwrap()),
+                            Value::try_deserialize_hex_untyped(&new).unwrap(),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            };
+
+            let snapshot = owned_env.snapshot();
+            assert!(diff(owned_env, &snapshot).is_empty());
+
+            owned_env
+                .execute_transaction(p1.clone(), None, c.clone(), "set-both", &[])
+                .unwrap();
+            let expected = vec![
+                (var_key("a"), Some(Value::Int(1)), Value::Int(2)),
+                (var_key("b"), Some(ascii("old")), ascii("new")),
+            ];
+            assert_eq!(diff(owned_env, &snapshot), expected);
+
+            // a nested snapshot only sees its own writes, which are reported even
+            //  if they leave the value unchanged
+            let inner = owned_env.snapshot();
+            owned_env
+                .execute_transaction(p1, None, c.clone(), "set-both", &[])
+                .unwrap();
+            assert_eq!(
+                diff(owned_env, &inner),
+                vec![
+                    (var_key("a"), Some(Value::Int(2)), Value::Int(2)),
+                    (var_key("b"), Some(ascii("new")), ascii("new")),
+                ]
+            );
+            owned_env.rollback_snapshot(inner);
+            assert_eq!(diff(owned_env, &snapshot), expected);
+
+            owned_env.commit_snapshot(snapshot);
+            let after = owned_env.snapshot();
+            assert!(diff(owned_env, &after).is_empty());
+            owned_env.commit_snapshot(after);
+        },
+        StacksEpochId::latest(),
+        true,
+    );
+}
+
+#[test]
+fn test_last_write_set() {
+    let contract = "(define-data-var counter uint u0)
+        (define-map owners uint principal)
+        (define-public (register)
+          (begin (map-set owners (var-get counter) tx-sender)
+                 (var-set counter (+ (var-get counter) u1))
+                 (ok true)))
+        (define-public (noop) (ok true))";
+
+    let run = || {
+        let mut write_set = None;
+        with_memory_environment(
+            |owned_env| {
+                let p1: PrincipalData = StandardPrincipalData::transient().into();
+                let c = QualifiedContractIdentifier::local("registry").unwrap();
+                assert!(owned_env.last_write_set().is_none());
+                owned_env.record_write_sets();
+                owned_env
+                    .initialize_contract(c.clone(), contract, None, ASTRules::PrecheckSize)
+                    .unwrap();
+                owned_env
+                    .execute_transaction(p1.clone(), None, c.clone(), "register", &[])
+                    .unwrap();
+                // a transaction without data writes leaves the last write set in place
+                owned_env
+                    .execute_transaction(p1, None, c.clone(), "noop", &[])
+                    .unwrap();
+                let counter_key =
+                    ClarityDatabase::make_key_for_trip(&c, StoreType::Variable, "counter");
+                let owners_key =
+                    ClarityDatabase::make_key_for_data_map_entry(&c, "owners", &Value::UInt(0));
+                let keys: Vec<_> = owned_env
+                    .last_write_set()
+                    .unwrap()
+                    .iter()
+                    .map(|(key, _)| String::from_utf8(key.clone()).unwrap())
+                    .collect();
+                let mut expected = vec![counter_key, owners_key];
+                expected.sort();
+                assert_eq!(keys, expected);
+                write_set = owned_env.last_write_set().cloned();
+            },
+            StacksEpochId::latest(),
+            true,
+        );
+        write_set.unwrap()
+    };
+
+    assert_eq!(run(), run());
+}
+
 #[test]
 fn test_all() {
     let to_test = [
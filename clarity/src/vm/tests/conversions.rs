@@ -15,7 +15,7 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 pub use crate::vm::analysis::errors::{CheckError, CheckErrors};
-use crate::vm::execute_v2;
+use crate::vm::errors::Error;
 use crate::vm::types::SequenceSubtype::{BufferType, StringType};
 use crate::vm::types::StringSubtype::ASCII;
 use crate::vm::types::TypeSignature::SequenceType;
@@ -23,6 +23,7 @@ use crate::vm::types::{
     ASCIIData, BuffData, BufferLength, CharType, SequenceData, TypeSignature, UTF8Data, Value,
 };
 use crate::vm::ClarityVersion;
+use crate::vm::{execute_v2, execute_v3};
 use std::convert::TryFrom;
 
 #[test]
@@ -233,6 +234,49 @@ fn test_simple_buff_to_uint_be() {
     );
 }
 
+#[test]
+fn test_buff_to_int_endianness() {
+    // the same 4 bytes decode differently under each byte order
+    let cases = [
+        ("(buff-to-uint-be 0x01020304)", Value::UInt(0x01020304)),
+        ("(buff-to-uint-le 0x01020304)", Value::UInt(0x04030201)),
+        ("(buff-to-int-be 0x01020304)", Value::Int(0x01020304)),
+        ("(buff-to-int-le 0x01020304)", Value::Int(0x04030201)),
+        // short buffers are zero-extended to 16 bytes before the signed
+        //  (two's complement) interpretation, so they never decode as negative
+        ("(buff-to-int-be 0xfffffffe)", Value::Int(0xfffffffe)),
+        ("(buff-to-int-le 0xfeffffff)", Value::Int(0xfffffffe)),
+        (
+            "(buff-to-int-be 0xfffffffffffffffffffffffffffffffe)",
+            Value::Int(-2),
+        ),
+        (
+            "(buff-to-int-le 0xfeffffffffffffffffffffffffffffff)",
+            Value::Int(-2),
+        ),
+        (
+            "(buff-to-uint-be 0xfffffffffffffffffffffffffffffffe)",
+            Value::UInt(u128::MAX - 1),
+        ),
+    ];
+    for (test, expected) in cases.iter() {
+        assert_eq!(expected, &execute_v2(test).unwrap().unwrap(), "{}", test);
+    }
+
+    for function in [
+        "buff-to-uint-be",
+        "buff-to-uint-le",
+        "buff-to-int-be",
+        "buff-to-int-le",
+    ] {
+        let too_long = format!("({} 0x0102030405060708090a0b0c0d0e0f1011)", function);
+        assert!(matches!(
+            execute_v2(&too_long).unwrap_err(),
+            Error::Unchecked(CheckErrors::TypeValueError(..))
+        ));
+    }
+}
+
 #[test]
 fn test_simple_string_to_int() {
     let good1_test = r#"(string-to-int? "-1")"#;
@@ -428,3 +472,84 @@ fn test_simple_int_to_utf8() {
         .into()
     );
 }
+
+#[test]
+fn test_simple_to_ascii() {
+    let good1_test = "(to-ascii? 0x616263)";
+    let good1_expected = Value::some(Value::Sequence(SequenceData::String(CharType::ASCII(
+        ASCIIData {
+            data: "abc".as_bytes().to_vec(),
+        },
+    ))))
+    .unwrap();
+    assert_eq!(good1_expected, execute_v3(good1_test).unwrap().unwrap());
+
+    // whitespace is allowed in ASCII strings
+    let good2_test = "(to-ascii? 0x612062)";
+    let good2_expected = execute_v3("(some \"a b\")").unwrap().unwrap();
+    assert_eq!(good2_expected, execute_v3(good2_test).unwrap().unwrap());
+
+    // a byte that is not ASCII, or a control character, yields none
+    for bad_bytes_test in ["(to-ascii? 0x61ff63)", "(to-ascii? 0x6100)"] {
+        assert_eq!(Value::none(), execute_v3(bad_bytes_test).unwrap().unwrap());
+    }
+
+    // converting back recovers the original buffer
+    let round_trip_test = "(string-to-buff (unwrap-panic (to-ascii? 0x616263)))";
+    assert_eq!(
+        execute_v3("0x616263").unwrap().unwrap(),
+        execute_v3(round_trip_test).unwrap().unwrap()
+    );
+
+    let wrong_type_test = "(to-ascii? \"abc\")";
+    assert_eq!(
+        execute_v3(wrong_type_test).unwrap_err(),
+        CheckErrors::TypeValueError(
+            TypeSignature::max_buffer(),
+            Value::Sequence(SequenceData::String(CharType::ASCII(ASCIIData {
+                data: "abc".as_bytes().to_vec()
+            })))
+        )
+        .into()
+    );
+
+    // only available from Clarity3
+    assert_eq!(
+        execute_v2(r#"(to-ascii? 0x61)"#).unwrap_err(),
+        CheckErrors::UndefinedFunction("to-ascii?".to_string()).into()
+    );
+}
+
+#[test]
+fn test_simple_string_to_buff() {
+    let good1_test = r#"(string-to-buff "abc")"#;
+    let good1_expected = Value::Sequence(SequenceData::Buffer(BuffData {
+        data: "abc".as_bytes().to_vec(),
+    }));
+    assert_eq!(good1_expected, execute_v3(good1_test).unwrap().unwrap());
+
+    let good2_test = r#"(string-to-buff u"a\u{e9}")"#;
+    let good2_expected = Value::Sequence(SequenceData::Buffer(BuffData {
+        data: "a\u{e9}".as_bytes().to_vec(),
+    }));
+    assert_eq!(good2_expected, execute_v3(good2_test).unwrap().unwrap());
+
+    let wrong_type_test = "(string-to-buff 0x00)";
+    assert_eq!(
+        execute_v3(wrong_type_test).unwrap_err(),
+        CheckErrors::UnionTypeValueError(
+            vec![
+                TypeSignature::max_string_ascii(),
+                TypeSignature::max_string_utf8(),
+            ],
+            Value::Sequence(SequenceData::Buffer(BuffData { data: vec![0] }))
+        )
+        .into()
+    );
+
+    // only available from Clarity3
+    assert_eq!(
+        execute_v2(r#"(string-to-buff "a")"#).unwrap_err(),
+        CheckErrors::UndefinedFunction("string-to-buff".to_string()).into()
+    );
+}
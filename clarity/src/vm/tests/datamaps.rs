@@ -14,15 +14,18 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+use crate::vm::ast::ASTRules;
 use crate::vm::contexts::OwnedEnvironment;
 use crate::vm::database::MemoryBackingStore;
 use crate::vm::errors::{CheckErrors, Error, RuntimeErrorType, ShortReturnType};
-use crate::vm::execute;
+use crate::vm::tests::{symbols_from_values, with_memory_environment};
 use crate::vm::types::{
-    ListData, QualifiedContractIdentifier, SequenceData, StandardPrincipalData, TupleData,
-    TupleTypeSignature, TypeSignature, Value,
+    ListData, PrincipalData, QualifiedContractIdentifier, SequenceData, StandardPrincipalData,
+    TupleData, TupleTypeSignature, TypeSignature, Value,
 };
 use crate::vm::ClarityName;
+use crate::vm::{execute, execute_v2, execute_v3};
+use stacks_common::types::StacksEpochId;
 use std::convert::From;
 use std::convert::TryFrom;
 
@@ -741,6 +744,39 @@ fn test_combines_tuples() {
     }
 }
 
+#[test]
+fn test_tuple_get_optional() {
+    let nested =
+        "(define-data-var t { a: (optional { b: int }), c: uint } { a: (some { b: 5 }), c: u1 })";
+    let tests = [
+        "(get? c (var-get t))",
+        "(get? b (get? a (var-get t)))",
+        "(get? b (get? a (some (var-get t))))",
+        // a `none` anywhere in the chain short-circuits the rest of it
+        "(var-set t { a: none, c: u2 }) (get? b (get? a (var-get t)))",
+        "(get? b (get? a (if true none (some (var-get t)))))",
+    ];
+    let expected = [
+        Value::some(Value::UInt(1)).unwrap(),
+        Value::some(Value::Int(5)).unwrap(),
+        Value::some(Value::Int(5)).unwrap(),
+        Value::none(),
+        Value::none(),
+    ];
+
+    for (test, expected) in tests.iter().zip(expected.iter()) {
+        let program = format!("{} {}", nested, test);
+        assert_eq!(expected.clone(), execute_v3(&program).unwrap().unwrap());
+    }
+
+    for execute in [execute, execute_v2] {
+        assert_eq!(
+            execute("(get? a { a: 1 })").unwrap_err(),
+            CheckErrors::UndefinedFunction("get?".to_string()).into()
+        );
+    }
+}
+
 #[test]
 fn test_non_tuple_map_get_set() {
     let test1 = "(define-map entries uint (string-ascii 5))
@@ -821,3 +857,126 @@ fn test_non_tuple_map_kv_store() {
     let expected = Value::list_from(vec![Value::Int(0)]);
     assert_executes(expected, &test_get);
 }
+
+#[test]
+fn test_map_insert_and_set_return_values() {
+    let test = "(define-map kv-store int int)
+         (list (map-insert kv-store 1 10)
+               (map-insert kv-store 1 20)
+               (is-eq (map-get? kv-store 1) (some 10))
+               (map-set kv-store 1 30)
+               (is-eq (map-get? kv-store 1) (some 30))
+               (map-set kv-store 2 40)
+               (map-insert kv-store 2 50)
+               (is-eq (map-get? kv-store 2) (some 40)))";
+    let expected = Value::list_from(vec![
+        Value::Bool(true),
+        Value::Bool(false),
+        Value::Bool(true),
+        Value::Bool(true),
+        Value::Bool(true),
+        Value::Bool(true),
+        Value::Bool(false),
+        Value::Bool(true),
+    ]);
+    assert_executes(expected, test);
+
+    let bad = [
+        "(define-map kv-store int int) (map-insert kv-store u1 10)",
+        "(define-map kv-store int int) (map-set kv-store 1 u10)",
+    ];
+    let bad_expected: &[Error] = &[
+        CheckErrors::TypeValueError(TypeSignature::IntType, Value::UInt(1)).into(),
+        CheckErrors::TypeValueError(TypeSignature::IntType, Value::UInt(10)).into(),
+    ];
+    for (program, expected) in bad.iter().zip(bad_expected.iter()) {
+        assert_eq!(*expected, execute(program).unwrap_err());
+    }
+}
+
+#[test]
+fn test_map_writes_roll_back_on_abort() {
+    let contract = "(define-map kv-store int int)
+         (define-public (insert-then-abort (key int) (value int))
+            (begin (map-insert kv-store key value) (err u1)))
+         (define-public (set-then-abort (key int) (value int))
+            (begin (map-set kv-store key value) (err u1)))
+         (define-public (insert (key int) (value int))
+            (ok (map-insert kv-store key value)))
+         (define-read-only (kv-get (key int)) (map-get? kv-store key))";
+    let contract_id = QualifiedContractIdentifier::local("kv").unwrap();
+    let sender = PrincipalData::Standard(StandardPrincipalData::transient());
+
+    with_memory_environment(
+        |owned_env| {
+            owned_env
+                .initialize_contract(contract_id.clone(), contract, None, ASTRules::PrecheckSize)
+                .unwrap();
+            let mut call = |function: &str, args: Vec<Value>| {
+                owned_env
+                    .execute_transaction(
+                        sender.clone(),
+                        None,
+                        contract_id.clone(),
+                        function,
+                        &symbols_from_values(args),
+                    )
+                    .unwrap()
+                    .0
+            };
+
+            assert_eq!(
+                call("insert-then-abort", vec![Value::Int(1), Value::Int(10)]),
+                Value::error(Value::UInt(1)).unwrap()
+            );
+            assert_eq!(call("kv-get", vec![Value::Int(1)]), Value::none());
+
+            // the aborted insert left the key absent, so a real insert still succeeds
+            assert_eq!(
+                call("insert", vec![Value::Int(1), Value::Int(20)]),
+                Value::okay(Value::Bool(true)).unwrap()
+            );
+            assert_eq!(
+                call("set-then-abort", vec![Value::Int(1), Value::Int(30)]),
+                Value::error(Value::UInt(1)).unwrap()
+            );
+            assert_eq!(
+                call("kv-get", vec![Value::Int(1)]),
+                Value::some(Value::Int(20)).unwrap()
+            );
+        },
+        StacksEpochId::latest(),
+        true,
+    );
+}
+
+#[test]
+fn test_map_get_returns_optional() {
+    let contract = "(define-map balances { owner: principal } { amount: int })
+         (map-set balances (tuple (owner tx-sender)) (tuple (amount 5)))
+        ";
+
+    let mut test_present = contract.to_string();
+    test_present.push_str("(map-get? balances (tuple (owner tx-sender)))");
+    let expected = Value::some(make_tuple(vec![("amount".into(), Value::Int(5))])).unwrap();
+    assert_executes(Ok(expected), &test_present);
+
+    let mut test_absent = contract.to_string();
+    test_absent
+        .push_str("(map-get? balances (tuple (owner 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR)))");
+    assert_executes(Ok(Value::none()), &test_absent);
+
+    let mut test_bad_key = contract.to_string();
+    test_bad_key.push_str("(map-get? balances (tuple (owner 1)))");
+    let expected_key_type = TypeSignature::from(
+        TupleTypeSignature::try_from(vec![("owner".into(), TypeSignature::PrincipalType)]).unwrap(),
+    );
+    assert_eq!(
+        execute(&test_bad_key).unwrap_err(),
+        CheckErrors::TypeValueError(
+            expected_key_type,
+            make_tuple(vec![("owner".into(), Value::Int(1))])
+        )
+        .into()
+    );
+}
@@ -26,11 +26,13 @@ use rstest_reuse::{self, *};
 #[case(ClarityVersion::Clarity2, StacksEpochId::Epoch21)]
 fn test_clarity_versions_defines(#[case] version: ClarityVersion, #[case] epoch: StacksEpochId) {}
 
+use crate::vm::analysis::mem_type_check;
 use crate::vm::ast::build_ast;
 use crate::vm::ast::errors::{ParseError, ParseErrors};
 use crate::vm::errors::{CheckErrors, Error, RuntimeErrorType};
+use crate::vm::representations::MAX_STRING_LEN;
 use crate::vm::types::{QualifiedContractIdentifier, TypeSignature, Value};
-use crate::vm::{execute, ClarityVersion};
+use crate::vm::{execute, ClarityName, ClarityVersion};
 use stacks_common::types::StacksEpochId;
 
 fn assert_eq_err(e1: CheckErrors, e2: Error) {
@@ -116,6 +118,52 @@ fn test_bad_define_names() {
     );
 }
 
+#[test]
+fn test_clarity_names() {
+    let longest = format!("a{}", "0".repeat(127));
+    for name in [
+        "a",
+        "is-eq?",
+        "map-set!",
+        "x_1",
+        "+",
+        "<=",
+        longest.as_str(),
+    ] {
+        assert_eq!(
+            name,
+            ClarityName::try_from(name.to_string()).unwrap().as_str()
+        );
+    }
+
+    let too_long = format!("a{}", "0".repeat(128));
+    for name in [too_long.as_str(), "1abc", "foo.bar", "", "a b"] {
+        assert_eq!(
+            RuntimeErrorType::BadNameValue("ClarityName", name.to_string()),
+            ClarityName::try_from(name.to_string()).unwrap_err()
+        );
+    }
+
+    // the define forms accept names up to the length limit
+    let program = format!(
+        "(define-constant {name}-c 1)
+         (define-data-var {name} int 2)
+         (define-private ({name}-f ({name}-a int)) (+ {name}-a {name}-c (var-get {name})))
+         ({name}-f 3)",
+        name = "a".repeat(MAX_STRING_LEN as usize - 2)
+    );
+    assert_eq!(Ok(Some(Value::Int(6))), execute(&program));
+    let program = format!("(define-data-var {} int 2)", longest);
+    assert_eq!(Ok(None), execute(&program));
+    let program = format!("(define-data-var {} int 2)", too_long);
+    // the parser stops reading a name after `MAX_STRING_LEN` bytes
+    match execute(&program).unwrap_err() {
+        Error::Runtime(RuntimeErrorType::ASTError(e), _)
+            if matches!(e.err, ParseErrors::SeparatorExpected(..)) => {}
+        other => panic!("Expected a parse error, got {:?}", other),
+    }
+}
+
 #[test]
 fn test_unwrap_ret() {
     let test0 = "(define-private (foo) (unwrap! (ok 1) 2)) (foo)";
@@ -215,6 +263,76 @@ fn test_recursive_panic(#[case] version: ClarityVersion, #[case] epoch: StacksEp
     }
 }
 
+#[apply(test_clarity_versions_defines)]
+fn test_forward_references(#[case] version: ClarityVersion, #[case] epoch: StacksEpochId) {
+    // definitions are sorted by dependency before evaluation, so a function
+    //  may reference one that appears later in the source.
+    let tests = "(define-private (is-even (a int))
+          (if (is-eq a 0) true (is-odd-step a)))
+         (define-private (is-odd-step (a int))
+          (not (is-eq (mod a 2) 0)))
+         (is-even 7)";
+
+    mem_type_check(tests, version, epoch).unwrap();
+    assert_eq!(Ok(Some(Value::Bool(true))), execute(tests));
+
+    // mutual recursion is still a dependency cycle: the cost of a call must be
+    //  bounded statically, so it is rejected regardless of source order.
+    let tests = "(define-private (is-even (a int))
+          (if (is-eq a 0) true (is-odd (- a 1))))
+         (define-private (is-odd (a int))
+          (if (is-eq a 0) false (is-even (- a 1))))
+         (is-even 7)";
+
+    let err = build_ast(
+        &QualifiedContractIdentifier::transient(),
+        tests,
+        &mut (),
+        version,
+        epoch,
+    )
+    .unwrap_err();
+    match err.err {
+        ParseErrors::CircularReference(_) => {}
+        _ => panic!("{:?}", err),
+    }
+}
+
+#[apply(test_clarity_versions_defines)]
+fn test_typed_arguments(#[case] version: ClarityVersion, #[case] epoch: StacksEpochId) {
+    let tests = "(define-public (transfer (amount uint) (memo (optional (buff 4))))
+          (ok (+ amount (if (is-some memo) u1 u0))))
+         (transfer u10 (some 0x0102))";
+
+    mem_type_check(tests, version, epoch).unwrap();
+    assert_eq!(
+        Ok(Some(Value::okay(Value::UInt(11)).unwrap())),
+        execute(tests)
+    );
+
+    let tests = "(define-public (transfer (amount uint)
+                                 (to currency))
+          (ok amount))";
+
+    assert_eq_err(
+        CheckErrors::UnknownTypeName("currency".to_string()),
+        execute(tests).unwrap_err(),
+    );
+    let err = mem_type_check(tests, version, epoch).unwrap_err();
+    assert_eq!(err.err, CheckErrors::BadSyntaxBinding);
+    assert_eq!(err.diagnostic.spans.len(), 1);
+    assert_eq!(err.diagnostic.spans[0].start_line, 1);
+
+    // from Clarity3, the error points at the offending argument
+    let err = mem_type_check(tests, ClarityVersion::Clarity3, StacksEpochId::Epoch21).unwrap_err();
+    assert_eq!(
+        err.err,
+        CheckErrors::UnknownTypeName("currency".to_string())
+    );
+    assert_eq!(err.diagnostic.spans.len(), 1);
+    assert_eq!(err.diagnostic.spans[0].start_line, 2);
+}
+
 #[test]
 fn test_bad_variables() {
     let test0 = "(+ a 1)";
@@ -49,6 +49,7 @@ use super::ClarityVersion;
 
 mod assets;
 mod contracts;
+mod conversions;
 mod datamaps;
 mod defines;
 mod principals;
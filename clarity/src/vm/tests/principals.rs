@@ -958,6 +958,75 @@ fn test_principal_construct_version_byte_future() {
     );
 }
 
+#[test]
+// Test cases where the version byte is a recognized network, but not the one the environment runs
+// on. The principal is still built, and returned in the `(err ..)` for inspection.
+fn test_principal_construct_cross_network() {
+    let hash_bytes = "fa6bf38ed557fe417333710d6033e9419391a320";
+    // (version byte, name, whether the version byte is a mainnet one)
+    let cases = [
+        ("16", None, true),
+        ("14", None, true),
+        ("16", Some("foo"), true),
+        ("1a", None, false),
+        ("15", None, false),
+        ("1a", Some("foo"), false),
+    ];
+
+    for (version, name, version_is_mainnet) in cases.iter() {
+        let input = match name {
+            Some(name) => format!(
+                "(principal-construct? 0x{} 0x{} \"{}\")",
+                version, hash_bytes, name
+            ),
+            None => format!("(principal-construct? 0x{} 0x{})", version, hash_bytes),
+        };
+        let principal = create_principal_from_strings(version, hash_bytes, *name);
+
+        // same network
+        assert_eq!(
+            Value::okay(principal.clone()).unwrap(),
+            execute_with_parameters(
+                &input,
+                ClarityVersion::Clarity2,
+                StacksEpochId::Epoch21,
+                ASTRules::PrecheckSize,
+                *version_is_mainnet
+            )
+            .unwrap()
+            .unwrap(),
+            "{}",
+            input
+        );
+
+        // other network
+        assert_eq!(
+            Value::error(Value::Tuple(
+                TupleData::from_data(vec![
+                    (
+                        "error_code".into(),
+                        Value::UInt(PrincipalConstructErrorCode::VERSION_BYTE as u128)
+                    ),
+                    ("value".into(), Value::some(principal).unwrap()),
+                ])
+                .unwrap()
+            ))
+            .unwrap(),
+            execute_with_parameters(
+                &input,
+                ClarityVersion::Clarity2,
+                StacksEpochId::Epoch21,
+                ASTRules::PrecheckSize,
+                !*version_is_mainnet
+            )
+            .unwrap()
+            .unwrap(),
+            "{}",
+            input
+        );
+    }
+}
+
 #[test]
 // Test cases where the wrong type should be a `CheckErrors` error, because it should have been
 // caught by the type checker.
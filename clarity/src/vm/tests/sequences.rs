@@ -16,7 +16,7 @@
 
 use crate::vm::types::signatures::{ListTypeData, SequenceSubtype};
 use crate::vm::types::TypeSignature::{BoolType, IntType, SequenceType, UIntType};
-use crate::vm::types::{StringSubtype, StringUTF8Length, TypeSignature, Value};
+use crate::vm::types::{StringSubtype, StringUTF8Length, TupleData, TypeSignature, Value};
 #[cfg(test)]
 use rstest::rstest;
 #[cfg(test)]
@@ -28,7 +28,7 @@ use crate::vm::types::signatures::SequenceSubtype::{BufferType, ListType, String
 use crate::vm::types::signatures::StringSubtype::ASCII;
 use crate::vm::types::BufferLength;
 use crate::vm::types::CharType::UTF8;
-use crate::vm::{execute, execute_v2, ClarityVersion};
+use crate::vm::{execute, execute_v2, execute_v3, ClarityVersion};
 use stacks_common::types::StacksEpochId;
 use std::convert::{TryFrom, TryInto};
 
@@ -140,6 +140,27 @@ fn test_index_of() {
     }
 }
 
+#[test]
+fn test_index_of_alias() {
+    let tests = [
+        ("(index-of? (list 1 2 3 4 5 4) 4)", "(some u3)"),
+        ("(index-of? (list 1 2 3 4 5 4) 100)", "none"),
+        ("(index-of? 0xfedb 0xdb)", "(some u1)"),
+    ];
+    for (test, expected) in tests.iter() {
+        assert_eq!(expected, &format!("{}", execute_v2(test).unwrap().unwrap()));
+        // `index-of?` and `index-of` are the same function
+        let original = test.replace("index-of?", "index-of");
+        assert_eq!(execute_v2(&original).unwrap(), execute_v2(test).unwrap());
+    }
+
+    // the alias is only defined from Clarity2 on
+    assert_eq!(
+        Error::Unchecked(CheckErrors::UndefinedFunction("index-of?".to_string())),
+        execute("(index-of? (list 1 2 3 4) 1)").unwrap_err()
+    );
+}
+
 #[test]
 fn test_element_at() {
     let good = [
@@ -438,6 +459,89 @@ fn test_variadic_map_list() {
     ])
     .unwrap();
     assert_eq!(expected, execute(test).unwrap().unwrap());
+
+    let test = "(map + (list 1 2 3) (list 10 20 30))";
+
+    let expected = Value::list_from(vec![Value::Int(11), Value::Int(22), Value::Int(33)]).unwrap();
+    assert_eq!(expected, execute(test).unwrap().unwrap());
+
+    // zipping parallel lists, truncated to the shorter one
+    let zip = "(define-private (zip (a int) (b uint)) { a: a, b: b })";
+    let test = format!("{} (map zip (list 1 2 3) (list u10 u20))", zip);
+    let pair = |a, b| {
+        Value::from(
+            TupleData::from_data(vec![
+                ("a".into(), Value::Int(a)),
+                ("b".into(), Value::UInt(b)),
+            ])
+            .unwrap(),
+        )
+    };
+    let expected = Value::list_from(vec![pair(1, 10), pair(2, 20)]).unwrap();
+    assert_eq!(expected, execute(&test).unwrap().unwrap());
+
+    // elements are checked against the callback's parameter types
+    let test = format!("{} (map zip (list 1 2 3) (list 10 20 30))", zip);
+    assert_eq!(
+        Error::from(CheckErrors::TypeValueError(UIntType, Value::Int(10))),
+        execute(&test).unwrap_err()
+    );
+}
+
+#[test]
+fn test_map_try() {
+    let checked_half = "(define-data-var calls int 0)
+        (define-private (checked-half (n int))
+          (begin
+            (var-set calls (+ (var-get calls) 1))
+            (if (is-eq (mod n 2) 0) (ok (/ n 2)) (err n))))";
+
+    let test = format!("{} (map-try! checked-half (list 2 4 6))", checked_half);
+    let expected =
+        Value::okay(Value::list_from(vec![Value::Int(1), Value::Int(2), Value::Int(3)]).unwrap())
+            .unwrap();
+    assert_eq!(expected, execute_v3(&test).unwrap().unwrap());
+
+    // the first err is returned, and the remaining elements are not processed
+    let test = format!(
+        "{} {{ result: (map-try! checked-half (list 2 3 5 8)), calls: (var-get calls) }}",
+        checked_half
+    );
+    let expected = Value::from(
+        TupleData::from_data(vec![
+            ("result".into(), Value::error(Value::Int(3)).unwrap()),
+            ("calls".into(), Value::Int(2)),
+        ])
+        .unwrap(),
+    );
+    assert_eq!(expected, execute_v3(&test).unwrap().unwrap());
+
+    let test = format!("{} (map-try! checked-half (list))", checked_half);
+    let expected = Value::okay(Value::list_from(vec![]).unwrap()).unwrap();
+    assert_eq!(expected, execute_v3(&test).unwrap().unwrap());
+
+    let test = "(define-private (byte-or-fail (b (buff 1))) (if (is-eq b 0xff) (err u1) (ok b)))
+        (map-try! byte-or-fail 0x0102)";
+    let expected = Value::okay(
+        Value::list_from(vec![
+            Value::buff_from_byte(0x01),
+            Value::buff_from_byte(0x02),
+        ])
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(expected, execute_v3(test).unwrap().unwrap());
+
+    let test = "(define-private (id (n int)) n) (map-try! id (list 1 2))";
+    assert_eq!(
+        Error::from(CheckErrors::ExpectedResponseValue(Value::Int(1))),
+        execute_v3(test).unwrap_err()
+    );
+
+    assert_eq!(
+        Error::from(CheckErrors::UndefinedFunction("map-try!".to_string())),
+        execute_v2(test).unwrap_err()
+    );
 }
 
 #[test]
@@ -488,6 +592,29 @@ fn test_slice_list() {
     }
 }
 
+#[test]
+fn test_slice_empty_at_end() {
+    let tests = [
+        "(slice? (list 2 3 4) u3 u3)",
+        "(slice? (list 2 3 4) u4 u4)",
+        "(slice? 0x000102030405 u6 u6)",
+        "(slice? \"abc\" u3 u3)",
+    ];
+
+    let expected = [
+        Value::some(Value::list_from(vec![]).unwrap()).unwrap(),
+        Value::none(),
+        Value::some(Value::buff_from(vec![]).unwrap()).unwrap(),
+        Value::some(Value::string_ascii_from_bytes(vec![]).unwrap()).unwrap(),
+    ];
+
+    for (test, expected) in tests.iter().zip(expected.iter()) {
+        assert_eq!(expected.clone(), execute_v3(test).unwrap().unwrap());
+        // before Clarity3, `left-position` must be less than the sequence length
+        assert_eq!(Value::none(), execute_v2(test).unwrap().unwrap());
+    }
+}
+
 #[test]
 fn test_slice_buff() {
     let tests = [
@@ -739,6 +866,21 @@ fn test_simple_list_replace_at() {
         execute_v2("(replace-at? (list 2 3) u0 0x00)").unwrap_err(),
         CheckErrors::TypeValueError(IntType, Value::buff_from_byte(0)).into()
     );
+
+    // Replacing through a data var keeps the stored list's type
+    let contract = "(define-data-var xs (list 3 int) (list 1 2 3))
+        (var-set xs (unwrap-panic (replace-at? (var-get xs) u1 20)))
+        (var-get xs)";
+    assert_eq!(
+        execute_v2(contract).unwrap().unwrap(),
+        Value::list_from(vec![Value::Int(1), Value::Int(20), Value::Int(3)]).unwrap()
+    );
+
+    // replace-at? is not defined in Clarity1
+    assert_eq!(
+        execute("(replace-at? (list 2 3) u0 4)").unwrap_err(),
+        CheckErrors::UndefinedFunction("replace-at?".to_string()).into()
+    );
 }
 
 #[test]
@@ -1006,6 +1148,27 @@ fn test_simple_buff_assert_max_len() {
         CheckErrors::ExpectedSequence(IntType).into()
     );
 
+    // Clarity3 accepts a constant as the bound
+    let max_len = "(define-constant MAX-LEN u3)";
+    assert_eq!(
+        Value::some(Value::buff_from(vec![49, 50, 51]).unwrap()).unwrap(),
+        execute_v3(&format!("{} (as-max-len? 0x313233 MAX-LEN)", max_len))
+            .unwrap()
+            .unwrap()
+    );
+    assert_eq!(
+        Value::none(),
+        execute_v3(&format!("{} (as-max-len? 0x31323334 MAX-LEN)", max_len))
+            .unwrap()
+            .unwrap()
+    );
+    for execute in [execute, execute_v2] {
+        assert_eq!(
+            execute(&format!("{} (as-max-len? 0x313233 MAX-LEN)", max_len)).unwrap_err(),
+            CheckErrors::TypeError(UIntType, UIntType).into()
+        );
+    }
+
     assert_eq!(
         execute("(as-max-len? 0x313233 0x31)").unwrap_err(),
         CheckErrors::TypeError(
@@ -1016,6 +1179,60 @@ fn test_simple_buff_assert_max_len() {
     );
 }
 
+#[test]
+fn test_sort() {
+    let principals = [
+        "'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR",
+        "'S1G2081040G2081040G2081040G208105NK8PE5.contract",
+        "'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM",
+        "'S1G2081040G2081040G2081040G208105NK8PE5",
+    ];
+    let sorted_principals = {
+        let mut values: Vec<_> = principals
+            .iter()
+            .map(|p| execute_v3(p).unwrap().unwrap())
+            .collect();
+        values.sort_by(Value::canonical_cmp);
+        Value::list_from(values).unwrap()
+    };
+    // every input order produces the same output
+    for rotation in 0..principals.len() {
+        let mut rotated = principals.to_vec();
+        rotated.rotate_left(rotation);
+        let program = format!("(sort (list {}))", rotated.join(" "));
+        assert_eq!(execute_v3(&program).unwrap().unwrap(), sorted_principals);
+    }
+
+    let ints = [
+        ("(sort (list 3 1 2))", vec![1, 2, 3]),
+        ("(sort (list 2 3 1))", vec![1, 2, 3]),
+        // ints are ordered by their two's complement serialization
+        ("(sort (list -1 0 5 -7))", vec![0, 5, -7, -1]),
+        ("(sort (list 1 1 0))", vec![0, 1, 1]),
+    ];
+    for (program, expected) in ints.iter() {
+        assert_eq!(
+            execute_v3(program).unwrap().unwrap(),
+            Value::list_from(expected.iter().cloned().map(Value::Int).collect()).unwrap()
+        );
+    }
+
+    assert_eq!(
+        execute_v3("(sort (list))").unwrap().unwrap(),
+        Value::list_from(vec![]).unwrap()
+    );
+    assert_eq!(
+        execute_v3("(sort 1)").unwrap_err(),
+        CheckErrors::ExpectedListApplication.into()
+    );
+    for execute in [execute, execute_v2] {
+        assert_eq!(
+            execute("(sort (list 1 2))").unwrap_err(),
+            CheckErrors::UndefinedFunction("sort".to_string()).into()
+        );
+    }
+}
+
 #[test]
 fn test_simple_list_assert_max_len() {
     let tests = [
@@ -1148,11 +1365,63 @@ fn test_simple_folds_string() {
     }
 }
 
+#[test]
+fn test_simple_folds_buff() {
+    let sum_bytes =
+        "(define-private (add-byte (x (buff 1)) (acc uint)) (+ acc (buff-to-uint-be x)))
+        (fold add-byte 0x010203 u0)";
+    assert_eq!(Value::UInt(6), execute_v2(sum_bytes).unwrap().unwrap());
+
+    let reverse = "(define-private (prepend (x (buff 1)) (acc (buff 3))) (unwrap-panic (as-max-len? (concat x acc) u3)))
+        (fold prepend 0x010203 0x)";
+    assert_eq!(
+        Value::buff_from(vec![3, 2, 1]).unwrap(),
+        execute(reverse).unwrap().unwrap()
+    );
+
+    // the callback is invoked with one-byte buffers
+    let mistyped = "(define-private (add-int (x int) (acc int)) (+ x acc))
+        (fold add-int 0x0102 0)";
+    assert_eq!(
+        execute(mistyped).unwrap_err(),
+        CheckErrors::TypeValueError(IntType, Value::buff_from_byte(1)).into()
+    );
+
+    // errors raised by the callback abort the fold
+    let failing = "(define-private (checked-add (x (buff 1)) (acc uint))
+            (if (is-eq x 0xff) (/ acc u0) (+ acc (buff-to-uint-be x))))
+        (fold checked-add 0x01ff02 u0)";
+    assert_eq!(
+        execute_v2(failing).unwrap_err(),
+        RuntimeErrorType::DivisionByZero.into()
+    );
+}
+
 #[test]
 fn test_native_len() {
     let test1 = "(len (list 1 2 3 4))";
     let expected = Value::UInt(4);
     assert_eq!(expected, execute(test1).unwrap().unwrap());
+
+    let test2 = "(len 0xdeadbeef)";
+    let expected = Value::UInt(4);
+    assert_eq!(expected, execute(test2).unwrap().unwrap());
+
+    let test3 = "(len (list))";
+    let expected = Value::UInt(0);
+    assert_eq!(expected, execute(test3).unwrap().unwrap());
+
+    let bad = ["(len 5)", "(len true)", "(len (some 0x01))"];
+    let bad_expected = [
+        CheckErrors::ExpectedSequence(IntType),
+        CheckErrors::ExpectedSequence(BoolType),
+        CheckErrors::ExpectedSequence(
+            TypeSignature::new_option(TypeSignature::min_buffer()).unwrap(),
+        ),
+    ];
+    for (program, expected) in bad.iter().zip(bad_expected) {
+        assert_eq!(execute(program).unwrap_err(), expected.into());
+    }
 }
 
 #[test]
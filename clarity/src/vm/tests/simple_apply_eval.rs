@@ -24,18 +24,22 @@ use rstest_reuse::{self, *};
 #[cfg(test)]
 use crate::vm::ast::parse;
 
-use crate::vm::ast::ASTRules;
+use crate::vm::ast::errors::ParseErrors;
+use crate::vm::ast::{build_ast_with_rules, ASTRules};
 use crate::vm::callables::DefinedFunction;
-use crate::vm::contexts::OwnedEnvironment;
-use crate::vm::costs::LimitedCostTracker;
+use crate::vm::contexts::{ArithmeticMode, OwnedEnvironment};
+use crate::vm::costs::{ExecutionCost, LimitedCostTracker};
+use crate::vm::diagnostic::DiagnosableError;
 use crate::vm::errors::{CheckErrors, Error, RuntimeErrorType, ShortReturnType};
 use crate::vm::tests::execute;
 use crate::vm::types::signatures::*;
 use crate::vm::types::{ASCIIData, BuffData, CharType, QualifiedContractIdentifier, TypeSignature};
 use crate::vm::types::{PrincipalData, ResponseData, SequenceData, SequenceSubtype, StringSubtype};
+use crate::vm::types::{TupleData, TupleTypeSignature};
 use crate::vm::ClarityVersion;
 use crate::vm::{
-    eval, execute as vm_execute, execute_v2 as vm_execute_v2, execute_with_parameters,
+    eval, execute as vm_execute, execute_v2 as vm_execute_v2, execute_v3 as vm_execute_v3,
+    execute_with_parameters,
 };
 use crate::vm::{CallStack, ContractContext, Environment, GlobalContext, LocalContext, Value};
 use stacks_common::address::c32;
@@ -47,7 +51,7 @@ use stacks_common::types::chainstate::StacksAddress;
 use stacks_common::types::chainstate::StacksPrivateKey;
 use stacks_common::types::chainstate::StacksPublicKey;
 use stacks_common::types::StacksEpochId;
-use stacks_common::util::hash::{hex_bytes, to_hex};
+use stacks_common::util::hash::{hex_bytes, to_hex, Hash160};
 
 #[template]
 #[rstest]
@@ -60,8 +64,11 @@ fn test_clarity_versions_simple_apply_eval(
 ) {
 }
 
-use crate::vm::database::MemoryBackingStore;
+use crate::vm::database::{ClarityBackingStore, MemoryBackingStore};
+use crate::vm::eval_all;
 use crate::vm::types::StacksAddressExtensions;
+use rusqlite::Connection;
+use stacks_common::types::chainstate::StacksBlockId;
 
 #[test]
 fn test_doubly_defined_persisted_vars() {
@@ -115,6 +122,169 @@ fn test_simple_let(#[case] version: ClarityVersion, #[case] epoch: StacksEpochId
     }
 }
 
+#[test]
+fn test_host_functions() {
+    let mut marf = MemoryBackingStore::new();
+    let mut env = OwnedEnvironment::new(marf.as_clarity_db(), StacksEpochId::latest());
+
+    env.register_host_function("double", ExecutionCost::zero(), |args, _env| match args {
+        [Value::Int(x)] => x
+            .checked_mul(2)
+            .map(Value::Int)
+            .ok_or(RuntimeErrorType::ArithmeticOverflow.into()),
+        [x] => Err(CheckErrors::TypeValueError(TypeSignature::IntType, x.clone()).into()),
+        _ => Err(CheckErrors::IncorrectArgumentCount(1, args.len()).into()),
+    })
+    .unwrap();
+
+    assert_eq!(
+        env.eval_raw("(+ 1 (double (double 5)))").unwrap().0,
+        Value::Int(21)
+    );
+    assert_eq!(
+        env.eval_raw("(double true)").unwrap_err(),
+        CheckErrors::TypeValueError(TypeSignature::IntType, Value::Bool(true)).into()
+    );
+
+    // built-ins can't be shadowed, and a name can only be registered once
+    for name in ["+", "tx-sender", "stx-account", "double"] {
+        assert_eq!(
+            env.register_host_function(name, ExecutionCost::zero(), |_args, _env| Ok(Value::Int(
+                0
+            )))
+            .unwrap_err(),
+            CheckErrors::NameAlreadyUsed(name.to_string()).into()
+        );
+    }
+    assert_eq!(env.eval_raw("(+ 1 2)").unwrap().0, Value::Int(3));
+}
+
+#[test]
+fn test_arithmetic_modes() {
+    let mut marf = MemoryBackingStore::new();
+    let mut env = OwnedEnvironment::new(marf.as_clarity_db(), StacksEpochId::latest());
+
+    let overflowing = [
+        (
+            "(+ 170141183460469231731687303715884105727 1)",
+            Value::Int(i128::MIN),
+        ),
+        ("(- u0 u1)", Value::UInt(u128::MAX)),
+        (
+            "(- -170141183460469231731687303715884105728)",
+            Value::Int(i128::MIN),
+        ),
+        (
+            "(* u340282366920938463463374607431768211455 u2)",
+            Value::UInt(u128::MAX - 1),
+        ),
+        (
+            "(fold + (list 1 2) 170141183460469231731687303715884105727)",
+            Value::Int(i128::MIN + 2),
+        ),
+    ];
+    let expectations: &[Error] = &[
+        RuntimeErrorType::ArithmeticOverflow.into(),
+        RuntimeErrorType::ArithmeticUnderflow.into(),
+        RuntimeErrorType::ArithmeticUnderflow.into(),
+        RuntimeErrorType::ArithmeticOverflow.into(),
+        RuntimeErrorType::ArithmeticOverflow.into(),
+    ];
+
+    for ((program, _), expectation) in overflowing.iter().zip(expectations) {
+        assert_eq!(&env.eval_raw(program).unwrap_err(), expectation);
+    }
+
+    env.set_arithmetic_mode(ArithmeticMode::Wrapping);
+    for (program, wrapped) in overflowing.iter() {
+        assert_eq!(&env.eval_raw(program).unwrap().0, wrapped);
+    }
+    assert_eq!(env.eval_raw("(+ 1 2)").unwrap().0, Value::Int(3));
+
+    // division by zero is an error in either mode
+    for program in ["(/ 1 0)", "(mod u1 u0)"] {
+        assert_eq!(
+            env.eval_raw(program).unwrap_err(),
+            RuntimeErrorType::DivisionByZero.into()
+        );
+    }
+
+    env.set_arithmetic_mode(ArithmeticMode::Abort);
+    assert_eq!(
+        env.eval_raw(overflowing[0].0).unwrap_err(),
+        RuntimeErrorType::ArithmeticOverflow.into()
+    );
+}
+
+#[test]
+#[should_panic(expected = "wrapping arithmetic is not permitted on mainnet")]
+fn test_wrapping_arithmetic_rejected_on_mainnet() {
+    let mut marf = MemoryBackingStore::new();
+    let mut env = OwnedEnvironment::new_free(
+        true,
+        CHAIN_ID_MAINNET,
+        marf.as_clarity_db(),
+        StacksEpochId::latest(),
+    );
+    env.set_arithmetic_mode(ArithmeticMode::Wrapping);
+}
+
+#[test]
+fn test_assert_eq() {
+    let contract = "(define-read-only (passes) (assert-eq! (+ 1 2) 3))
+         (define-read-only (fails) (assert-eq! (list 1 2) (list 2 1)))
+         (define-read-only (mismatched) (assert-eq! 1 u1))";
+    let mut marf = MemoryBackingStore::new();
+    let mut env = OwnedEnvironment::new(marf.as_clarity_db(), StacksEpochId::latest());
+    let contract_id = QualifiedContractIdentifier::local("unit-tests").unwrap();
+    env.initialize_versioned_contract(
+        contract_id.clone(),
+        ClarityVersion::Clarity3,
+        contract,
+        None,
+        ASTRules::PrecheckSize,
+    )
+    .unwrap();
+
+    // outside of debug mode, the native is rejected before it compares anything
+    assert_eq!(
+        env.eval_read_only(&contract_id, "(passes)").unwrap_err(),
+        RuntimeErrorType::DebugOnlyFunction("assert-eq!".into()).into()
+    );
+
+    env.set_debug_mode(true);
+    assert_eq!(
+        env.eval_read_only(&contract_id, "(passes)").unwrap().0,
+        Value::okay_true()
+    );
+    assert_eq!(
+        env.eval_read_only(&contract_id, "(fails)").unwrap_err(),
+        RuntimeErrorType::AssertionFailed(
+            Value::list_from(vec![Value::Int(1), Value::Int(2)]).unwrap(),
+            Value::list_from(vec![Value::Int(2), Value::Int(1)]).unwrap(),
+        )
+        .into()
+    );
+    assert_eq!(
+        env.eval_read_only(&contract_id, "(mismatched)")
+            .unwrap_err(),
+        CheckErrors::TypeError(TypeSignature::IntType, TypeSignature::UIntType).into()
+    );
+}
+
+#[test]
+#[should_panic(expected = "debug mode is not permitted on mainnet")]
+fn test_debug_mode_rejected_on_mainnet() {
+    let mut marf = MemoryBackingStore::new();
+    let mut env = OwnedEnvironment::new_free(
+        true,
+        CHAIN_ID_MAINNET,
+        marf.as_clarity_db(),
+        StacksEpochId::latest(),
+    );
+    env.set_debug_mode(true);
+}
+
 #[test]
 fn test_sha256() {
     let sha256_evals = [
@@ -221,6 +391,35 @@ fn test_keccak256() {
         .for_each(|(program, expectation)| assert_eq!(to_buffer(expectation), execute(program)));
 }
 
+#[test]
+fn test_hash160() {
+    let inputs = [
+        "",
+        "54686520717569636b2062726f776e20666f78206a756d7073206f76657220746865206c617a7920646f67", // The quick brown fox jumps over the lazy dog
+    ];
+
+    let expectations = [
+        "b472a266d0bd89c13706a4132ccfb16f7c3b9fcb",
+        "0e3397b4abc7a382b3ea2365883c3c7ca5f07600",
+    ];
+
+    inputs
+        .iter()
+        .zip(expectations.iter())
+        .for_each(|(input, expectation)| {
+            let host_hash = Hash160::from_data(&hex_bytes(input).unwrap());
+            assert_eq!(expectation, &host_hash.to_hex());
+            assert_eq!(
+                host_hash.as_bytes().to_vec(),
+                hex_bytes(expectation).unwrap()
+            );
+            assert_eq!(
+                Value::buff_from(host_hash.as_bytes().to_vec()).unwrap(),
+                execute(&format!("(hash160 0x{})", input))
+            );
+        });
+}
+
 #[test]
 /// This test serializes two different values which do fit in
 ///  the Clarity maximum value size, but whose serializations
@@ -634,6 +833,36 @@ fn test_buffer_equality() {
         .for_each(|(program, expectation)| assert_eq!(expectation.clone(), execute(program)));
 }
 
+#[test]
+fn test_variadic_equality() {
+    let tests = [
+        "(is-eq 1)",
+        "(is-eq 1 1 1 1)",
+        "(is-eq 1 1 2 1)",
+        "(is-eq (some u1) (some u1) none)",
+        // arguments are all evaluated, but comparison stops at the first
+        //  inequality, so the trailing argument's type is never checked
+        "(is-eq 1 2 true)",
+    ];
+    let expectations = [
+        Value::Bool(true),
+        Value::Bool(true),
+        Value::Bool(false),
+        Value::Bool(false),
+        Value::Bool(false),
+    ];
+
+    tests
+        .iter()
+        .zip(expectations.iter())
+        .for_each(|(program, expectation)| assert_eq!(expectation.clone(), execute(program)));
+
+    assert_eq!(
+        vm_execute("(is-eq 1 1 true)").unwrap_err(),
+        CheckErrors::TypeError(TypeSignature::BoolType, TypeSignature::IntType).into()
+    );
+}
+
 #[test]
 fn test_principal_equality() {
     let tests = [
@@ -743,6 +972,55 @@ fn test_simple_if_functions(#[case] version: ClarityVersion, #[case] epoch: Stac
     }
 }
 
+#[test]
+fn test_single_arm_if() {
+    let tests = [
+        "(if (is-eq 5 5) 1)",
+        "(if (is-eq 5 3) 1)",
+        "(if true (+ 1 2))",
+        "(if false \"abc\")",
+        "(if true none)",
+        "(define-data-var n int 0)
+         (if false (var-set n 1))
+         (var-get n)",
+    ];
+    let expectations = [
+        Value::some(Value::Int(1)).unwrap(),
+        Value::none(),
+        Value::some(Value::Int(3)).unwrap(),
+        Value::none(),
+        Value::some(Value::none()).unwrap(),
+        Value::Int(0),
+    ];
+
+    for (program, expectation) in tests.iter().zip(expectations.iter()) {
+        assert_eq!(
+            Some(expectation.clone()),
+            vm_execute_v3(program).unwrap(),
+            "{}",
+            program
+        );
+    }
+
+    // two-arm `if` is unchanged
+    assert_eq!(
+        Some(Value::Int(3)),
+        vm_execute_v3("(if (is-eq 5 3) 1 3)").unwrap()
+    );
+
+    // single-arm `if` is only available from Clarity3
+    for execute in [vm_execute, vm_execute_v2] {
+        assert_eq!(
+            Error::from(CheckErrors::IncorrectArgumentCount(3, 2)),
+            execute("(if true 1)").unwrap_err()
+        );
+    }
+    assert_eq!(
+        Error::from(CheckErrors::RequiresAtMostArguments(3, 4)),
+        vm_execute_v3("(if true 1 2 3)").unwrap_err()
+    );
+}
+
 #[test]
 fn test_concat_append_supertype() {
     let tests = [
@@ -1188,6 +1466,126 @@ fn test_unsigned_arithmetic() {
     }
 }
 
+#[test]
+fn test_mul_div() {
+    let tests = [
+        ("(mul-div 7 3 2)", Value::Int(10)),
+        ("(mul-div -7 3 2)", Value::Int(-10)),
+        ("(mul-div -7 -3 -2)", Value::Int(-10)),
+        ("(mul-div 0 -3 2)", Value::Int(0)),
+        ("(mul-div u7 u3 u2)", Value::UInt(10)),
+        // the raw products overflow, but the quotients fit
+        ("(mul-div 10 (pow 2 126) 20)", Value::Int(1 << 125)),
+        (
+            "(mul-div (pow 2 126) (pow 2 126) (pow 2 126))",
+            Value::Int(1 << 126),
+        ),
+        ("(mul-div (pow 2 126) -2 1)", Value::Int(i128::MIN)),
+        (
+            "(mul-div u340282366920938463463374607431768211455 u340282366920938463463374607431768211455 u340282366920938463463374607431768211455)",
+            Value::UInt(u128::MAX),
+        ),
+        (
+            "(mul-div u340282366920938463463374607431768211455 u3 u6)",
+            Value::UInt(u128::MAX / 2),
+        ),
+    ];
+    for (program, expectation) in tests.iter() {
+        assert_eq!(Some(expectation.clone()), vm_execute_v3(program).unwrap());
+    }
+
+    let errors = [
+        "(mul-div 1 2 0)",
+        "(mul-div u1 u2 u0)",
+        "(mul-div (pow 2 126) 2 1)",
+        "(mul-div (pow 2 126) -4 1)",
+        "(mul-div u340282366920938463463374607431768211455 u2 u1)",
+    ];
+    let expectations: &[Error] = &[
+        RuntimeErrorType::DivisionByZero.into(),
+        RuntimeErrorType::DivisionByZero.into(),
+        RuntimeErrorType::ArithmeticOverflow.into(),
+        RuntimeErrorType::ArithmeticOverflow.into(),
+        RuntimeErrorType::ArithmeticOverflow.into(),
+    ];
+    for (program, expectation) in errors.iter().zip(expectations.iter()) {
+        assert_eq!(*expectation, vm_execute_v3(program).unwrap_err());
+    }
+
+    // mul-div is only available from Clarity3
+    for execute in [vm_execute, vm_execute_v2] {
+        assert_eq!(
+            Error::from(CheckErrors::UndefinedFunction("mul-div".to_string())),
+            execute("(mul-div 1 2 3)").unwrap_err()
+        );
+    }
+}
+
+#[test]
+fn test_arithmetic_over_lists() {
+    let tests = [
+        ("(+ (list 1 2 3))", Value::Int(6)),
+        ("(* (list u2 u3 u4))", Value::UInt(24)),
+        ("(+ (list -5))", Value::Int(-5)),
+        ("(+ 5 4 1)", Value::Int(10)),
+        ("(* 2 3)", Value::Int(6)),
+        ("(+ (filter is-neg (list 1 2)))", Value::Int(0)),
+        ("(* (filter is-neg (list 1 2)))", Value::Int(1)),
+        ("(* (filter is-zero (list u1 u2)))", Value::UInt(1)),
+        ("(fold + (list 1 2 3) 0)", Value::Int(6)),
+        (
+            "(map + (list (list 1 2) (list 3)))",
+            Value::list_from(vec![Value::Int(3), Value::Int(3)]).unwrap(),
+        ),
+    ];
+    let prelude = "(define-private (is-neg (x int)) (< x 0)) (define-private (is-zero (x uint)) (is-eq x u0))";
+
+    for (program, expectation) in tests.iter() {
+        assert_eq!(
+            Some(expectation.clone()),
+            vm_execute_v3(&format!("{} {}", prelude, program)).unwrap()
+        );
+    }
+
+    let errors = [
+        "(* (list (pow 2 126) 4))",
+        "(+ (list u1 (- (pow u2 u127) u1) (pow u2 u127)))",
+        "(+ (list true))",
+        "(+ (list))",
+    ];
+    let expectations: &[Error] = &[
+        RuntimeErrorType::ArithmeticOverflow.into(),
+        RuntimeErrorType::ArithmeticOverflow.into(),
+        CheckErrors::UnionTypeValueError(
+            vec![TypeSignature::IntType, TypeSignature::UIntType],
+            Value::Bool(true),
+        )
+        .into(),
+        CheckErrors::UnionTypeValueError(
+            vec![TypeSignature::IntType, TypeSignature::UIntType],
+            Value::list_from(vec![]).unwrap(),
+        )
+        .into(),
+    ];
+    for (program, expectation) in errors.iter().zip(expectations.iter()) {
+        assert_eq!(*expectation, vm_execute_v3(program).unwrap_err());
+    }
+
+    // Clarity1 and Clarity2 only support the variadic form
+    for execute in [vm_execute, vm_execute_v2] {
+        assert_eq!(
+            CheckErrors::UnionTypeValueError(
+                vec![TypeSignature::IntType, TypeSignature::UIntType],
+                Value::list_from(vec![Value::Int(1), Value::Int(2), Value::Int(3)]).unwrap(),
+            ),
+            match execute("(+ (list 1 2 3))").unwrap_err() {
+                Error::Unchecked(e) => e,
+                e => panic!("Unexpected error: {}", e),
+            }
+        );
+    }
+}
+
 #[test]
 fn test_options_errors() {
     let tests = [
@@ -1366,6 +1764,90 @@ fn test_bitwise() {
     }
 }
 
+#[test]
+fn test_bit_count_and_leading_zeros() {
+    let tests = [
+        ("(bit-count u7)", Value::UInt(3)),
+        ("(bit-count u0)", Value::UInt(0)),
+        ("(bit-count 255)", Value::UInt(8)),
+        (
+            "(bit-count u340282366920938463463374607431768211455)",
+            Value::UInt(128),
+        ),
+        // negative ints are counted in two's complement
+        ("(bit-count -1)", Value::UInt(128)),
+        ("(bit-count -2)", Value::UInt(127)),
+        (
+            "(bit-count -170141183460469231731687303715884105728)",
+            Value::UInt(1),
+        ),
+        ("(leading-zeros u1)", Value::UInt(127)),
+        ("(leading-zeros u0)", Value::UInt(128)),
+        ("(leading-zeros 0)", Value::UInt(128)),
+        ("(leading-zeros 255)", Value::UInt(120)),
+        (
+            "(leading-zeros u340282366920938463463374607431768211455)",
+            Value::UInt(0),
+        ),
+        (
+            "(leading-zeros 170141183460469231731687303715884105727)",
+            Value::UInt(1),
+        ),
+        ("(leading-zeros -1)", Value::UInt(0)),
+    ];
+    for (program, expected) in tests.iter() {
+        assert_eq!(vm_execute_v3(program).unwrap().unwrap(), *expected);
+    }
+
+    assert_eq!(
+        vm_execute_v2("(bit-count u7)").unwrap_err(),
+        CheckErrors::UndefinedFunction("bit-count".to_string()).into()
+    );
+    assert_eq!(
+        vm_execute_v2("(leading-zeros u1)").unwrap_err(),
+        CheckErrors::UndefinedFunction("leading-zeros".to_string()).into()
+    );
+
+    assert_eq!(
+        vm_execute_v3("(bit-count true)").unwrap_err(),
+        CheckErrors::UnionTypeError(
+            vec![TypeSignature::IntType, TypeSignature::UIntType],
+            TypeSignature::BoolType
+        )
+        .into()
+    );
+}
+
+#[test]
+fn test_buffer_literal_display() {
+    let buff = execute("0xdeadbeef");
+    assert_eq!(
+        buff,
+        Value::buff_from(vec![0xde, 0xad, 0xbe, 0xef]).unwrap()
+    );
+    assert_eq!(buff.to_string(), "0xdeadbeef");
+    assert_eq!(
+        TypeSignature::type_of(&buff),
+        TypeSignature::SequenceType(SequenceSubtype::BufferType(
+            BufferLength::try_from(4_u32).unwrap()
+        ))
+    );
+    assert_eq!(execute("(list 0x 0x01)").to_string(), "(0x 0x01)");
+
+    let err = vm_execute("(+ 1 0xdeadbeef)").unwrap_err();
+    assert_eq!(
+        err,
+        CheckErrors::TypeValueError(TypeSignature::IntType, buff).into()
+    );
+    match err {
+        Error::Unchecked(check_err) => assert_eq!(
+            check_err.message(),
+            "expecting expression of type 'int', found '0xdeadbeef'"
+        ),
+        _ => panic!("expected a check error, got {:?}", err),
+    }
+}
+
 #[test]
 fn test_some() {
     let tests = [
@@ -1397,6 +1879,59 @@ fn test_some() {
     }
 }
 
+#[test]
+fn test_response_and_optional_equality() {
+    let tests = [
+        "(is-eq (ok 1) (ok 1))",
+        "(is-eq (ok 1) (ok 2))",
+        "(is-eq (err 1) (err 1))",
+        "(is-eq (ok 1) (err 1))",
+        "(is-eq (err 1) (ok 1))",
+        "(is-eq (ok 1) (ok 1) (err 1))",
+    ];
+
+    let expectations = [
+        Value::Bool(true),
+        Value::Bool(false),
+        Value::Bool(true),
+        Value::Bool(false),
+        Value::Bool(false),
+        Value::Bool(false),
+    ];
+
+    for (program, expectation) in tests.iter().zip(expectations.iter()) {
+        assert_eq!(*expectation, vm_execute(program).unwrap().unwrap());
+    }
+
+    // an optional and a response never compare, even when they wrap the same value
+    let bad = [
+        "(is-eq (ok 1) (some 1))",
+        "(is-eq (some 1) (err 1))",
+        "(is-eq none (ok 1))",
+    ];
+    let bad_expected: &[Error] = &[
+        CheckErrors::TypeError(
+            TypeSignature::new_option(TypeSignature::IntType).unwrap(),
+            TypeSignature::new_response(TypeSignature::IntType, TypeSignature::NoType).unwrap(),
+        )
+        .into(),
+        CheckErrors::TypeError(
+            TypeSignature::new_response(TypeSignature::NoType, TypeSignature::IntType).unwrap(),
+            TypeSignature::new_option(TypeSignature::IntType).unwrap(),
+        )
+        .into(),
+        CheckErrors::TypeError(
+            TypeSignature::new_response(TypeSignature::IntType, TypeSignature::NoType).unwrap(),
+            TypeSignature::new_option(TypeSignature::NoType).unwrap(),
+        )
+        .into(),
+    ];
+
+    for (program, expectation) in bad.iter().zip(bad_expected.iter()) {
+        assert_eq!(*expectation, vm_execute(program).unwrap_err());
+    }
+}
+
 #[test]
 fn test_option_destructs() {
     let tests = [
@@ -1456,6 +1991,134 @@ fn test_option_destructs() {
     }
 }
 
+#[test]
+fn test_hash_input_normalization() {
+    // the digests given in the `sha256` docs
+    assert_eq!(
+        "0x374708fff7719dd5979ec875d56cd2286f6d3cf7ec317a3b25632aab28ec37bb",
+        vm_execute("(sha256 0)").unwrap().unwrap().to_string()
+    );
+    assert_eq!(
+        "0xe3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        vm_execute("(sha256 0x)").unwrap().unwrap().to_string()
+    );
+
+    for hash_fn in ["sha256", "sha512", "sha512/256", "keccak256", "hash160"] {
+        let run = |arg: &str| execute(&format!("({} {})", hash_fn, arg));
+
+        // ints and uints hash as their 16-byte little-endian form
+        let zero_bytes = run("0x00000000000000000000000000000000");
+        assert_eq!(run("0"), zero_bytes);
+        assert_eq!(run("u0"), zero_bytes);
+        assert_eq!(run("0x01000000000000000000000000000000"), run("1"));
+        assert_eq!(run("0x01000000000000000000000000000000"), run("u1"));
+        assert_eq!(run("0xffffffffffffffffffffffffffffffff"), run("-1"));
+
+        // the empty buffer is not the same as 0
+        assert_ne!(run("0x"), run("0"));
+
+        assert_eq!(
+            vm_execute(&format!("({} true)", hash_fn)).unwrap_err(),
+            CheckErrors::UnionTypeValueError(
+                vec![
+                    TypeSignature::IntType,
+                    TypeSignature::UIntType,
+                    TypeSignature::max_buffer(),
+                ],
+                Value::Bool(true),
+            )
+            .into()
+        );
+    }
+}
+
+#[test]
+fn test_hash_large_buffer_in_place() {
+    use crate::vm::util::hash::{hash_value, HashFunction};
+    use stacks_common::util::hash::{Keccak256Hash, Sha256Sum};
+
+    let bytes: Vec<u8> = (0..1_048_576u32).map(|i| (i % 251) as u8).collect();
+    let input = Value::buff_from(bytes.clone()).unwrap();
+
+    for version in [
+        ClarityVersion::Clarity1,
+        ClarityVersion::Clarity2,
+        ClarityVersion::Clarity3,
+    ] {
+        assert_eq!(
+            hash_value(HashFunction::Sha256, &input, &version).unwrap(),
+            Sha256Sum::from_data(&bytes).as_bytes().to_vec()
+        );
+        assert_eq!(
+            hash_value(HashFunction::Keccak256, &input, &version).unwrap(),
+            Keccak256Hash::from_data(&bytes).as_bytes().to_vec()
+        );
+    }
+
+    // the host API agrees with the natives
+    for (native, function) in [
+        ("hash160", HashFunction::Hash160),
+        ("sha256", HashFunction::Sha256),
+        ("sha512", HashFunction::Sha512),
+        ("sha512/256", HashFunction::Sha512Trunc256),
+        ("keccak256", HashFunction::Keccak256),
+    ] {
+        for (arg, value) in [("-1", Value::Int(-1)), ("0x0102", execute("0x0102"))] {
+            assert_eq!(
+                execute(&format!("({} {})", native, arg)),
+                Value::buff_from(hash_value(function, &value, &ClarityVersion::Clarity1).unwrap())
+                    .unwrap()
+            );
+        }
+    }
+    assert!(hash_value(
+        HashFunction::Sha256,
+        &Value::Bool(true),
+        &ClarityVersion::Clarity1
+    )
+    .is_err());
+}
+
+#[test]
+fn test_hash_structured_values() {
+    let run = |program: &str| vm_execute_v3(program).unwrap().unwrap();
+
+    // tuples are serialized in canonical field order, so construction order is irrelevant
+    assert_eq!(
+        run("(sha256 (tuple (a 1) (b 2)))"),
+        run("(sha256 (tuple (b 2) (a 1)))")
+    );
+    assert_eq!(
+        run("(sha256 (tuple (a 1) (b 2)))"),
+        run("(sha256 (unwrap-panic (to-consensus-buff? (tuple (a 1) (b 2)))))")
+    );
+    assert_eq!(
+        run("(keccak256 (list 1 2 3))"),
+        run("(keccak256 (unwrap-panic (to-consensus-buff? (list 1 2 3))))")
+    );
+    assert_eq!(
+        run("(hash160 (some \"abc\"))"),
+        run("(hash160 (unwrap-panic (to-consensus-buff? (some \"abc\"))))")
+    );
+    assert_eq!(
+        run("(sha512 true)"),
+        run("(sha512 (unwrap-panic (to-consensus-buff? true)))")
+    );
+
+    // int, uint and buffer inputs hash exactly as they do in Clarity1
+    for program in ["(sha256 -1)", "(sha512/256 u7)", "(hash160 0x0102)"] {
+        assert_eq!(run(program), vm_execute(program).unwrap().unwrap());
+    }
+
+    // before Clarity3, only ints, uints and buffers can be hashed
+    for execute in [vm_execute, vm_execute_v2] {
+        assert!(matches!(
+            execute("(sha256 (list 1 2))").unwrap_err(),
+            Error::Unchecked(CheckErrors::UnionTypeValueError(..))
+        ));
+    }
+}
+
 #[test]
 fn test_hash_errors() {
     let tests = [
@@ -1561,6 +2224,29 @@ fn test_bool_functions() {
         .for_each(|(program, expectation)| assert_eq!(expectation.clone(), execute(program)));
 }
 
+#[test]
+fn test_bool_functions_short_circuit_effects() {
+    let tests = [
+        "(or true (var-set cursor 5))",
+        "(and false (var-set cursor 5))",
+        "(or false true (var-set cursor 5))",
+        "(and true false (var-set cursor 5))",
+        // the deciding operand itself still runs
+        "(or false (var-set cursor 5))",
+        "(and true (var-set cursor 5))",
+    ];
+
+    let expectations = [0, 0, 0, 0, 5, 5];
+
+    for (program, expectation) in tests.iter().zip(expectations.iter()) {
+        let program = format!(
+            "(define-data-var cursor int 0) {} (var-get cursor)",
+            program
+        );
+        assert_eq!(Value::Int(*expectation), execute(&program), "{}", program);
+    }
+}
+
 #[test]
 fn test_bad_lets() {
     let tests = [
@@ -1589,6 +2275,82 @@ fn test_bad_lets() {
         });
 }
 
+#[test]
+fn test_reserved_names() {
+    let tests = [
+        ("(define-data-var tx-sender int 0)", "tx-sender"),
+        ("(let ((block-height 1)) block-height)", "block-height"),
+        (
+            "(define-private (f (stx-transfer? int)) 1)",
+            "stx-transfer?",
+        ),
+        ("(match (some 1) let let 0)", "let"),
+    ];
+    for (program, name) in tests.iter() {
+        let expected: Error = CheckErrors::NameAlreadyUsed(name.to_string()).into();
+        assert_eq!(vm_execute(program).unwrap_err(), expected);
+        assert_eq!(vm_execute_v2(program).unwrap_err(), expected);
+    }
+
+    // the define forms are reserved since Clarity3
+    let tests = [
+        ("(define-constant define-map 1)", "define-map"),
+        ("(let ((define-public 1)) define-public)", "define-public"),
+        (
+            "(define-private (f (impl-trait int)) impl-trait)",
+            "impl-trait",
+        ),
+    ];
+    for (program, name) in tests.iter() {
+        assert_eq!(
+            vm_execute_v3(program).unwrap_err(),
+            CheckErrors::NameAlreadyUsed(name.to_string()).into()
+        );
+        assert!(vm_execute(program).is_ok());
+        assert!(vm_execute_v2(program).is_ok());
+    }
+}
+
+#[test]
+fn test_unwrap_or_else() {
+    let defines = "(define-data-var called bool false)
+        (define-private (fallback) (begin (var-set called true) 0))";
+
+    // the fallback only runs when the optional is none
+    let tests = [
+        (
+            "{ value: (unwrap-or-else (some 5) fallback), called: (var-get called) }",
+            "{ value: 5, called: false }",
+        ),
+        (
+            "{ value: (unwrap-or-else none fallback), called: (var-get called) }",
+            "{ value: 0, called: true }",
+        ),
+    ];
+    for (program, expected) in tests.iter() {
+        let program = format!("{} {}", defines, program);
+        assert_eq!(
+            vm_execute_v3(&program).unwrap(),
+            vm_execute_v3(expected).unwrap()
+        );
+    }
+
+    assert!(matches!(
+        vm_execute_v3("(unwrap-or-else 5 zero)").unwrap_err(),
+        Error::Unchecked(CheckErrors::ExpectedOptionalValue(_))
+    ));
+    assert!(matches!(
+        vm_execute_v3("(unwrap-or-else none undefined)").unwrap_err(),
+        Error::Unchecked(CheckErrors::UndefinedFunction(_))
+    ));
+
+    // unwrap-or-else is only available from Clarity3
+    assert_eq!(
+        vm_execute_v2("(unwrap-or-else none undefined)").unwrap_err(),
+        CheckErrors::UndefinedFunction("unwrap-or-else".to_string()).into()
+    );
+}
+
 #[test]
 fn test_lets() {
     let tests = [
@@ -1603,6 +2365,268 @@ fn test_lets() {
         .for_each(|(program, expectation)| assert_eq!(expectation.clone(), execute(program)));
 }
 
+#[test]
+fn test_data_vars_of_every_type() {
+    let tests = [
+        "(define-data-var flag bool false) (var-set flag true) (var-get flag)",
+        "(define-data-var bytes (buff 4) 0x) (var-set bytes 0x01020304) (var-get bytes)",
+        "(define-data-var maybe (optional uint) none) (var-set maybe (some u5)) (var-get maybe)",
+        "(define-data-var outcome (response int bool) (ok 1)) (var-set outcome (err false)) (var-get outcome)",
+        "(define-data-var pair { a: int, b: (string-ascii 3) } { a: 0, b: \"\" })
+         (var-set pair { b: \"abc\", a: -1 })
+         (var-get pair)",
+        "(define-data-var owner principal tx-sender)
+         (var-set owner 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR.contract)
+         (var-get owner)",
+    ];
+
+    let expectations = [
+        Value::Bool(true),
+        Value::buff_from(vec![1, 2, 3, 4]).unwrap(),
+        Value::some(Value::UInt(5)).unwrap(),
+        Value::error(Value::Bool(false)).unwrap(),
+        Value::from(
+            TupleData::from_data(vec![
+                ("a".into(), Value::Int(-1)),
+                (
+                    "b".into(),
+                    Value::string_ascii_from_bytes(b"abc".to_vec()).unwrap(),
+                ),
+            ])
+            .unwrap(),
+        ),
+        Value::from(
+            PrincipalData::parse("SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR.contract").unwrap(),
+        ),
+    ];
+
+    for (program, expectation) in tests.iter().zip(expectations.iter()) {
+        assert_eq!(expectation, &execute(program));
+    }
+
+    assert_eq!(
+        vm_execute(
+            "(define-data-var pair { a: int, b: (string-ascii 3) } { a: 0, b: \"\" })
+             (var-set pair { a: u1, b: \"abc\" })"
+        )
+        .unwrap_err(),
+        CheckErrors::TypeValueError(
+            TypeSignature::TupleType(
+                TupleTypeSignature::try_from(vec![
+                    ("a".into(), TypeSignature::IntType),
+                    (
+                        "b".into(),
+                        TypeSignature::SequenceType(SequenceSubtype::StringType(
+                            StringSubtype::ASCII(3_u32.try_into().unwrap())
+                        ))
+                    ),
+                ])
+                .unwrap()
+            ),
+            Value::from(
+                TupleData::from_data(vec![
+                    ("a".into(), Value::UInt(1)),
+                    (
+                        "b".into(),
+                        Value::string_ascii_from_bytes(b"abc".to_vec()).unwrap()
+                    ),
+                ])
+                .unwrap()
+            )
+        )
+        .into()
+    );
+}
+
+#[test]
+fn test_data_var_type_mismatch() {
+    assert_eq!(
+        vm_execute("(define-data-var cursor int true)").unwrap_err(),
+        CheckErrors::TypeValueError(TypeSignature::IntType, Value::Bool(true)).into()
+    );
+    assert_eq!(
+        vm_execute("(define-data-var cursor int 0) (var-set cursor u1)").unwrap_err(),
+        CheckErrors::TypeValueError(TypeSignature::IntType, Value::UInt(1)).into()
+    );
+    assert_eq!(
+        vm_execute(
+            "(define-data-var cursor (list 2 int) (list 1 2)) (var-set cursor (list 1 2 3))"
+        )
+        .unwrap_err(),
+        CheckErrors::TypeValueError(
+            TypeSignature::list_of(TypeSignature::IntType, 2).unwrap(),
+            Value::list_from(vec![Value::Int(1), Value::Int(2), Value::Int(3)]).unwrap()
+        )
+        .into()
+    );
+}
+
+/// A backing store that records every key it is asked to persist.
+struct RecordingBackingStore {
+    inner: MemoryBackingStore,
+    written: Vec<String>,
+    read: Vec<String>,
+}
+
+impl ClarityBackingStore for RecordingBackingStore {
+    fn put_all(&mut self, items: Vec<(String, String)>) {
+        self.written
+            .extend(items.iter().map(|(key, _)| key.clone()));
+        self.inner.put_all(items)
+    }
+
+    fn get(&mut self, key: &str) -> Option<String> {
+        self.read.push(key.to_string());
+        self.inner.get(key)
+    }
+
+    fn get_with_proof(&mut self, key: &str) -> Option<(String, Vec<u8>)> {
+        self.inner.get_with_proof(key)
+    }
+
+    fn set_block_hash(&mut self, bhh: StacksBlockId) -> Result<StacksBlockId, Error> {
+        self.inner.set_block_hash(bhh)
+    }
+
+    fn get_block_at_height(&mut self, height: u32) -> Option<StacksBlockId> {
+        self.inner.get_block_at_height(height)
+    }
+
+    fn get_current_block_height(&mut self) -> u32 {
+        self.inner.get_current_block_height()
+    }
+
+    fn get_open_chain_tip_height(&mut self) -> u32 {
+        self.inner.get_open_chain_tip_height()
+    }
+
+    fn get_open_chain_tip(&mut self) -> StacksBlockId {
+        self.inner.get_open_chain_tip()
+    }
+
+    fn get_side_store(&mut self) -> &Connection {
+        self.inner.get_side_store()
+    }
+}
+
+fn execute_over_store(program: &str, store: &mut dyn ClarityBackingStore) -> Value {
+    let contract_id = QualifiedContractIdentifier::transient();
+    let mut contract_context = ContractContext::new(contract_id.clone(), ClarityVersion::Clarity2);
+    let mut global_context = GlobalContext::with_database(store, StacksEpochId::Epoch21);
+    global_context
+        .execute(|g| {
+            let parsed = build_ast_with_rules(
+                &contract_id,
+                program,
+                &mut (),
+                ClarityVersion::Clarity2,
+                StacksEpochId::Epoch21,
+                ASTRules::PrecheckSize,
+            )?;
+            eval_all(&parsed.expressions, &mut contract_context, g, None)
+        })
+        .unwrap()
+        .unwrap()
+}
+
+#[test]
+fn test_lets_over_any_backing_store() {
+    let program = "(define-data-var cursor int 0) (let ((a 1) (b 2)) (var-set cursor a) (var-set cursor (+ b (var-get cursor))) (var-get cursor))";
+
+    let mut memory = MemoryBackingStore::new();
+    assert_eq!(execute_over_store(program, &mut memory), Value::Int(3));
+
+    let mut recording = RecordingBackingStore {
+        inner: MemoryBackingStore::new(),
+        written: vec![],
+        read: vec![],
+    };
+    assert_eq!(execute_over_store(program, &mut recording), Value::Int(3));
+    assert!(recording
+        .written
+        .iter()
+        .any(|key| key.ends_with("::cursor")));
+}
+
+#[test]
+fn test_read_cache() {
+    let mut recording = RecordingBackingStore {
+        inner: MemoryBackingStore::new(),
+        written: vec![],
+        read: vec![],
+    };
+    let contract_id = QualifiedContractIdentifier::transient();
+    let mut contract_context = ContractContext::new(contract_id.clone(), ClarityVersion::Clarity2);
+
+    {
+        let mut global_context =
+            GlobalContext::with_database(&mut recording, StacksEpochId::Epoch21);
+        global_context.set_read_cache_capacity(Some(16));
+        let mut run = |g: &mut GlobalContext, program: &str| {
+            let parsed = build_ast_with_rules(
+                &contract_id,
+                program,
+                &mut (),
+                ClarityVersion::Clarity2,
+                StacksEpochId::Epoch21,
+                ASTRules::PrecheckSize,
+            )
+            .unwrap();
+            eval_all(&parsed.expressions, &mut contract_context, g, None)
+                .unwrap()
+                .unwrap_or(Value::none())
+        };
+
+        global_context
+            .execute(|g| {
+                run(
+                    g,
+                    "(define-data-var cursor int 7) (define-map m int int) (map-set m 1 10)",
+                );
+                Ok(())
+            })
+            .unwrap();
+
+        global_context
+            .execute(|g| {
+                // repeated reads return the cached value
+                for _ in 0..3 {
+                    assert_eq!(run(g, "(var-get cursor)"), Value::Int(7));
+                    assert_eq!(
+                        run(g, "(map-get? m 1)"),
+                        Value::some(Value::Int(10)).unwrap()
+                    );
+                }
+
+                // a write evicts the cached value
+                assert_eq!(run(g, "(var-set cursor 8) (var-get cursor)"), Value::Int(8));
+                assert_eq!(
+                    run(g, "(map-set m 1 11) (map-get? m 1)"),
+                    Value::some(Value::Int(11)).unwrap()
+                );
+
+                // a rolled-back write restores the prior value
+                g.begin();
+                assert_eq!(run(g, "(var-set cursor 9) (var-get cursor)"), Value::Int(9));
+                g.roll_back();
+                assert_eq!(run(g, "(var-get cursor)"), Value::Int(8));
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    // each key was read from the store once, before it was written
+    let reads_of = |name: &str| {
+        recording
+            .read
+            .iter()
+            .filter(|key| key.starts_with("vm::") && key.split("::").nth(3) == Some(name))
+            .count()
+    };
+    assert_eq!(reads_of("cursor"), 1);
+    assert_eq!(reads_of("m"), 1);
+}
+
 #[test]
 // tests that the type signature of the result of a merge tuple is updated.
 //  this is required to pass the type admission checks of, e.g., data store
@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+pub mod parse;
 pub mod serialization;
 pub mod signatures;
 
@@ -25,6 +26,7 @@ use std::{cmp, fmt};
 use regex::Regex;
 
 use stacks_common::address::c32;
+use stacks_common::address::Error as C32Error;
 use stacks_common::types::StacksEpochId;
 use stacks_common::util::hash;
 
@@ -486,7 +488,24 @@ impl SequenceData {
         Ok(())
     }
 
+    /// Returns the elements in `[left_position, right_position)`, or `BadIndex` if that
+    ///  range is not within the sequence.
     pub fn slice(self, left_position: usize, right_position: usize) -> Result<Value> {
+        let len = self.len();
+        if right_position > len {
+            return Err(RuntimeErrorType::BadIndex {
+                index: right_position,
+                len,
+            }
+            .into());
+        }
+        if left_position > right_position {
+            return Err(RuntimeErrorType::BadIndex {
+                index: left_position,
+                len,
+            }
+            .into());
+        }
         let empty_seq = left_position == right_position;
 
         let result = match self {
@@ -807,6 +826,16 @@ impl PartialEq for TupleData {
 
 pub const NONE: Value = Value::Optional(OptionalData { data: None });
 
+/// Callbacks for `Value::visit`, which walks a value depth-first: `enter` is called on
+///  each value before its children (the contents of an optional or response, the values
+///  of a tuple in field name order, and the items of a list), and `leave` after them.
+///  Buffers and strings are single values, their bytes and characters are not visited.
+pub trait ValueVisitor {
+    fn enter(&mut self, value: &Value);
+
+    fn leave(&mut self, _value: &Value) {}
+}
+
 impl Value {
     pub fn some(data: Value) -> Result<Value> {
         if data.size() + WRAPPER_VALUE_SIZE > MAX_VALUE_SIZE {
@@ -824,6 +853,10 @@ impl Value {
         NONE.clone()
     }
 
+    pub fn bool(value: bool) -> Value {
+        Value::Bool(value)
+    }
+
     pub fn okay_true() -> Value {
         Value::Response(ResponseData {
             committed: true,
@@ -879,6 +912,27 @@ impl Value {
         TypeSignature::type_of(self).depth()
     }
 
+    /// Walk this value and everything it contains, see `ValueVisitor`.
+    pub fn visit<V: ValueVisitor>(&self, visitor: &mut V) {
+        visitor.enter(self);
+        match self {
+            Value::Optional(OptionalData { data: Some(inner) }) => inner.visit(visitor),
+            Value::Response(ResponseData { data, .. }) => data.visit(visitor),
+            Value::Tuple(TupleData { data_map, .. }) => {
+                for field_value in data_map.values() {
+                    field_value.visit(visitor);
+                }
+            }
+            Value::Sequence(SequenceData::List(ListData { data, .. })) => {
+                for item in data.iter() {
+                    item.visit(visitor);
+                }
+            }
+            _ => {}
+        }
+        visitor.leave(self);
+    }
+
     /// Invariant: the supplied Values have already been "checked", i.e., it's a valid Value object
     ///  this invariant is enforced through the Value constructors, each of which checks to ensure
     ///  that any typing data is correct.
@@ -1174,6 +1228,7 @@ impl BuffData {
     }
 
     fn append(&mut self, other_seq: &mut BuffData) -> Result<()> {
+        check_appended_size(self.data.len() + other_seq.data.len(), 1)?;
         self.data.append(&mut other_seq.data);
         Ok(())
     }
@@ -1183,6 +1238,20 @@ impl BuffData {
     }
 }
 
+/// Checks that a buffer or string of `len` elements, each taking up `element_size`
+///  bytes in its type, still fits within `MAX_VALUE_SIZE`.
+fn check_appended_size(len: usize, element_size: u64) -> Result<()> {
+    let actual = (len as u64).saturating_mul(element_size);
+    if actual > u64::from(MAX_VALUE_SIZE) {
+        return Err(RuntimeErrorType::ValueTooLarge {
+            actual,
+            max: u64::from(MAX_VALUE_SIZE),
+        }
+        .into());
+    }
+    Ok(())
+}
+
 impl ListData {
     pub fn len(&self) -> u32 {
         self.data.len().try_into().unwrap()
@@ -1201,6 +1270,7 @@ impl ListData {
 
 impl ASCIIData {
     fn append(&mut self, other_seq: &mut ASCIIData) -> Result<()> {
+        check_appended_size(self.data.len() + other_seq.data.len(), 1)?;
         self.data.append(&mut other_seq.data);
         Ok(())
     }
@@ -1212,6 +1282,8 @@ impl ASCIIData {
 
 impl UTF8Data {
     fn append(&mut self, other_seq: &mut UTF8Data) -> Result<()> {
+        // string-utf8 types are sized at 4 bytes per character
+        check_appended_size(self.data.len() + other_seq.data.len(), 4)?;
         self.data.append(&mut other_seq.data);
         Ok(())
     }
@@ -1278,6 +1350,48 @@ impl fmt::Display for Value {
     }
 }
 
+/// Parses the decimal form produced by `Display` for integers: `-?[0-9]+` for
+///  `Value::Int` and `u[0-9]+` for `Value::UInt`. Out-of-range input is rejected
+///  rather than wrapped. Other kinds of values are not parsed by this impl.
+impl str::FromStr for Value {
+    type Err = crate::vm::errors::Error;
+
+    fn from_str(input: &str) -> Result<Value> {
+        let (digits, is_uint, is_negative) = if let Some(rest) = input.strip_prefix('u') {
+            (rest, true, false)
+        } else if let Some(rest) = input.strip_prefix('-') {
+            (rest, false, true)
+        } else {
+            (input, false, false)
+        };
+
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(RuntimeErrorType::ParseError(format!(
+                "Invalid integer literal: {}",
+                input
+            ))
+            .into());
+        }
+
+        let out_of_range = || {
+            RuntimeErrorType::ParseError(format!("Integer literal out of range: {}", input)).into()
+        };
+        if is_uint {
+            digits
+                .parse::<u128>()
+                .map(Value::UInt)
+                .map_err(|_| out_of_range())
+        } else {
+            // parse the sign along with the digits, so that i128::MIN is representable
+            let signed = if is_negative { input } else { digits };
+            signed
+                .parse::<i128>()
+                .map(Value::Int)
+                .map_err(|_| out_of_range())
+        }
+    }
+}
+
 impl PrincipalData {
     pub fn version(&self) -> u8 {
         match self {
@@ -1307,14 +1421,13 @@ impl PrincipalData {
     }
 
     pub fn parse_standard_principal(literal: &str) -> Result<StandardPrincipalData> {
-        let (version, data) = c32::c32_address_decode(&literal).map_err(|x| {
-            RuntimeErrorType::ParseError(format!("Invalid principal literal: {}", x))
+        let (version, data) = c32::c32_address_decode(literal).map_err(|e| match e {
+            C32Error::BadChecksum(..) => PrincipalParseError::BadChecksum,
+            C32Error::InvalidVersion(version) => PrincipalParseError::BadVersion(version),
+            _ => PrincipalParseError::InvalidEncoding,
         })?;
         if data.len() != 20 {
-            return Err(RuntimeErrorType::ParseError(
-                "Invalid principal literal: Expected 20 data bytes.".to_string(),
-            )
-            .into());
+            return Err(PrincipalParseError::BadLength(data.len()).into());
         }
         let mut fixed_data = [0; 20];
         fixed_data.copy_from_slice(&data[..20]);
@@ -1322,6 +1435,26 @@ impl PrincipalData {
     }
 }
 
+/// Why a string could not be parsed as a standard principal.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrincipalParseError {
+    /// Not a c32check address: too short, or contains non-c32 characters.
+    InvalidEncoding,
+    /// The checksum does not match the address's version and hash bytes.
+    BadChecksum,
+    /// The version byte cannot be c32-encoded. Any version a c32 character
+    ///  encodes (0-31) is accepted, matching principal literals in Clarity.
+    BadVersion(u8),
+    /// The address decoded to this many hash bytes rather than 20.
+    BadLength(usize),
+}
+
+impl From<PrincipalParseError> for crate::vm::errors::Error {
+    fn from(err: PrincipalParseError) -> Self {
+        RuntimeErrorType::BadPrincipal(err).into()
+    }
+}
+
 impl StandardPrincipalData {
     pub fn to_address(&self) -> String {
         c32::c32_address(self.0, &self.1[..]).unwrap_or_else(|_| "INVALID_C32_ADD".to_string())
@@ -1543,6 +1676,74 @@ pub fn byte_len_of_serialization(serialized: &str) -> u64 {
 #[cfg(test)]
 mod test {
     use super::*;
+
+    #[test]
+    fn test_visit() {
+        #[derive(Default)]
+        struct Counter {
+            nodes: usize,
+            depth: usize,
+            max_depth: usize,
+            ints: Vec<i128>,
+        }
+
+        impl ValueVisitor for Counter {
+            fn enter(&mut self, value: &Value) {
+                self.nodes += 1;
+                self.depth += 1;
+                self.max_depth = self.max_depth.max(self.depth);
+                if let Value::Int(i) = value {
+                    self.ints.push(*i);
+                }
+            }
+
+            fn leave(&mut self, _value: &Value) {
+                self.depth -= 1;
+            }
+        }
+
+        // (some (list (tuple (a 1))))
+        let tuple = Value::from(TupleData::from_data(vec![("a".into(), Value::Int(1))]).unwrap());
+        let value = Value::some(Value::list_from(vec![tuple]).unwrap()).unwrap();
+        let mut counter = Counter::default();
+        value.visit(&mut counter);
+        assert_eq!(counter.nodes, 4);
+        assert_eq!(counter.max_depth, 4);
+        assert_eq!(counter.depth, 0);
+
+        // tuple values in field name order, then list items in order
+        let value = Value::okay(
+            Value::list_from(vec![
+                Value::from(
+                    TupleData::from_data(vec![
+                        ("b".into(), Value::Int(2)),
+                        ("a".into(), Value::Int(1)),
+                    ])
+                    .unwrap(),
+                ),
+                Value::from(
+                    TupleData::from_data(vec![
+                        ("a".into(), Value::Int(3)),
+                        ("b".into(), Value::Int(4)),
+                    ])
+                    .unwrap(),
+                ),
+            ])
+            .unwrap(),
+        )
+        .unwrap();
+        let mut counter = Counter::default();
+        value.visit(&mut counter);
+        assert_eq!(counter.ints, vec![1, 2, 3, 4]);
+        assert_eq!(counter.nodes, 8);
+
+        // leaves
+        let mut counter = Counter::default();
+        Value::buff_from(vec![1, 2, 3]).unwrap().visit(&mut counter);
+        Value::none().visit(&mut counter);
+        assert_eq!(counter.nodes, 2);
+        assert_eq!(counter.max_depth, 1);
+    }
     #[test]
     fn test_constructors() {
         assert_eq!(
@@ -1694,6 +1895,81 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_constructors_match_natives() {
+        use crate::vm::execute;
+
+        let cases = [
+            ("none", Value::none()),
+            ("(some 1)", Value::some(Value::Int(1)).unwrap()),
+            ("(ok 1)", Value::okay(Value::Int(1)).unwrap()),
+            ("(err u2)", Value::error(Value::UInt(2)).unwrap()),
+            ("(err none)", Value::err_none()),
+            ("(ok true)", Value::okay_true()),
+            ("true", Value::bool(true)),
+            ("(is-eq 1 2)", Value::bool(false)),
+        ];
+        for (program, expected) in cases {
+            assert_eq!(execute(program).unwrap().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_int_string_round_trip() {
+        for value in [
+            Value::Int(i128::MIN),
+            Value::Int(i128::MAX),
+            Value::Int(0),
+            Value::UInt(u128::MAX),
+            Value::UInt(0),
+        ] {
+            let as_string = value.to_string();
+            assert_eq!(as_string.parse::<Value>().unwrap(), value);
+        }
+
+        assert_eq!(
+            "-170141183460469231731687303715884105728"
+                .parse::<Value>()
+                .unwrap(),
+            Value::Int(i128::MIN)
+        );
+        assert_eq!(
+            "u340282366920938463463374607431768211455"
+                .parse::<Value>()
+                .unwrap(),
+            Value::UInt(u128::MAX)
+        );
+
+        // out of range
+        "170141183460469231731687303715884105728"
+            .parse::<Value>()
+            .unwrap_err();
+        "-170141183460469231731687303715884105729"
+            .parse::<Value>()
+            .unwrap_err();
+        "u340282366920938463463374607431768211456"
+            .parse::<Value>()
+            .unwrap_err();
+
+        // a sign is only valid for ints
+        "u-1".parse::<Value>().unwrap_err();
+        "-u1".parse::<Value>().unwrap_err();
+        // not decimal integers
+        for bad in ["", "-", "u", "+1", "1.0", "0x10", " 1", "true"] {
+            assert!(
+                matches!(
+                    bad.parse::<Value>(),
+                    Err(crate::vm::errors::Error::Runtime(
+                        RuntimeErrorType::ParseError(_),
+                        _
+                    ))
+                ),
+                "{:?} should not parse",
+                bad
+            );
+        }
+    }
+
     #[test]
     fn expect_buff() {
         let buff = Value::Sequence(SequenceData::Buffer(BuffData {
@@ -1720,4 +1996,125 @@ mod test {
         }));
         let _ = buff.expect_buff(4);
     }
+
+    #[test]
+    fn test_sequence_bounds_errors() {
+        let buff = || match Value::buff_from(vec![1, 2, 3, 4]).unwrap() {
+            Value::Sequence(data) => data,
+            _ => unreachable!(),
+        };
+
+        assert_eq!(
+            buff().slice(1, 3).unwrap(),
+            Value::buff_from(vec![2, 3]).unwrap()
+        );
+        assert_eq!(
+            buff().slice(4, 4).unwrap(),
+            Value::buff_from(vec![]).unwrap()
+        );
+        assert_eq!(
+            buff().slice(2, 10).unwrap_err(),
+            RuntimeErrorType::BadIndex { index: 10, len: 4 }.into()
+        );
+        assert_eq!(
+            buff().slice(3, 2).unwrap_err(),
+            RuntimeErrorType::BadIndex { index: 3, len: 4 }.into()
+        );
+
+        let half = MAX_VALUE_SIZE as usize / 2;
+        let mut left = SequenceData::Buffer(BuffData {
+            data: vec![0; half],
+        });
+        let mut right = SequenceData::Buffer(BuffData {
+            data: vec![0; half],
+        });
+        left.append(&StacksEpochId::latest(), &mut right).unwrap();
+        assert_eq!(left.len(), MAX_VALUE_SIZE as usize);
+
+        let mut one_more = SequenceData::Buffer(BuffData { data: vec![0] });
+        assert_eq!(
+            left.append(&StacksEpochId::latest(), &mut one_more)
+                .unwrap_err(),
+            RuntimeErrorType::ValueTooLarge {
+                actual: u64::from(MAX_VALUE_SIZE) + 1,
+                max: u64::from(MAX_VALUE_SIZE),
+            }
+            .into()
+        );
+
+        // string-utf8 is sized at 4 bytes per character
+        let mut utf8 = SequenceData::String(CharType::UTF8(UTF8Data {
+            data: vec![vec![b'a']; MAX_VALUE_SIZE as usize / 4],
+        }));
+        let mut one_more = SequenceData::String(CharType::UTF8(UTF8Data {
+            data: vec![vec![b'b']],
+        }));
+        assert_eq!(
+            utf8.append(&StacksEpochId::latest(), &mut one_more)
+                .unwrap_err(),
+            RuntimeErrorType::ValueTooLarge {
+                actual: u64::from(MAX_VALUE_SIZE) + 4,
+                max: u64::from(MAX_VALUE_SIZE),
+            }
+            .into()
+        );
+    }
+
+    #[test]
+    fn test_parse_principal() {
+        let standard = "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7";
+        let contract = "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.counter";
+        for literal in [standard, contract] {
+            let principal = PrincipalData::parse(literal).unwrap();
+            assert_eq!(principal.to_string(), literal);
+            assert_eq!(PrincipalData::parse(&principal.to_string()), Ok(principal));
+        }
+        assert_eq!(
+            PrincipalData::parse(standard).unwrap(),
+            PrincipalData::Standard(StandardPrincipalData(22, {
+                let mut bytes = [0; 20];
+                bytes.copy_from_slice(
+                    &hash::hex_bytes("a46ff88886c2ef9762d970b4d2c63678835bd39d").unwrap(),
+                );
+                bytes
+            }))
+        );
+        match PrincipalData::parse(contract).unwrap() {
+            PrincipalData::Contract(id) => {
+                assert_eq!(id.issuer.0, 26);
+                assert_eq!(id.name.as_str(), "counter");
+            }
+            other => panic!("expected a contract principal, found {}", other),
+        }
+
+        let errors = [
+            // last character corrupted
+            (
+                "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ8",
+                PrincipalParseError::BadChecksum,
+            ),
+            (
+                "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ8.counter",
+                PrincipalParseError::BadChecksum,
+            ),
+            // a 19-byte hash with a valid checksum
+            (
+                &c32::c32_address(22, &[1; 19]).unwrap(),
+                PrincipalParseError::BadLength(19),
+            ),
+            (
+                "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ!",
+                PrincipalParseError::InvalidEncoding,
+            ),
+            ("SP", PrincipalParseError::InvalidEncoding),
+        ];
+        for (literal, expected) in errors.iter() {
+            assert_eq!(
+                PrincipalData::parse(literal),
+                Err(RuntimeErrorType::BadPrincipal(expected.clone()).into()),
+                "{}",
+                literal
+            );
+        }
+    }
 }
@@ -0,0 +1,311 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Type-directed parsing of user supplied values, for use by the CLI and
+//! test harnesses. Unlike `vm_execute`, nothing is evaluated: the input must
+//! be a literal (or a nesting of `some`/`ok`/`err`/`list`/tuple literals),
+//! and the expected `TypeSignature` drives how each piece is read.
+
+use stacks_common::types::StacksEpochId;
+
+use crate::vm::ast::parser::v2::parse;
+use crate::vm::errors::{CheckErrors, Error, InterpreterResult as Result, RuntimeErrorType};
+use crate::vm::representations::{PreSymbolicExpression, PreSymbolicExpressionType};
+use crate::vm::types::{
+    ListTypeData, PrincipalData, SequenceSubtype, TupleData, TupleTypeSignature, TypeSignature,
+    Value,
+};
+
+/// Parse `input` as a value of type `ty`.
+///
+/// Principals may be given with or without the leading `'`, and booleans,
+/// `none`, `(some ..)`, `(ok ..)`, `(err ..)`, `(list ..)` and `{ key: value }`
+/// are read recursively against the corresponding part of `ty`.
+///
+/// # Errors
+/// - `RuntimeErrorType::ParseError` if the input cannot be read as `ty`
+/// - `CheckErrors::TypeValueError` if a literal is not admitted by `ty`
+pub fn parse_value_for_type(input: &str, ty: &TypeSignature) -> Result<Value> {
+    let input = input.trim();
+    if let TypeSignature::PrincipalType = ty {
+        let literal = input.strip_prefix('\'').unwrap_or(input);
+        if let Ok(principal) = PrincipalData::parse(literal) {
+            return Ok(Value::Principal(principal));
+        }
+    }
+
+    let mut exprs: Vec<_> = parse(input)?
+        .into_iter()
+        .filter(|expr| !matches!(expr.pre_expr, PreSymbolicExpressionType::Comment(_)))
+        .collect();
+    if exprs.len() != 1 {
+        return Err(RuntimeErrorType::ParseError(format!(
+            "Expected a single value of type {}, found `{}`",
+            ty, input
+        ))
+        .into());
+    }
+    let expr = exprs.remove(0);
+    value_from_expression(&expr, ty)
+}
+
+fn value_from_expression(expr: &PreSymbolicExpression, ty: &TypeSignature) -> Result<Value> {
+    use crate::vm::representations::PreSymbolicExpressionType::*;
+
+    match (&expr.pre_expr, ty) {
+        (AtomValue(value), _) => {
+            if ty.admits(&StacksEpochId::latest(), value)? {
+                Ok(value.clone())
+            } else {
+                Err(CheckErrors::TypeValueError(ty.clone(), value.clone()).into())
+            }
+        }
+        (Atom(name), TypeSignature::BoolType) if name.as_str() == "true" => Ok(Value::Bool(true)),
+        (Atom(name), TypeSignature::BoolType) if name.as_str() == "false" => Ok(Value::Bool(false)),
+        (Atom(name), TypeSignature::OptionalType(_)) if name.as_str() == "none" => {
+            Ok(Value::none())
+        }
+        (Atom(name), TypeSignature::PrincipalType) => PrincipalData::parse(name.as_str())
+            .map(Value::Principal)
+            .map_err(|_| unparsable(expr, ty)),
+        (List(items), _) => value_from_application(expr, items, ty),
+        (Tuple(items), TypeSignature::TupleType(tuple_type)) => {
+            tuple_from_expressions(expr, items, tuple_type)
+        }
+        _ => Err(unparsable(expr, ty)),
+    }
+}
+
+fn value_from_application(
+    expr: &PreSymbolicExpression,
+    items: &[PreSymbolicExpression],
+    ty: &TypeSignature,
+) -> Result<Value> {
+    let (name, args) = match items.split_first() {
+        Some((first, args)) => match first.match_atom() {
+            Some(name) => (name.as_str(), args),
+            None => return Err(unparsable(expr, ty)),
+        },
+        None => return Err(unparsable(expr, ty)),
+    };
+
+    match (name, args, ty) {
+        ("some", [arg], TypeSignature::OptionalType(inner)) => {
+            Value::some(value_from_expression(arg, inner)?)
+        }
+        ("ok", [arg], TypeSignature::ResponseType(inner)) => {
+            Value::okay(value_from_expression(arg, &inner.0)?)
+        }
+        ("err", [arg], TypeSignature::ResponseType(inner)) => {
+            Value::error(value_from_expression(arg, &inner.1)?)
+        }
+        ("list", _, TypeSignature::SequenceType(SequenceSubtype::ListType(list_type))) => {
+            list_from_expressions(args, list_type)
+        }
+        ("tuple", _, TypeSignature::TupleType(tuple_type)) => {
+            let mut pairs = vec![];
+            for arg in args.iter() {
+                match arg.match_list() {
+                    Some([key, value]) => {
+                        pairs.push(key.clone());
+                        pairs.push(value.clone());
+                    }
+                    _ => return Err(unparsable(expr, ty)),
+                }
+            }
+            tuple_from_expressions(expr, &pairs, tuple_type)
+        }
+        _ => Err(unparsable(expr, ty)),
+    }
+}
+
+fn list_from_expressions(
+    items: &[PreSymbolicExpression],
+    list_type: &ListTypeData,
+) -> Result<Value> {
+    let item_type = list_type.get_list_item_type();
+    let values = items
+        .iter()
+        .map(|item| value_from_expression(item, item_type))
+        .collect::<Result<Vec<_>>>()?;
+    if values.len() > list_type.get_max_len() as usize {
+        return Err(CheckErrors::ConstructedListTooLarge.into());
+    }
+    Value::list_with_type(&StacksEpochId::latest(), values, list_type.clone())
+}
+
+fn tuple_from_expressions(
+    expr: &PreSymbolicExpression,
+    items: &[PreSymbolicExpression],
+    tuple_type: &TupleTypeSignature,
+) -> Result<Value> {
+    let pairs = items.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return Err(CheckErrors::TupleExpectsPairs.into());
+    }
+    let mut fields = vec![];
+    for pair in pairs {
+        let name = pair[0].match_atom().ok_or(CheckErrors::BadTupleFieldName)?;
+        let field_type = tuple_type
+            .field_type(name)
+            .ok_or_else(|| CheckErrors::NoSuchTupleField(name.to_string(), tuple_type.clone()))?;
+        fields.push((name.clone(), value_from_expression(&pair[1], field_type)?));
+    }
+
+    let value = Value::Tuple(TupleData::from_data(fields)?);
+    let ty = TypeSignature::TupleType(tuple_type.clone());
+    if ty.admits(&StacksEpochId::latest(), &value)? {
+        Ok(value)
+    } else {
+        Err(unparsable(expr, &ty))
+    }
+}
+
+fn unparsable(expr: &PreSymbolicExpression, ty: &TypeSignature) -> Error {
+    use crate::vm::representations::PreSymbolicExpressionType::*;
+
+    let found = match &expr.pre_expr {
+        AtomValue(value) => value.to_string(),
+        Atom(name) => name.to_string(),
+        List(items) => match items.first().and_then(|first| first.match_atom()) {
+            Some(name) => format!("({} ...)", name),
+            None => "(...)".to_string(),
+        },
+        Tuple(_) => "{ ... }".to_string(),
+        other => format!("{:?}", other),
+    };
+    RuntimeErrorType::ParseError(format!(
+        "Expected a value of type {}, found `{}`",
+        ty, found
+    ))
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::ClarityVersion;
+
+    fn parse_type(input: &str) -> TypeSignature {
+        TypeSignature::from_string(input, ClarityVersion::Clarity2, StacksEpochId::latest())
+    }
+
+    #[test]
+    fn test_parse_value_for_type() {
+        assert_eq!(
+            parse_value_for_type("0x1234", &parse_type("(buff 2)")).unwrap(),
+            Value::buff_from(vec![0x12, 0x34]).unwrap()
+        );
+        assert_eq!(
+            parse_value_for_type("5", &TypeSignature::IntType).unwrap(),
+            Value::Int(5)
+        );
+        assert_eq!(
+            parse_value_for_type(" u5 ", &TypeSignature::UIntType).unwrap(),
+            Value::UInt(5)
+        );
+        assert_eq!(
+            parse_value_for_type("true", &TypeSignature::BoolType).unwrap(),
+            Value::Bool(true)
+        );
+
+        let principal = "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7";
+        let expected = Value::Principal(PrincipalData::parse(principal).unwrap());
+        assert_eq!(
+            parse_value_for_type(principal, &TypeSignature::PrincipalType).unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_value_for_type(&format!("'{}", principal), &TypeSignature::PrincipalType)
+                .unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_value_for_type(&format!("{}.foo", principal), &TypeSignature::PrincipalType)
+                .unwrap(),
+            Value::Principal(PrincipalData::parse(&format!("{}.foo", principal)).unwrap())
+        );
+
+        assert_eq!(
+            parse_value_for_type("\"hello\"", &parse_type("(string-ascii 10)")).unwrap(),
+            Value::string_ascii_from_bytes(b"hello".to_vec()).unwrap()
+        );
+        assert_eq!(
+            parse_value_for_type("(some 5)", &parse_type("(optional int)")).unwrap(),
+            Value::some(Value::Int(5)).unwrap()
+        );
+        assert_eq!(
+            parse_value_for_type("none", &parse_type("(optional int)")).unwrap(),
+            Value::none()
+        );
+        assert_eq!(
+            parse_value_for_type("(err u1)", &parse_type("(response bool uint)")).unwrap(),
+            Value::error(Value::UInt(1)).unwrap()
+        );
+        assert_eq!(
+            parse_value_for_type("(list 1 2 3)", &parse_type("(list 5 int)")).unwrap(),
+            Value::list_from(vec![Value::Int(1), Value::Int(2), Value::Int(3)]).unwrap()
+        );
+        assert_eq!(
+            parse_value_for_type(
+                "{ a: (some u1), b: false }",
+                &parse_type("{ a: (optional uint), b: bool }")
+            )
+            .unwrap(),
+            parse_value_for_type(
+                "(tuple (b false) (a (some u1)))",
+                &parse_type("{ a: (optional uint), b: bool }")
+            )
+            .unwrap()
+        );
+
+        match parse_value_for_type("(list 1 2 3)", &parse_type("(list 2 int)")).unwrap_err() {
+            Error::Unchecked(CheckErrors::ConstructedListTooLarge) => {}
+            e => panic!("Unexpected error: {:?}", e),
+        }
+        match parse_value_for_type("\"hello world\"", &parse_type("(string-ascii 5)")).unwrap_err()
+        {
+            Error::Unchecked(CheckErrors::TypeValueError(..)) => {}
+            e => panic!("Unexpected error: {:?}", e),
+        }
+        assert!(matches!(
+            parse_value_for_type("(string-ascii 5)", &parse_type("(string-ascii 5)")),
+            Err(Error::Runtime(RuntimeErrorType::ParseError(_), _))
+        ));
+    }
+
+    #[test]
+    fn test_parse_value_for_type_mismatch() {
+        match parse_value_for_type("abc", &TypeSignature::IntType).unwrap_err() {
+            Error::Runtime(RuntimeErrorType::ParseError(msg), _) => {
+                assert_eq!(msg, "Expected a value of type int, found `abc`")
+            }
+            e => panic!("Unexpected error: {:?}", e),
+        }
+        match parse_value_for_type("u5", &TypeSignature::IntType).unwrap_err() {
+            Error::Unchecked(CheckErrors::TypeValueError(expected, value)) => {
+                assert_eq!(expected, TypeSignature::IntType);
+                assert_eq!(value, Value::UInt(5));
+            }
+            e => panic!("Unexpected error: {:?}", e),
+        }
+        assert!(parse_value_for_type("(some u5)", &parse_type("(optional int)")).is_err());
+        assert!(parse_value_for_type("(ok 5)", &parse_type("(optional int)")).is_err());
+        assert!(parse_value_for_type("5 6", &TypeSignature::IntType).is_err());
+        assert!(parse_value_for_type("not-a-principal", &TypeSignature::PrincipalType).is_err());
+        assert!(parse_value_for_type("{ c: 1 }", &parse_type("{ a: int }")).is_err());
+    }
+}
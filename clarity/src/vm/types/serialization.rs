@@ -36,7 +36,7 @@ use crate::vm::types::{
     StringUTF8Length, TupleData, TypeSignature, Value, BOUND_VALUE_SERIALIZATION_BYTES,
     MAX_VALUE_SIZE,
 };
-use stacks_common::util::hash::{hex_bytes, to_hex};
+use stacks_common::util::hash::{hex_bytes, to_hex, Sha512Trunc256Sum};
 use stacks_common::util::retry::BoundReader;
 
 use crate::codec::{Error as codec_error, StacksMessageCodec};
@@ -57,6 +57,13 @@ pub enum SerializationError {
     LeftoverBytesInDeserialization,
 }
 
+/// Deepest nesting of lists, tuples, optionals, and responses that deserialization will
+///  descend into before failing with `TypeSignatureTooDeep`. This bounds the recursion
+///  on untrusted input independently of any expected type. The total number of elements
+///  is bounded separately: every element takes at least one byte, and reads are capped
+///  at `BOUND_VALUE_SERIALIZATION_BYTES`.
+pub const MAX_DESERIALIZATION_DEPTH: u8 = 16;
+
 lazy_static! {
     pub static ref NONE_SERIALIZATION_LEN: u64 = Value::none().serialize_to_vec().len() as u64;
 }
@@ -472,7 +479,7 @@ impl Value {
         use super::PrincipalData::*;
         use super::Value::*;
 
-        if depth >= 16 {
+        if depth >= MAX_DESERIALIZATION_DEPTH {
             return Err(CheckErrors::TypeSignatureTooDeep.into());
         }
 
@@ -941,6 +948,35 @@ impl std::hash::Hash for Value {
     }
 }
 
+impl Value {
+    /// Digest of this value's consensus serialization. Unlike the `Hash` impl, this
+    ///  does not depend on a (possibly randomly seeded) `Hasher`, so equal values
+    ///  produce the same digest across runs and processes.
+    pub fn canonical_hash(&self) -> Sha512Trunc256Sum {
+        let mut s = vec![];
+        self.consensus_serialize(&mut s)
+            .expect("FATAL: failed to serialize to vec");
+        Sha512Trunc256Sum::from_data(&s)
+    }
+
+    /// Compare two values by the byte-wise order of their consensus serializations.
+    ///  Note that this is not the numeric order for ints, whose serialization is two's
+    ///  complement. It is deliberately not an `Ord` impl: a contract principal and a
+    ///  callable contract share a serialization, so values that are not equal may
+    ///  compare as `Equal`, and this holds inside lists, tuples, optionals and
+    ///  responses as well.
+    pub fn canonical_cmp(&self, other: &Value) -> cmp::Ordering {
+        let mut self_bytes = vec![];
+        self.consensus_serialize(&mut self_bytes)
+            .expect("FATAL: failed to serialize to vec");
+        let mut other_bytes = vec![];
+        other
+            .consensus_serialize(&mut other_bytes)
+            .expect("FATAL: failed to serialize to vec");
+        self_bytes.cmp(&other_bytes)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use rstest::rstest;
@@ -953,9 +989,10 @@ mod tests {
     use crate::vm::types::TypeSignature::{BoolType, IntType};
 
     use super::super::*;
-    use super::SerializationError;
+    use super::{SerializationError, MAX_DESERIALIZATION_DEPTH};
     use crate::vm::ClarityVersion;
     use stacks_common::types::StacksEpochId;
+    use stacks_common::util::hash::{hex_bytes, Sha512Trunc256Sum};
 
     #[template]
     #[rstest]
@@ -1216,6 +1253,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_serialized_size() {
+        let values = [
+            Value::Int(-1),
+            Value::buff_from(vec![0xde, 0xad, 0xbe, 0xef]).unwrap(),
+            Value::some(Value::some(Value::none()).unwrap()).unwrap(),
+            Value::from(
+                TupleData::from_data(vec![
+                    ("a".into(), Value::UInt(1)),
+                    ("bc".into(), Value::okay(Value::Bool(false)).unwrap()),
+                ])
+                .unwrap(),
+            ),
+        ];
+        let expected_sizes = [17, 9, 3, 29];
+
+        for (value, expected) in values.iter().zip(expected_sizes.iter()) {
+            let mut bytes = vec![];
+            value.serialize_write(&mut bytes).unwrap();
+            assert_eq!(value.serialized_size() as usize, bytes.len());
+            assert_eq!(value.serialized_size(), *expected);
+        }
+    }
+
     #[test]
     fn test_tuples() {
         let t_1 = Value::from(
@@ -1383,6 +1444,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_deserialize_depth_limit() {
+        let nested = |depth: u8| {
+            let mut value = Value::Int(1);
+            for _ in 0..depth {
+                value = Value::some(value).unwrap();
+            }
+            value
+        };
+
+        // the innermost value of `nested(n)` is read at depth `n`
+        let deepest_allowed = nested(MAX_DESERIALIZATION_DEPTH - 1);
+        assert_eq!(deepest_allowed.depth(), MAX_DESERIALIZATION_DEPTH);
+        test_deser_ser(deepest_allowed);
+
+        let too_deep = nested(MAX_DESERIALIZATION_DEPTH);
+        assert_eq!(
+            Value::try_deserialize_hex_untyped(&too_deep.serialize()).unwrap_err(),
+            CheckErrors::TypeSignatureTooDeep.into()
+        );
+        assert_eq!(
+            Value::try_deserialize_hex(&too_deep.serialize(), &TypeSignature::type_of(&too_deep))
+                .unwrap_err(),
+            CheckErrors::TypeSignatureTooDeep.into()
+        );
+    }
+
     #[test]
     fn test_principals() {
         let issuer =
@@ -1399,4 +1487,86 @@ mod tests {
         test_bad_expectation(contract_p2, TypeSignature::BoolType);
         test_bad_expectation(standard_p, TypeSignature::BoolType);
     }
+
+    #[test]
+    fn test_canonical_hash_and_ordering() {
+        let t_0 = Value::from(
+            TupleData::from_data(vec![
+                ("a".into(), Value::Int(1)),
+                ("b".into(), Value::buff_from(vec![1, 2, 3]).unwrap()),
+            ])
+            .unwrap(),
+        );
+        let t_1 = Value::from(
+            TupleData::from_data(vec![
+                ("b".into(), Value::buff_from(vec![1, 2, 3]).unwrap()),
+                ("a".into(), Value::Int(1)),
+            ])
+            .unwrap(),
+        );
+        let t_2 = Value::from(
+            TupleData::from_data(vec![
+                ("a".into(), Value::Int(2)),
+                ("b".into(), Value::buff_from(vec![1, 2, 3]).unwrap()),
+            ])
+            .unwrap(),
+        );
+
+        // independently-constructed equal values hash and order identically
+        assert_eq!(t_0.canonical_hash(), t_1.canonical_hash());
+        assert_eq!(t_0.canonical_cmp(&t_1), std::cmp::Ordering::Equal);
+
+        // unequal values differ
+        assert_ne!(t_0.canonical_hash(), t_2.canonical_hash());
+        assert_eq!(t_0.canonical_cmp(&t_2), std::cmp::Ordering::Less);
+        assert_ne!(
+            Value::Int(1).canonical_hash(),
+            Value::UInt(1).canonical_hash()
+        );
+
+        // the digest is over the consensus serialization
+        assert_eq!(
+            Value::Int(1).canonical_hash(),
+            Sha512Trunc256Sum::from_data(&hex_bytes(&Value::Int(1).serialize()).unwrap())
+        );
+
+        // values order by their serialized bytes
+        let mut values = vec![
+            Value::UInt(2),
+            Value::Int(-1),
+            Value::Bool(true),
+            Value::Int(1),
+            Value::none(),
+        ];
+        values.sort_by(Value::canonical_cmp);
+        assert_eq!(
+            values,
+            vec![
+                Value::Int(1),
+                Value::Int(-1),
+                Value::UInt(2),
+                Value::Bool(true),
+                Value::none(),
+            ]
+        );
+
+        // a contract principal and a callable contract share a serialization, at any depth
+        let contract_id = QualifiedContractIdentifier::local("foo").unwrap();
+        let principal = Value::Principal(PrincipalData::Contract(contract_id.clone()));
+        let callable = Value::CallableContract(CallableData {
+            contract_identifier: contract_id,
+            trait_identifier: None,
+        });
+        assert_ne!(principal, callable);
+        assert_eq!(
+            principal.canonical_cmp(&callable),
+            std::cmp::Ordering::Equal
+        );
+        assert_eq!(
+            Value::some(principal)
+                .unwrap()
+                .canonical_cmp(&Value::some(callable).unwrap()),
+            std::cmp::Ordering::Equal
+        );
+    }
 }
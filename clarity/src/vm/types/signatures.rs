@@ -229,7 +229,12 @@ pub enum FunctionType {
     Fixed(FixedFunction),
     // Functions where the single input is a union type, e.g., Buffer or Int
     UnionArgs(Vec<TypeSignature>, TypeSignature),
+    // Hash functions: the input is a buffer, uint or int, or since Clarity2 any other
+    //  serializable value. The type is the output type.
+    Hash(TypeSignature),
     ArithmeticVariadic,
+    // Like ArithmeticVariadic, but since Clarity2 also accepts a single list of ints or uints
+    ArithmeticVariadicOrList,
     ArithmeticUnary,
     ArithmeticBinary,
     ArithmeticComparison,
@@ -298,7 +303,9 @@ impl FunctionType {
                 let return_type = return_type.canonicalize(epoch);
                 FunctionType::UnionArgs(arg_types, return_type)
             }
+            FunctionType::Hash(return_type) => FunctionType::Hash(return_type.canonicalize(epoch)),
             FunctionType::ArithmeticVariadic => FunctionType::ArithmeticVariadic,
+            FunctionType::ArithmeticVariadicOrList => FunctionType::ArithmeticVariadicOrList,
             FunctionType::ArithmeticUnary => FunctionType::ArithmeticUnary,
             FunctionType::ArithmeticBinary => FunctionType::ArithmeticBinary,
             FunctionType::ArithmeticComparison => FunctionType::ArithmeticComparison,
@@ -2021,6 +2028,79 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_admits_type() {
+        let parse = |desc: &str| {
+            TypeSignature::from_string(desc, ClarityVersion::Clarity2, StacksEpochId::Epoch21)
+        };
+        let admitted = [
+            ("(buff 4)", parse("(buff 2)")),
+            ("(buff 4)", parse("(buff 4)")),
+            ("(list 3 (buff 4))", parse("(list 2 (buff 2))")),
+            ("(list 3 int)", TypeSignature::list_of(NoType, 0).unwrap()),
+            ("(optional (buff 4))", parse("(optional (buff 1))")),
+            (
+                "(optional (optional int))",
+                parse("(optional (optional int))"),
+            ),
+            ("(optional int)", TypeSignature::new_option(NoType).unwrap()),
+            (
+                "(optional (optional int))",
+                TypeSignature::new_option(TypeSignature::new_option(NoType).unwrap()).unwrap(),
+            ),
+            (
+                "(response (buff 4) int)",
+                TypeSignature::new_response(parse("(buff 2)"), NoType).unwrap(),
+            ),
+            (
+                "(response (buff 4) int)",
+                TypeSignature::new_response(NoType, IntType).unwrap(),
+            ),
+        ];
+        let rejected = [
+            ("(buff 2)", parse("(buff 4)")),
+            ("(list 2 int)", parse("(list 3 int)")),
+            ("(optional (buff 1))", parse("(optional (buff 4))")),
+            ("(optional int)", parse("(optional (optional int))")),
+            ("(optional int)", IntType),
+            ("(response int int)", parse("(optional int)")),
+            (
+                "(response int int)",
+                TypeSignature::new_response(UIntType, NoType).unwrap(),
+            ),
+        ];
+
+        for epoch in [StacksEpochId::Epoch2_05, StacksEpochId::Epoch21] {
+            for (container, other) in admitted.iter() {
+                assert!(
+                    parse(container).admits_type(&epoch, other).unwrap(),
+                    "{} should admit {}",
+                    container,
+                    other
+                );
+            }
+            for (container, other) in rejected.iter() {
+                assert!(
+                    !parse(container).admits_type(&epoch, other).unwrap(),
+                    "{} should not admit {}",
+                    container,
+                    other
+                );
+            }
+        }
+
+        assert_eq!(
+            NoType.admits_type(&StacksEpochId::Epoch21, &IntType),
+            Err(CouldNotDetermineType)
+        );
+        assert!(parse("(buff 4)")
+            .admits(
+                &StacksEpochId::Epoch21,
+                &Value::buff_from(vec![1, 2]).unwrap()
+            )
+            .unwrap());
+    }
+
     #[test]
     fn test_least_supertype() {
         let callables = [
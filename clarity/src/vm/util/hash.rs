@@ -0,0 +1,89 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Hashing of Clarity values, for hosts embedding the VM that need the digests the
+//!  hash natives compute without evaluating Clarity code.
+
+use stacks_common::codec::StacksMessageCodec;
+use stacks_common::util::hash::{Hash160, Keccak256Hash, Sha256Sum, Sha512Sum, Sha512Trunc256Sum};
+
+use crate::vm::errors::{CheckErrors, InterpreterResult as Result};
+use crate::vm::types::{SequenceData, TypeSignature, Value};
+use crate::vm::ClarityVersion;
+
+/// The hash functions of the Clarity hash natives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashFunction {
+    /// `hash160`
+    Hash160,
+    /// `sha256`
+    Sha256,
+    /// `sha512`
+    Sha512,
+    /// `sha512/256`
+    Sha512Trunc256,
+    /// `keccak256`
+    Keccak256,
+}
+
+impl HashFunction {
+    /// The digest of `bytes`.
+    pub fn digest(&self, bytes: &[u8]) -> Vec<u8> {
+        match self {
+            HashFunction::Hash160 => Hash160::from_data(bytes).as_bytes().to_vec(),
+            HashFunction::Sha256 => Sha256Sum::from_data(bytes).as_bytes().to_vec(),
+            HashFunction::Sha512 => Sha512Sum::from_data(bytes).as_bytes().to_vec(),
+            HashFunction::Sha512Trunc256 => Sha512Trunc256Sum::from_data(bytes).as_bytes().to_vec(),
+            HashFunction::Keccak256 => Keccak256Hash::from_data(bytes).as_bytes().to_vec(),
+        }
+    }
+}
+
+/// Hashes `value` with `function` exactly as the corresponding native does in
+///  `version`, returning the digest. Ints and uints are hashed as their 16-byte
+///  little-endian representation and buffers as their contents. From Clarity3, any
+///  other value is hashed over its consensus serialization; before that, it is a
+///  `CheckErrors::UnionTypeValueError`.
+pub fn hash_value(
+    function: HashFunction,
+    value: &Value,
+    version: &ClarityVersion,
+) -> Result<Vec<u8>> {
+    hash_value_streaming(value, version, |bytes| function.digest(bytes))
+}
+
+/// Hands the bytes that the hash natives consume for `input` to `hash_fn`. Buffers
+///  are hashed in place, without being copied out of the `Value`.
+fn hash_value_streaming<F, R>(input: &Value, version: &ClarityVersion, hash_fn: F) -> Result<R>
+where
+    F: FnOnce(&[u8]) -> R,
+{
+    match input {
+        Value::Int(value) => Ok(hash_fn(&value.to_le_bytes())),
+        Value::UInt(value) => Ok(hash_fn(&value.to_le_bytes())),
+        Value::Sequence(SequenceData::Buffer(value)) => Ok(hash_fn(&value.data)),
+        _ if *version >= ClarityVersion::Clarity3 => Ok(hash_fn(&input.serialize_to_vec())),
+        _ => Err(CheckErrors::UnionTypeValueError(
+            vec![
+                TypeSignature::IntType,
+                TypeSignature::UIntType,
+                TypeSignature::max_buffer(),
+            ],
+            input.clone(),
+        )
+        .into()),
+    }
+}
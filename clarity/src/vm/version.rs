@@ -7,6 +7,10 @@ use std::str::FromStr;
 pub enum ClarityVersion {
     Clarity1,
     Clarity2,
+    /// Not yet activated by any Stacks epoch. Changes to the behavior of analysis or
+    ///  evaluation that would alter the outcome of deployed Clarity2 contracts are
+    ///  introduced here.
+    Clarity3,
 }
 
 impl fmt::Display for ClarityVersion {
@@ -14,11 +18,14 @@ impl fmt::Display for ClarityVersion {
         match self {
             ClarityVersion::Clarity1 => write!(f, "Clarity 1"),
             ClarityVersion::Clarity2 => write!(f, "Clarity 2"),
+            ClarityVersion::Clarity3 => write!(f, "Clarity 3"),
         }
     }
 }
 
 impl ClarityVersion {
+    /// The newest version that a Stacks epoch activates. Later versions can only be
+    ///  selected explicitly.
     pub fn latest() -> ClarityVersion {
         ClarityVersion::Clarity2
     }
@@ -44,9 +51,12 @@ impl FromStr for ClarityVersion {
             Ok(ClarityVersion::Clarity1)
         } else if s == "clarity2" {
             Ok(ClarityVersion::Clarity2)
+        } else if s == "clarity3" {
+            Ok(ClarityVersion::Clarity3)
         } else {
             Err(RuntimeErrorType::ParseError(
-                "Invalid clarity version. Valid versions are: Clarity1, Clarity2.".to_string(),
+                "Invalid clarity version. Valid versions are: Clarity1, Clarity2, Clarity3."
+                    .to_string(),
             )
             .into())
         }
@@ -75,6 +75,10 @@ pub const BOOT_CODE_LOCKUP: &'static str = std::include_str!("lockup.clar");
 pub const BOOT_CODE_COSTS: &'static str = std::include_str!("costs.clar");
 pub const BOOT_CODE_COSTS_2: &'static str = std::include_str!("costs-2.clar");
 pub const BOOT_CODE_COSTS_3: &'static str = std::include_str!("costs-3.clar");
+/// `costs-3` extended with the cost functions of the natives introduced in Clarity3. It is
+///  meant to be the default cost contract of the epoch that activates Clarity3. No epoch
+///  deploys it yet, so nothing can charge its cost functions until then.
+pub const BOOT_CODE_COSTS_4: &'static str = std::include_str!("costs-4.clar");
 pub const BOOT_CODE_COSTS_2_TESTNET: &'static str = std::include_str!("costs-2-testnet.clar");
 pub const BOOT_CODE_COST_VOTING_MAINNET: &'static str = std::include_str!("cost-voting.clar");
 pub const BOOT_CODE_BNS: &'static str = std::include_str!("bns.clar");
@@ -89,6 +93,7 @@ const POX_2_BODY: &'static str = std::include_str!("pox-2.clar");
 pub const COSTS_1_NAME: &'static str = "costs";
 pub const COSTS_2_NAME: &'static str = "costs-2";
 pub const COSTS_3_NAME: &'static str = "costs-3";
+pub const COSTS_4_NAME: &'static str = "costs-4";
 
 pub mod docs;
 
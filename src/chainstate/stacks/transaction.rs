@@ -120,6 +120,7 @@ fn ClarityVersion_consensus_serialize<W: Write>(
     match *version {
         ClarityVersion::Clarity1 => write_next(fd, &1u8)?,
         ClarityVersion::Clarity2 => write_next(fd, &2u8)?,
+        ClarityVersion::Clarity3 => write_next(fd, &3u8)?,
     }
     Ok(())
 }
@@ -131,6 +132,7 @@ fn ClarityVersion_consensus_deserialize<R: Read>(
     match version_byte {
         1u8 => Ok(ClarityVersion::Clarity1),
         2u8 => Ok(ClarityVersion::Clarity2),
+        // Clarity3 is not accepted until a Stacks epoch activates it
         _ => Err(codec_error::DeserializeError(format!(
             "Unrecognized ClarityVersion byte {}",
             &version_byte
@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+use crate::chainstate::stacks::boot::{BOOT_CODE_COSTS_3, BOOT_CODE_COSTS_4};
 use crate::chainstate::stacks::index::storage::TrieFileStorage;
 use crate::chainstate::stacks::index::ClarityMarfTrieId;
 use crate::clarity_vm::clarity::ClarityInstance;
@@ -23,18 +24,23 @@ use crate::types::chainstate::BlockHeaderHash;
 use crate::types::chainstate::StacksBlockId;
 use crate::types::StacksEpochId;
 use crate::util_lib::boot::boot_code_id;
-use clarity::vm::ast::ASTRules;
+use clarity::vm::analysis::mem_type_check;
+use clarity::vm::ast::{build_ast, ASTRules};
 use clarity::vm::clarity::TransactionConnection;
 use clarity::vm::contexts::Environment;
-use clarity::vm::contexts::{AssetMap, AssetMapEntry, GlobalContext, OwnedEnvironment};
+use clarity::vm::contexts::{
+    AssetMap, AssetMapEntry, ContractContext, GlobalContext, LocalContext, OwnedEnvironment,
+};
 use clarity::vm::contracts::Contract;
 use clarity::vm::costs::cost_functions::ClarityCostFunction;
-use clarity::vm::costs::{ClarityCostFunctionReference, ExecutionCost, LimitedCostTracker};
+use clarity::vm::costs::{
+    ClarityCostFunctionReference, CostTracker, ExecutionCost, LimitedCostTracker,
+};
 use clarity::vm::database::ClarityDatabase;
 use clarity::vm::errors::{CheckErrors, Error, RuntimeErrorType};
 use clarity::vm::events::StacksTransactionEvent;
 use clarity::vm::functions::NativeFunctions;
-use clarity::vm::representations::SymbolicExpression;
+use clarity::vm::representations::{ClarityName, SymbolicExpression};
 use clarity::vm::test_util::{
     execute, execute_on_network, symbols_from_values, TEST_BURN_STATE_DB, TEST_BURN_STATE_DB_21,
     TEST_HEADER_DB,
@@ -42,10 +48,12 @@ use clarity::vm::test_util::{
 use clarity::vm::types::{
     AssetIdentifier, OptionalData, PrincipalData, QualifiedContractIdentifier, ResponseData, Value,
 };
-use clarity::vm::ClarityVersion;
+use clarity::vm::{eval, execute_v2, ClarityVersion, EvalHook, ExecutionResult};
 use stacks_common::util::hash::hex_bytes;
 
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::rc::Rc;
 
 use crate::clarity_vm::database::marf::MarfedKV;
 use clarity::vm::database::MemoryBackingStore;
@@ -67,6 +75,7 @@ pub fn get_simple_test(function: &NativeFunctions) -> &'static str {
         Subtract => "(- 1 1)",
         Multiply => "(* 1 1)",
         Divide => "(/ 1 1)",
+        MulDiv => "(mul-div 1 1 1)",
         CmpGeq => "(>= 2 1)",
         CmpLeq => "(<= 2 1)",
         CmpLess => "(< 2 1)",
@@ -85,6 +94,7 @@ pub fn get_simple_test(function: &NativeFunctions) -> &'static str {
         FetchVar => "(var-get var-foo)",
         SetVar => "(var-set var-foo 1)",
         Map => "(map not list-foo)",
+        MapTry => "(map-try! ok-foo list-bar)",
         Filter => "(filter not list-foo)",
         BuffToIntLe => "(buff-to-int-le 0x00000000000000000000000000000001)",
         BuffToUIntLe => "(buff-to-uint-le 0x00000000000000000000000000000001)",
@@ -113,6 +123,7 @@ pub fn get_simple_test(function: &NativeFunctions) -> &'static str {
         DeleteEntry => "(map-delete map-foo {a: 1})",
         TupleCons => "(tuple (a 1))",
         TupleGet => "(get a tuple-foo)",
+        TupleGetOptional => "(get? a tuple-foo)",
         TupleMerge => "(merge {a: 1, b: 2} {b: 1})",
         Begin => "(begin 1)",
         Hash160 => "(hash160 1)",
@@ -133,6 +144,7 @@ pub fn get_simple_test(function: &NativeFunctions) -> &'static str {
         ConsError => "(err 1)",
         ConsSome => "(some 1)",
         DefaultTo => "(default-to 1 none)",
+        UnwrapOrElse => "(unwrap-or-else none fallback-foo)",
         Asserts => "(asserts! true (err 1))",
         UnwrapRet => "(unwrap! (ok 1) (err 1))",
         UnwrapErrRet => "(unwrap-err! (err 1) (ok 1))",
@@ -162,16 +174,163 @@ pub fn get_simple_test(function: &NativeFunctions) -> &'static str {
         BitwiseAnd => "(bit-and 2 3)",
         BitwiseOr => "(bit-or 2 3)",
         BitwiseNot => "(bit-not 3)",
+        BitCount => "(bit-count u7)",
+        LeadingZeros => "(leading-zeros u1)",
         BitwiseLShift => "(bit-shift-left 2 u1)",
         BitwiseRShift => "(bit-shift-right 2 u1)",
         BitwiseXor2 => "(bit-xor 1 2)",
         Slice => "(slice? str-foo u1 u1)",
         ToConsensusBuff => "(to-consensus-buff? u1)",
+        ToAscii => "(to-ascii? 0x61)",
+        StringToBuff => r#"(string-to-buff "a")"#,
         FromConsensusBuff => "(from-consensus-buff? bool 0x03)",
         ReplaceAt => "(replace-at? list-bar u0 5)",
+        Sort => "(sort list-bar)",
+        // debug-only: it aborts outside debug mode, which these environments never enable
+        AssertEq => "(if false (assert-eq! 1 1) (ok true))",
     }
 }
 
+#[test]
+fn test_sha256_cost_matches_cost_contract() {
+    // costs-2 and costs-3: (define-read-only (cost_sha256 (n uint)) (runtime (linear n u1 u100))),
+    //  where `sha256` measures `n` as the serialized size of its argument.
+    let formula = |n: u64| n + 100;
+    let contract_id = QualifiedContractIdentifier::transient();
+
+    with_owned_env(StacksEpochId::Epoch21, false, |mut owned_env| {
+        let mut placeholder_context =
+            ContractContext::new(contract_id.clone(), ClarityVersion::Clarity2);
+        let mut env = owned_env.get_exec_environment(None, None, &mut placeholder_context);
+
+        let mut spent = vec![];
+        for len in [0u64, 32, 1024] {
+            // a buffer serializes as a type prefix, a 4-byte length, and its bytes
+            let input_size = 1 + 4 + len;
+            let charged = env
+                .global_context
+                .cost_track
+                .compute_cost(ClarityCostFunction::Sha256, &[input_size])
+                .unwrap();
+            assert_eq!(charged.runtime, formula(input_size));
+
+            let program = format!("(sha256 0x{})", "ab".repeat(len as usize));
+            let exprs = build_ast(
+                &contract_id,
+                &program,
+                &mut (),
+                ClarityVersion::Clarity2,
+                StacksEpochId::Epoch21,
+            )
+            .unwrap()
+            .expressions;
+            let before = env.global_context.cost_track.get_total().runtime;
+            eval(&exprs[0], &mut env, &LocalContext::new()).unwrap();
+            spent.push((
+                len,
+                env.global_context.cost_track.get_total().runtime - before,
+            ));
+        }
+
+        // evaluation charges the formula's per-byte cost and nothing else that
+        //  scales with the buffer
+        let (_, base) = spent[0];
+        for (len, runtime) in spent {
+            assert_eq!(runtime - base, formula(len) - formula(0));
+        }
+    });
+}
+
+// Records `(name, None)` on entry to each native and `(name, Some(cost))` on exit.
+struct NativeCallRecorder(Rc<RefCell<Vec<(String, Option<ExecutionCost>)>>>);
+
+impl EvalHook for NativeCallRecorder {
+    fn will_begin_eval(&mut self, _: &mut Environment, _: &LocalContext, _: &SymbolicExpression) {}
+
+    fn did_finish_eval(
+        &mut self,
+        _: &mut Environment,
+        _: &LocalContext,
+        _: &SymbolicExpression,
+        _: &Result<Value, Error>,
+    ) {
+    }
+
+    fn did_complete(&mut self, _: Result<&mut ExecutionResult, String>) {}
+
+    fn will_begin_native(&mut self, _: &mut Environment, name: &str) {
+        self.0.borrow_mut().push((name.to_string(), None));
+    }
+
+    fn did_finish_native(
+        &mut self,
+        _: &mut Environment,
+        name: &str,
+        cost: &ExecutionCost,
+        _: &Result<Value, Error>,
+    ) {
+        self.0
+            .borrow_mut()
+            .push((name.to_string(), Some(cost.clone())));
+    }
+}
+
+#[test]
+fn test_native_hooks_report_cost_deltas() {
+    let contract_id = QualifiedContractIdentifier::transient();
+    let exprs = build_ast(
+        &contract_id,
+        "(sha256 (+ 1 2))",
+        &mut (),
+        ClarityVersion::Clarity2,
+        StacksEpochId::Epoch21,
+    )
+    .unwrap()
+    .expressions;
+
+    let calls = Rc::new(RefCell::new(vec![]));
+    let evaluate = |with_hook: bool| {
+        with_owned_env(StacksEpochId::Epoch21, false, |mut owned_env| {
+            if with_hook {
+                // the environment only lives inside this closure, so its hooks must be 'static
+                owned_env.add_eval_hook(Box::leak(Box::new(NativeCallRecorder(calls.clone()))));
+            }
+            let mut placeholder_context =
+                ContractContext::new(contract_id.clone(), ClarityVersion::Clarity2);
+            let mut env = owned_env.get_exec_environment(None, None, &mut placeholder_context);
+            let result = eval(&exprs[0], &mut env, &LocalContext::new()).unwrap();
+            (result, env.global_context.cost_track.get_total())
+        })
+    };
+
+    // hooks observe, but do not change, the result or its cost
+    let (result, total_cost) = evaluate(true);
+    assert_eq!((result, total_cost.clone()), evaluate(false));
+
+    // natives are entered outermost first, before their arguments are evaluated,
+    //  and each reports the cost of its whole subtree
+    let calls = calls.borrow();
+    let names: Vec<_> = calls
+        .iter()
+        .map(|(name, cost)| (name.as_str(), cost.is_some()))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            ("sha256", false),
+            ("+", false),
+            ("+", true),
+            ("sha256", true)
+        ]
+    );
+    let add_cost = calls[2].1.as_ref().unwrap();
+    let sha256_cost = calls[3].1.as_ref().unwrap();
+    assert!(add_cost.runtime > 0);
+    assert!(sha256_cost.runtime > add_cost.runtime);
+    // the total additionally includes looking up `sha256` itself
+    assert!(sha256_cost.runtime < total_cost.runtime);
+}
+
 fn execute_transaction(
     env: &mut OwnedEnvironment,
     issuer: PrincipalData,
@@ -435,6 +594,58 @@ fn epoch205_eq_input_size_testnet() {
     epoch205_eq_input_size(false)
 }
 
+/// Build a `(list (tuple (a ...)) (tuple (a ...)))` expression nested `depth` levels
+///  around a small list of uints, so each level doubles the size of the value.
+fn nested_list_of_tuples(depth: usize) -> String {
+    let mut expr = "(list u1 u2 u3)".to_string();
+    for _ in 0..depth {
+        expr = format!("(list (tuple (a {})) (tuple (a {})))", expr, expr);
+    }
+    expr
+}
+
+// `is-eq` is charged on the serialized size of its arguments, so comparing two
+// deeply nested values must cost more than comparing two shallow ones, even though
+// the comparison itself is a single native call.
+fn epoch205_eq_cost_scales_with_nesting(use_mainnet: bool) {
+    for epoch in [StacksEpochId::Epoch2_05, StacksEpochId::Epoch21] {
+        let mut last_cost = 0;
+        for depth in 0..5 {
+            let value = nested_list_of_tuples(depth);
+            let with_eq = format!(
+                "(define-public (execute)
+                   (begin (is-eq {} {}) (ok 1)))",
+                value, value
+            );
+            let no_eq = format!(
+                "(define-public (execute)
+                   (begin {} {} (ok 1)))",
+                value, value
+            );
+            let eq_cost = exec_cost(&with_eq, use_mainnet, epoch).runtime
+                - exec_cost(&no_eq, use_mainnet, epoch).runtime;
+            assert!(
+                eq_cost > last_cost,
+                "is-eq at depth {} should cost more than at depth {} in {}",
+                depth,
+                depth.saturating_sub(1),
+                epoch
+            );
+            last_cost = eq_cost;
+        }
+    }
+}
+
+#[test]
+fn epoch205_eq_cost_scales_with_nesting_mainnet() {
+    epoch205_eq_cost_scales_with_nesting(true)
+}
+
+#[test]
+fn epoch205_eq_cost_scales_with_nesting_testnet() {
+    epoch205_eq_cost_scales_with_nesting(false)
+}
+
 // Test the `concat` changes in epoch 2.05. Using a dynamic input to the cost function will make the difference in runtime
 // cost larger when larger objects are fed into `concat` from the datastore.
 // Capture the cost of just the concat operation by measuring the cost of contracts that do everything but concat, and
@@ -848,6 +1059,8 @@ fn test_tracked_costs(
         (define-constant list-foo (list true))
         (define-constant list-bar (list 1))
         (define-constant str-foo \"foobar\")
+        (define-private (fallback-foo) 1)
+        (define-private (ok-foo (a int)) (ok a))
         (use-trait trait-1 .contract-trait.trait-1)
         (define-public (execute (contract <trait-1>)) (ok {}))",
         prog
@@ -998,7 +1211,11 @@ fn epoch_21_test_all(use_mainnet: bool) {
     );
 
     for f in NativeFunctions::ALL.iter() {
-        // Note: Include Clarity2 functions for Epoch21.
+        // Note: Include Clarity2 functions for Epoch21. Clarity3 functions are
+        //  charged by costs-4, which Epoch21 does not instantiate.
+        if f.get_version() > ClarityVersion::Clarity2 {
+            continue;
+        }
         let test = get_simple_test(f);
         let cost = test_tracked_costs(
             test,
@@ -1020,6 +1237,60 @@ fn epoch_21_test_all_testnet() {
     epoch_21_test_all(false)
 }
 
+// costs-4 must define every cost function, so that it can be the default cost contract
+//  of the epoch that activates Clarity3, and must charge exactly what costs-3 charges for
+//  every cost function costs-3 defines.
+#[test]
+fn test_costs_4_extends_costs_3() {
+    let cost_functions = |code: &str| {
+        let (_, analysis) = mem_type_check(code, ClarityVersion::Clarity2, StacksEpochId::Epoch21)
+            .expect("Failed to type check cost contract");
+        analysis.read_only_function_types
+    };
+    let costs_3 = cost_functions(BOOT_CODE_COSTS_3);
+    let costs_4 = cost_functions(BOOT_CODE_COSTS_4);
+    // the cost functions of the natives introduced in Clarity3
+    let added = [
+        ClarityCostFunction::Sort,
+        ClarityCostFunction::MulDiv,
+        ClarityCostFunction::ToAscii,
+        ClarityCostFunction::StringToBuff,
+        ClarityCostFunction::BitCount,
+        ClarityCostFunction::LeadingZeros,
+        ClarityCostFunction::UnwrapOrElse,
+        ClarityCostFunction::AssertEq,
+        ClarityCostFunction::MapTry,
+    ];
+
+    for f in ClarityCostFunction::ALL.iter() {
+        // never charged: the tracker panics rather than look it up
+        if f == &ClarityCostFunction::Unimplemented {
+            continue;
+        }
+        let name = ClarityName::try_from(f.get_name()).unwrap();
+        assert!(costs_4.contains_key(&name), "costs-4 must define {}", name);
+        if added.contains(f) {
+            assert!(!costs_3.contains_key(&name));
+        } else {
+            assert_eq!(costs_3.get(&name), costs_4.get(&name));
+        }
+    }
+
+    // cost_sort is (nlogn n u3 u185)
+    let cost = execute_v2(&format!("{}\n(cost_sort u8)", BOOT_CODE_COSTS_4))
+        .unwrap()
+        .unwrap()
+        .expect_tuple();
+    assert_eq!(cost.get("runtime").unwrap(), &Value::UInt(3 * 8 * 3 + 185));
+
+    // cost_mul_div is what `*` charges for its three arguments under costs-3
+    let cost = execute_v2(&format!("{}\n(cost_mul_div u3)", BOOT_CODE_COSTS_4))
+        .unwrap()
+        .unwrap()
+        .expect_tuple();
+    assert_eq!(cost.get("runtime").unwrap(), &Value::UInt(13 * 3 + 125));
+}
+
 fn test_cost_contract_short_circuits(use_mainnet: bool, clarity_version: ClarityVersion) {
     let marf_kv = MarfedKV::temporary();
     let chain_id = test_only_mainnet_to_chain_id(use_mainnet);
@@ -222,6 +222,71 @@ fn test_at_block_good(#[case] version: ClarityVersion, #[case] epoch: StacksEpoc
     );
 }
 
+#[apply(test_clarity_versions_type_checker)]
+fn test_at_block_read_only_calls(#[case] version: ClarityVersion, #[case] epoch: StacksEpochId) {
+    // contracts are initialized without analysis here, so this exercises the runtime
+    //  guard: any write reached from an at-block closure, however deeply nested,
+    //  aborts with WriteAttemptedInReadOnly.
+    fn initialize(owned_env: &mut OwnedEnvironment) {
+        let c = QualifiedContractIdentifier::local("contract").unwrap();
+        let contract =
+            "(define-data-var datum int 1)
+             (define-read-only (get-datum) (var-get datum))
+             (define-public (bump)
+               (begin
+                 (var-set datum (+ 1 (var-get datum)))
+                 (ok (var-get datum))))
+             (define-private (bump-via) (bump))
+             (define-public (read-at-block)
+               (ok (at-block 0x0101010101010101010101010101010101010101010101010101010101010101 (get-datum))))
+             (define-public (bump-at-block)
+               (at-block 0x0101010101010101010101010101010101010101010101010101010101010101 (bump)))
+             (define-public (bump-via-at-block)
+               (at-block 0x0101010101010101010101010101010101010101010101010101010101010101 (bump-via)))";
+
+        owned_env
+            .initialize_contract(c, &contract, None, ASTRules::PrecheckSize)
+            .unwrap();
+    }
+
+    fn call(owned_env: &mut OwnedEnvironment, to_exec: &str) -> Result<Value> {
+        let c = QualifiedContractIdentifier::local("contract").unwrap();
+        let p1 = execute(p1_str).expect_principal();
+        owned_env
+            .execute_transaction(p1, None, c, to_exec, &vec![])
+            .map(|(x, _, _)| x)
+    }
+
+    with_separate_forks_environment(
+        version,
+        epoch,
+        initialize,
+        |x| {
+            assert_eq!(
+                call(x, "read-at-block").unwrap(),
+                Value::okay(Value::Int(1)).unwrap()
+            );
+            for to_exec in ["bump-at-block", "bump-via-at-block"] {
+                assert_eq!(
+                    call(x, to_exec).unwrap_err(),
+                    CheckErrors::WriteAttemptedInReadOnly.into()
+                );
+            }
+
+            let mut placeholder_context =
+                ContractContext::new(QualifiedContractIdentifier::transient(), version);
+            let mut env = x.get_exec_environment(None, None, &mut placeholder_context);
+            let c = QualifiedContractIdentifier::local("contract").unwrap();
+            assert_eq!(
+                env.eval_read_only(&c, "(var-get datum)").unwrap(),
+                Value::Int(1)
+            );
+        },
+        |_x| {},
+        |_x| {},
+    );
+}
+
 #[apply(test_clarity_versions_type_checker)]
 fn test_at_block_missing_defines(#[case] version: ClarityVersion, #[case] epoch: StacksEpochId) {
     fn initialize_1(owned_env: &mut OwnedEnvironment) {
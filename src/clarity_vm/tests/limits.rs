@@ -0,0 +1,294 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::chainstate::stacks::boot::{BOOT_CODE_COSTS_3, BOOT_CODE_COST_VOTING_TESTNET};
+use crate::types::StacksEpochId;
+use crate::util_lib::boot::boot_code_id;
+use clarity::vm::ast::errors::ParseErrors;
+use clarity::vm::ast::{build_ast_with_rules, ASTRules};
+use clarity::vm::contexts::{ContractContext, GlobalContext, OwnedEnvironment};
+use clarity::vm::costs::{ExecutionCost, LimitedCostTracker};
+use clarity::vm::database::{ClarityDatabase, MemoryBackingStore, WriteSet};
+use clarity::vm::errors::{CheckErrors, Error, RuntimeErrorType};
+use clarity::vm::events::StacksTransactionEvent;
+use clarity::vm::types::QualifiedContractIdentifier;
+use clarity::vm::{eval_all, ClarityVersion, Value};
+use stacks_common::consts::CHAIN_ID_TESTNET;
+
+/// The number of events a program run by `execute_with_limits` may emit.
+pub const EXECUTE_WITH_LIMITS_MAX_EVENTS: usize = 1024;
+
+/// Sets `epoch` on `database` and deploys the testnet boot contracts that a
+///  `LimitedCostTracker` loads its cost functions from.
+pub fn boot_cost_contracts(
+    mut database: ClarityDatabase,
+    epoch: StacksEpochId,
+) -> Result<ClarityDatabase, Error> {
+    database.begin();
+    database.set_clarity_epoch_version(epoch);
+    database.commit();
+    let mut boot_env = OwnedEnvironment::new_free(false, CHAIN_ID_TESTNET, database, epoch);
+    for (name, code) in [
+        ("cost-voting", BOOT_CODE_COST_VOTING_TESTNET.as_str()),
+        ("costs-3", BOOT_CODE_COSTS_3),
+    ] {
+        boot_env.initialize_versioned_contract(
+            boot_code_id(name, false),
+            ClarityVersion::default_for_epoch(epoch),
+            code,
+            None,
+            ASTRules::PrecheckSize,
+        )?;
+    }
+    let (database, _) = boot_env
+        .destruct()
+        .expect("FAIL: boot environment is not at the top level");
+    Ok(database)
+}
+
+/// Runs `program` in Clarity2, Epoch21, testnet, over a fresh memory store, with every
+///  resource bounded: costs are charged by the boot cost contract against `cost_budget`,
+///  memory against `memory_limit`, the call stack is at most `stack_depth` deep (and never
+///  deeper than `MAX_CALL_STACK_DEPTH`), and at most `EXECUTE_WITH_LIMITS_MAX_EVENTS`
+///  events may be emitted. No wall-clock limit is set, so the result only depends on the
+///  arguments. Meant for fuzz and differential testing: a program exceeding a limit, or
+///  failing to parse or evaluate, comes back as an `Error`.
+pub fn execute_with_limits(
+    program: &str,
+    cost_budget: ExecutionCost,
+    memory_limit: u64,
+    stack_depth: usize,
+) -> Result<Option<Value>, Error> {
+    let epoch = StacksEpochId::Epoch21;
+    let clarity_version = ClarityVersion::Clarity2;
+    let mut marf = MemoryBackingStore::new();
+    let mut database = boot_cost_contracts(marf.as_clarity_db(), epoch)?;
+
+    let mut cost_track =
+        LimitedCostTracker::new(false, CHAIN_ID_TESTNET, cost_budget, &mut database, epoch)?;
+    cost_track.set_memory_limit(memory_limit);
+
+    let contract_id = QualifiedContractIdentifier::transient();
+    let mut contract_context = ContractContext::new(contract_id.clone(), clarity_version);
+    let mut global_context =
+        GlobalContext::new(false, CHAIN_ID_TESTNET, database, cost_track, epoch);
+    global_context.set_max_events(Some(EXECUTE_WITH_LIMITS_MAX_EVENTS));
+    global_context.set_max_call_stack_depth(Some(stack_depth));
+    global_context.execute(|g| {
+        let parsed = build_ast_with_rules(
+            &contract_id,
+            program,
+            &mut g.cost_track,
+            clarity_version,
+            epoch,
+            ASTRules::PrecheckSize,
+        )?
+        .expressions;
+        eval_all(&parsed, &mut contract_context, g, None)
+    })
+}
+
+/// Everything observable about a program run by `run_and_capture`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutionReport {
+    /// The value of the last expression of the program
+    pub result: Option<Value>,
+    /// The total cost charged by the boot cost contract
+    pub cost: ExecutionCost,
+    /// The most memory the cost tracker had in use at once
+    pub memory_high_water: u64,
+    /// The events emitted, in order
+    pub events: Vec<StacksTransactionEvent>,
+    /// The final value of every key the program wrote
+    pub write_set: WriteSet,
+}
+
+/// Runs `program` in Clarity2, Epoch21, testnet, over a fresh memory store, with costs
+///  charged by the boot cost contract against an unlimited budget, and reports its result
+///  along with everything it consumed and produced. Meant for snapshotting: two builds of
+///  the VM that disagree on any field of the report for the same program have changed
+///  behavior.
+pub fn run_and_capture(program: &str) -> Result<ExecutionReport, Error> {
+    let epoch = StacksEpochId::Epoch21;
+    let clarity_version = ClarityVersion::Clarity2;
+    let mut marf = MemoryBackingStore::new();
+    let mut database = boot_cost_contracts(marf.as_clarity_db(), epoch)?;
+
+    let cost_track = LimitedCostTracker::new(
+        false,
+        CHAIN_ID_TESTNET,
+        ExecutionCost::max_value(),
+        &mut database,
+        epoch,
+    )?;
+
+    let contract_id = QualifiedContractIdentifier::transient();
+    let mut contract_context = ContractContext::new(contract_id.clone(), clarity_version);
+    let mut global_context =
+        GlobalContext::new(false, CHAIN_ID_TESTNET, database, cost_track, epoch);
+    global_context.database.record_write_sets();
+    global_context.begin();
+    let result = build_ast_with_rules(
+        &contract_id,
+        program,
+        &mut global_context.cost_track,
+        clarity_version,
+        epoch,
+        ASTRules::PrecheckSize,
+    )
+    .map_err(Error::from)
+    .and_then(|parsed| {
+        eval_all(
+            &parsed.expressions,
+            &mut contract_context,
+            &mut global_context,
+            None,
+        )
+    });
+    let result = match result {
+        Ok(result) => result,
+        Err(e) => {
+            global_context.roll_back();
+            return Err(e);
+        }
+    };
+    let (_, event_batch) = global_context.commit()?;
+
+    Ok(ExecutionReport {
+        result,
+        cost: global_context.cost_track.get_total(),
+        memory_high_water: global_context.cost_track.get_memory_high_water(),
+        events: event_batch.map(|batch| batch.events).unwrap_or_default(),
+        write_set: global_context
+            .database
+            .last_write_set()
+            .cloned()
+            .unwrap_or_default(),
+    })
+}
+
+#[test]
+fn test_execute_with_limits() {
+    let execute = |program: &str, stack_depth: usize| {
+        execute_with_limits(program, ExecutionCost::max_value(), 1_000_000, stack_depth)
+    };
+
+    assert_eq!(Ok(Some(Value::Int(3))), execute("(+ 1 2)", 64));
+
+    let deep_parens = format!("{}1{}", "(+ 1 ".repeat(1000), ")".repeat(1000));
+    let nested_calls = format!("{}1{}", "(+ 1 ".repeat(10), ")".repeat(10));
+    let adversarial = [
+        (deep_parens.as_str(), 64),
+        (nested_calls.as_str(), 5),
+        ("(pow 2 1000)", 64),
+        ("(pow u2 u128)", 64),
+        ("(define-private (f) (f)) (f)", 64),
+    ];
+    for (program, stack_depth) in adversarial.iter() {
+        let result = execute(program, *stack_depth);
+        assert!(result.is_err(), "{} returned {:?}", program, result);
+        // the result only depends on the input
+        assert_eq!(result, execute(program, *stack_depth));
+    }
+
+    // the names in a reported cycle come in hash-set order, so only the error kind is stable
+    match execute("(define-private (f) (g)) (define-private (g) (f)) (f)", 64) {
+        Err(Error::Runtime(RuntimeErrorType::ASTError(e), _))
+            if matches!(e.err, ParseErrors::CircularReference(..)) => {}
+        other => panic!("Expected a circular reference error, got {:?}", other),
+    }
+
+    // the stack depth limit only applies below the consensus limit
+    assert_eq!(
+        Error::from(RuntimeErrorType::MaxStackDepthReached),
+        execute(&nested_calls, 5).unwrap_err()
+    );
+    assert_eq!(Ok(Some(Value::Int(11))), execute(&nested_calls, 1000));
+
+    // costs and memory are charged against the given budgets
+    let program = "(fold + (list 1 2 3 4 5 6 7 8) 0)";
+    assert_eq!(Ok(Some(Value::Int(36))), execute(program, 64));
+    // parsing is charged as well
+    match execute_with_limits(program, ExecutionCost::zero(), 1_000_000, 64) {
+        Err(Error::Runtime(RuntimeErrorType::ASTError(e), _))
+            if matches!(e.err, ParseErrors::CostBalanceExceeded(..)) => {}
+        other => panic!("Expected a cost error, got {:?}", other),
+    }
+    let program = format!("(let ((a 0x{})) (len a))", "00".repeat(1000));
+    assert_eq!(Ok(Some(Value::UInt(1000))), execute(&program, 64));
+    match execute_with_limits(&program, ExecutionCost::max_value(), 100, 64) {
+        Err(Error::Unchecked(CheckErrors::MemoryBalanceExceeded(..))) => {}
+        other => panic!("Expected a memory error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_run_and_capture() {
+    let report = run_and_capture("(pow 2 16)").unwrap();
+    assert_eq!(Some(Value::Int(65536)), report.result);
+    assert_eq!(
+        ExecutionCost {
+            write_length: 0,
+            write_count: 0,
+            read_length: 0,
+            read_count: 0,
+            runtime: 582,
+        },
+        report.cost
+    );
+    assert!(report.events.is_empty());
+    assert!(report.write_set.is_empty());
+    // the report only depends on the program
+    assert_eq!(report, run_and_capture("(pow 2 16)").unwrap());
+}
+
+#[test]
+fn test_host_function_costs() {
+    let epoch = StacksEpochId::Epoch21;
+    let mut marf = MemoryBackingStore::new();
+    let mut database = boot_cost_contracts(marf.as_clarity_db(), epoch).unwrap();
+    let budget = ExecutionCost {
+        runtime: 1_000_000,
+        ..ExecutionCost::max_value()
+    };
+    let cost_track =
+        LimitedCostTracker::new(false, CHAIN_ID_TESTNET, budget, &mut database, epoch).unwrap();
+    let mut env =
+        OwnedEnvironment::new_cost_limited(false, CHAIN_ID_TESTNET, database, cost_track, epoch);
+
+    let per_call = |runtime| ExecutionCost {
+        runtime,
+        ..ExecutionCost::zero()
+    };
+    for (name, runtime) in [("free", 0), ("paid", 1000), ("dear", 2_000_000)] {
+        env.register_host_function(name, per_call(runtime), |_args, _env| Ok(Value::Int(0)))
+            .unwrap();
+    }
+
+    // each call is charged its registered cost on top of the cost of evaluating it
+    let before = env.get_cost_total();
+    env.eval_raw("(free)").unwrap();
+    let free = env.get_cost_total().runtime - before.runtime;
+    let before = env.get_cost_total();
+    env.eval_raw("(paid)").unwrap();
+    let paid = env.get_cost_total().runtime - before.runtime;
+    assert_eq!(1000, paid - free);
+
+    match env.eval_raw("(dear)") {
+        Err(Error::Unchecked(CheckErrors::CostBalanceExceeded(..))) => {}
+        other => panic!("Expected a cost error, got {:?}", other),
+    }
+}
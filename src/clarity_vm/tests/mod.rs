@@ -22,4 +22,5 @@ pub mod epoch_switch;
 pub mod events;
 pub mod forking;
 pub mod large_contract;
+pub mod limits;
 pub mod simple_tests;